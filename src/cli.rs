@@ -0,0 +1,585 @@
+/// Headless run of collection from command line, for CI pipelines.
+/// Same requests, variables, captures, assertions and scripts as in UI, without window
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    settings::Settings,
+    states::{main_page::entity::Entity, runner::RunReport, Event, States},
+};
+
+/// Exit code when some request failed
+const EXIT_FAILED: i32 = 1;
+/// Exit code for wrong arguments or settings
+const EXIT_ERROR: i32 = 2;
+
+pub const USAGE: &str = "Usage: frapi run --collection <name|id> [options]
+
+Options:
+  --settings <path>         Settings file, default one when omitted
+  --collection <name|id>    Collection to run
+  --environment <name|id>   Environment to use, active one when omitted
+  --request <name|id>       Run only this request, could be repeated
+  --delay <ms>              Delay between requests
+  --bail                    Stop on first failed request
+  --timeout <s>             Fail run when it takes longer
+  --junit <path>            Write JUnit XML report
+  --json <path>             Write JSON report
+  --verbose                 Print all events, not only errors
+
+Vault with master password unlocked by FRAPI_VAULT_PASSWORD variable.
+Exit code: 0 - all passed, 1 - some failed, 2 - wrong arguments or settings";
+
+/// Command line options of run
+#[derive(Debug, Default)]
+struct Options {
+    settings: Option<PathBuf>,
+    collection: String,
+    environment: Option<String>,
+    requests: Vec<String>,
+    delay: String,
+    bail: bool,
+    timeout: Option<Duration>,
+    junit: Option<PathBuf>,
+    json: Option<PathBuf>,
+    verbose: bool,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .cloned()
+                    .ok_or(format!("Missing value of {arg}"))
+            };
+            match arg.as_str() {
+                "--settings" => options.settings = Some(PathBuf::from(value()?)),
+                "--collection" => options.collection = value()?,
+                "--environment" => options.environment = Some(value()?),
+                "--request" => options.requests.push(value()?),
+                "--delay" => {
+                    let delay = value()?;
+                    delay
+                        .parse::<u64>()
+                        .map_err(|_| format!("Invalid delay {delay}"))?;
+                    options.delay = delay;
+                }
+                "--bail" => options.bail = true,
+                "--timeout" => {
+                    let timeout = value()?;
+                    let seconds = timeout
+                        .parse::<u64>()
+                        .map_err(|_| format!("Invalid timeout {timeout}"))?;
+                    options.timeout = Some(Duration::from_secs(seconds));
+                }
+                "--junit" => options.junit = Some(PathBuf::from(value()?)),
+                "--json" => options.json = Some(PathBuf::from(value()?)),
+                "--verbose" => options.verbose = true,
+                other => return Err(format!("Unknown argument {other}")),
+            }
+        }
+
+        if options.collection.is_empty() {
+            return Err("Collection is not set".into());
+        }
+        Ok(options)
+    }
+}
+
+/// Run collection by arguments after `run`. Returns process exit code
+pub async fn run(args: &[String]) -> i32 {
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{USAGE}");
+        return 0;
+    }
+
+    let options = match Options::parse(args) {
+        Ok(val) => val,
+        Err(err) => {
+            eprintln!("{err}\n\n{USAGE}");
+            return EXIT_ERROR;
+        }
+    };
+
+    let mut states = match prepare(&options) {
+        Ok(val) => val,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            return EXIT_ERROR;
+        }
+    };
+
+    let environment = states.environments.active_name();
+    println!(
+        "Running collection {} with environment {environment}",
+        states.runner.collection_name
+    );
+
+    states.runner.start(&states.events);
+    let started = Instant::now();
+    let mut printed_events = 0;
+    let mut printed_reports = 0;
+    let mut is_timed_out = false;
+
+    while states.runner.is_running() {
+        states.update();
+
+        printed_events = print_events(&states, printed_events, options.verbose);
+        for report in &states.runner.report[printed_reports..] {
+            print_report(report);
+        }
+        printed_reports = states.runner.report.len();
+
+        if options
+            .timeout
+            .is_some_and(|timeout| started.elapsed() > timeout)
+        {
+            states.runner.stop();
+            is_timed_out = true;
+            eprintln!("Error: run timed out");
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    print_events(&states, printed_events, options.verbose);
+    for report in &states.runner.report[printed_reports..] {
+        print_report(report);
+    }
+
+    let (passed, total) = states.runner.passed();
+    println!(
+        "\nPassed {passed} of {total} in {} ms",
+        started.elapsed().as_millis()
+    );
+
+    let collection = states.runner.collection_name.clone();
+    let mut code = if passed == total && !is_timed_out {
+        0
+    } else {
+        EXIT_FAILED
+    };
+    if let Some(path) = &options.junit {
+        let xml = junit_report(&collection, &states.runner.report, started.elapsed());
+        if let Err(err) = fs::write(path, xml) {
+            eprintln!(
+                "Error: could not write JUnit report {}. {err}",
+                path.display()
+            );
+            code = EXIT_ERROR;
+        }
+    }
+    if let Some(path) = &options.json {
+        let report = json_report(
+            &collection,
+            &environment,
+            &states.runner.report,
+            started.elapsed(),
+        );
+        let text = serde_json::to_string_pretty(&report).unwrap_or_default();
+        if let Err(err) = fs::write(path, text) {
+            eprintln!(
+                "Error: could not write JSON report {}. {err}",
+                path.display()
+            );
+            code = EXIT_ERROR;
+        }
+    }
+
+    code
+}
+
+/// States with selected environment and runner set up for collection
+fn prepare(options: &Options) -> Result<States, String> {
+    let settings = Settings::dyn_load(options.settings.clone())?;
+    let mut states = States::from(&settings);
+
+    if let Ok(password) = std::env::var("FRAPI_VAULT_PASSWORD") {
+        if states.vault.is_created() && !states.vault.is_unlocked() {
+            states.vault.unlock(&password)?;
+            states.environments.fill_secrets(&states.vault.secrets()?);
+        }
+    }
+
+    if let Some(name) = &options.environment {
+        let id = states
+            .environments
+            .items
+            .iter()
+            .find(|environment| &environment.id == name || &environment.name == name)
+            .map(|environment| environment.id.clone())
+            .ok_or(format!("Environment {name} not found"))?;
+        states.environments.select(Some(id));
+    }
+
+    let collection = states
+        .main_page
+        .entities
+        .iter()
+        .find_map(|entity| match entity {
+            Entity::COLLECTION(collection)
+                if collection.id == options.collection
+                    || collection.draft.name == options.collection =>
+            {
+                Some(collection)
+            }
+            _ => None,
+        })
+        .ok_or(format!("Collection {} not found", options.collection))?;
+    states.runner.open(collection);
+
+    if !options.requests.is_empty() {
+        for name in &options.requests {
+            let step = collection
+                .requests
+                .iter()
+                .position(|request| &request.id == name || &request.draft.name == name)
+                .ok_or(format!("Request {name} not found"))?;
+            if !states.runner.steps[step].is_runnable {
+                return Err(format!(
+                    "Request {name} is not HTTP, only HTTP requests run"
+                ));
+            }
+        }
+        for (step, request) in states
+            .runner
+            .steps
+            .iter_mut()
+            .zip(collection.requests.iter())
+        {
+            step.is_selected = step.is_runnable
+                && options
+                    .requests
+                    .iter()
+                    .any(|name| name == &request.id || name == &request.draft.name);
+        }
+    }
+    // Empty run must not pass silently in pipeline
+    if !states.runner.steps.iter().any(|step| step.is_selected) {
+        return Err(format!(
+            "Collection {} has no HTTP requests",
+            options.collection
+        ));
+    }
+    states.runner.delay = options.delay.clone();
+    states.runner.stop_on_failure = options.bail;
+
+    Ok(states)
+}
+
+/// Print events logged since last call. Returns count of events
+fn print_events(states: &States, from: usize, verbose: bool) -> usize {
+    let events = states.events.lock().unwrap();
+    for i in from..events.len() {
+        match events.get(i) {
+            Event::Error(data) => eprintln!("  error: {}", data.message),
+            Event::Warning(data) if verbose => eprintln!("  warning: {}", data.message),
+            Event::Info(data) if verbose => println!("  {}", data.message),
+            _ => {}
+        }
+    }
+    events.len()
+}
+
+fn print_report(report: &RunReport) {
+    println!(
+        "{} {} {} {}",
+        if report.is_passed { "PASS" } else { "FAIL" },
+        report.name,
+        if report.code > 0 {
+            report.code.to_string()
+        } else {
+            "-".into()
+        },
+        match report.duration {
+            Some(duration) => format!("{} ms", duration.as_millis()),
+            None => "-".into(),
+        }
+    );
+    if let Some(error) = &report.error {
+        println!("     {error}");
+    }
+    for result in &report.assertions {
+        println!(
+            "     {} {} {}",
+            if result.is_passed { "✔" } else { "✘" },
+            result.name,
+            result.message
+        );
+    }
+}
+
+/// Report in JUnit XML: collection as test suite, request as test case
+fn junit_report(collection: &str, reports: &[RunReport], elapsed: Duration) -> String {
+    let failures = reports.iter().filter(|report| !report.is_passed).count();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"frapi\" tests=\"{}\" failures=\"{failures}\" time=\"{:.3}\">\n",
+        reports.len(),
+        elapsed.as_secs_f64()
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{failures}\" time=\"{:.3}\">\n",
+        escape(collection),
+        reports.len(),
+        elapsed.as_secs_f64()
+    ));
+
+    for report in reports {
+        xml.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+            escape(&report.name),
+            escape(collection),
+            report.duration.unwrap_or_default().as_secs_f64()
+        ));
+        if report.is_passed {
+            xml.push_str("/>\n");
+            continue;
+        }
+
+        let message = match &report.error {
+            Some(error) => error.clone(),
+            None if report.assertions.is_empty() => format!("Status {}", report.code),
+            None => format!(
+                "{} of {} assertions failed",
+                report
+                    .assertions
+                    .iter()
+                    .filter(|result| !result.is_passed)
+                    .count(),
+                report.assertions.len()
+            ),
+        };
+        let details = report
+            .assertions
+            .iter()
+            .map(|result| {
+                format!(
+                    "{} {} {}",
+                    if result.is_passed { "PASS" } else { "FAIL" },
+                    result.name,
+                    result.message
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+        xml.push_str(&format!(
+            ">\n      <failure message=\"{}\">{}</failure>\n    </testcase>\n",
+            escape(&message),
+            escape(&details)
+        ));
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn json_report(
+    collection: &str,
+    environment: &str,
+    reports: &[RunReport],
+    elapsed: Duration,
+) -> Value {
+    let passed = reports.iter().filter(|report| report.is_passed).count();
+    json!({
+        "collection": collection,
+        "environment": environment,
+        "passed": passed,
+        "failed": reports.len() - passed,
+        "duration_ms": elapsed.as_millis() as u64,
+        "requests": reports
+            .iter()
+            .map(|report| json!({
+                "name": report.name,
+                "status": report.code,
+                "duration_ms": report.duration.map(|duration| duration.as_millis() as u64),
+                "passed": report.is_passed,
+                "error": report.error,
+                "assertions": report
+                    .assertions
+                    .iter()
+                    .map(|result| json!({
+                        "name": result.name,
+                        "passed": result.is_passed,
+                        "message": result.message,
+                    }))
+                    .collect::<Vec<Value>>(),
+            }))
+            .collect::<Vec<Value>>(),
+    })
+}
+
+/// Text escaped for XML attribute or content
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::states::main_page::response::AssertionResult;
+
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    fn report(name: &str, is_passed: bool) -> RunReport {
+        RunReport {
+            request_id: name.into(),
+            name: name.into(),
+            code: 200,
+            duration: Some(Duration::from_millis(1500)),
+            assertions: vec![],
+            is_passed,
+            error: None,
+            response_idx: Some(0),
+        }
+    }
+
+    #[test]
+    fn parse_options() {
+        let options = Options::parse(&args(
+            "--collection Smoke --environment dev --request a --request b --delay 100 \
+             --bail --timeout 30 --junit out.xml --json out.json --verbose",
+        ))
+        .unwrap();
+        assert_eq!(options.collection, "Smoke");
+        assert_eq!(options.environment.as_deref(), Some("dev"));
+        assert_eq!(options.requests, vec!["a", "b"]);
+        assert_eq!(options.delay, "100");
+        assert!(options.bail && options.verbose);
+        assert_eq!(options.timeout, Some(Duration::from_secs(30)));
+        assert_eq!(options.junit, Some(PathBuf::from("out.xml")));
+        assert_eq!(options.json, Some(PathBuf::from("out.json")));
+        assert_eq!(options.settings, None);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |line| Options::parse(&args(line)).unwrap_err();
+        assert_eq!(error(""), "Collection is not set");
+        assert_eq!(error("--collection"), "Missing value of --collection");
+        assert_eq!(error("--collection a --delay soon"), "Invalid delay soon");
+        assert_eq!(error("--collection a --timeout -1"), "Invalid timeout -1");
+        assert_eq!(error("--collection a --fast"), "Unknown argument --fast");
+    }
+
+    #[tokio::test]
+    async fn exit_codes() {
+        assert_eq!(run(&args("--help")).await, 0);
+        assert_eq!(run(&args("--collection a -h")).await, 0);
+        assert_eq!(run(&args("--fast")).await, EXIT_ERROR);
+        assert_eq!(run(&args("--bail")).await, EXIT_ERROR);
+    }
+
+    #[test]
+    fn escape_xml() {
+        assert_eq!(
+            escape(r#"<a href="x">Tom & 'Jerry'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &apos;Jerry&apos;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn junit() {
+        let mut failed = report("Get <user>", false);
+        failed.code = 500;
+        failed.assertions = vec![
+            AssertionResult {
+                name: "Status equals 200".into(),
+                is_passed: false,
+                message: "500".into(),
+            },
+            AssertionResult {
+                name: "Body contains \"id\"".into(),
+                is_passed: true,
+                message: "found".into(),
+            },
+        ];
+        let mut not_sent = report("Delete", false);
+        not_sent.error = Some("Variable {{id}} is not set & 'required'".into());
+
+        let xml = junit_report(
+            "Users & Co",
+            &[report("List", true), failed, not_sent],
+            Duration::from_millis(2500),
+        );
+        assert_eq!(
+            xml,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="frapi" tests="3" failures="2" time="2.500">
+  <testsuite name="Users &amp; Co" tests="3" failures="2" time="2.500">
+    <testcase name="List" classname="Users &amp; Co" time="1.500"/>
+    <testcase name="Get &lt;user&gt;" classname="Users &amp; Co" time="1.500">
+      <failure message="1 of 2 assertions failed">FAIL Status equals 200 500
+PASS Body contains &quot;id&quot; found</failure>
+    </testcase>
+    <testcase name="Delete" classname="Users &amp; Co" time="1.500">
+      <failure message="Variable {{id}} is not set &amp; &apos;required&apos;"></failure>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+
+    #[test]
+    fn json() {
+        let mut failed = report("Create", false);
+        failed.code = 0;
+        failed.duration = None;
+        failed.error = Some("Connection refused".into());
+        failed.response_idx = None;
+        let mut passed = report("List", true);
+        passed.assertions = vec![AssertionResult {
+            name: "Status equals 200".into(),
+            is_passed: true,
+            message: "200".into(),
+        }];
+
+        let value = json_report("Users", "dev", &[passed, failed], Duration::from_secs(3));
+        assert_eq!(
+            value,
+            json!({
+                "collection": "Users",
+                "environment": "dev",
+                "passed": 1,
+                "failed": 1,
+                "duration_ms": 3000,
+                "requests": [
+                    {
+                        "name": "List",
+                        "status": 200,
+                        "duration_ms": 1500,
+                        "passed": true,
+                        "error": null,
+                        "assertions": [
+                            {"name": "Status equals 200", "passed": true, "message": "200"},
+                        ],
+                    },
+                    {
+                        "name": "Create",
+                        "status": 0,
+                        "duration_ms": null,
+                        "passed": false,
+                        "error": "Connection refused",
+                        "assertions": [],
+                    },
+                ],
+            })
+        );
+    }
+}
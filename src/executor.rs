@@ -3,12 +3,17 @@ use std::{
     fmt::Debug,
     fs,
     sync::{Arc, Mutex},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
+use chrono::Utc;
 use egui::ahash::HashMap;
 use tokio_tungstenite::{
-    tungstenite::{handshake::client::Request, Message as TokioMessage},
+    tungstenite::{
+        handshake::client::Request,
+        protocol::{frame::coding::CloseCode, CloseFrame},
+        Message as TokioMessage,
+    },
     MaybeTlsStream, WebSocketStream,
 };
 
@@ -17,10 +22,11 @@ use futures::{
     stream::{SplitSink, SplitStream},
     SinkExt, StreamExt, TryStreamExt,
 };
+use prost_reflect::{DynamicMessage, MethodDescriptor};
 use reqwest::{
-    header::{HeaderName, HeaderValue, CONTENT_TYPE},
+    header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE, SEC_WEBSOCKET_PROTOCOL},
     redirect::Policy,
-    Client,
+    Client, RequestBuilder, StatusCode,
 };
 use tokio::{
     net::TcpStream,
@@ -28,9 +34,19 @@ use tokio::{
     sync::mpsc::{channel, Receiver, Sender},
     time::{self, sleep},
 };
+use tokio_stream::wrappers::ReceiverStream;
 use tokio_tungstenite::connect_async;
+use tonic::{metadata::MetadataMap, transport::Channel, Status, Streaming};
 
 use crate::{
+    executor::{
+        captures::Captured,
+        graphql::GraphQlWsMessage,
+        grpc::{DynamicCodec, GrpcCallKind},
+        json_rpc::{JsonRpcCalls, JsonRpcMessage},
+        mqtt::{MqttClient, MqttEvent},
+        socket_io::SocketIoPacket,
+    },
     settings::main_settings::entity::request_settings::{
         method_settigns::Method, protocol_settings::Protocol,
     },
@@ -38,8 +54,12 @@ use crate::{
         main_page::{
             generics::Header,
             request::{
-                request_data::{RequestBody, RequestData},
-                HttpVersion, RequestHttpSetup, RequestWsSetup,
+                request_data::{
+                    Assertion, AuthType, BodyMode, Capture, RequestAuth, RequestBody, RequestData,
+                    Scripts,
+                },
+                HttpVersion, RequestGrpcSetup, RequestHttpSetup, RequestMqttSetup, RequestSetup,
+                RequestUdpSetup, RequestWsSetup, WsMode,
             },
             response::Response,
         },
@@ -49,6 +69,31 @@ use crate::{
 
 use tokio_tungstenite::tungstenite::client::IntoClientRequest;
 
+pub mod assertions;
+pub mod aws_sigv4;
+pub mod captures;
+pub mod digest;
+pub mod graphql;
+pub mod grpc;
+pub mod hmac_signer;
+pub mod json_rpc;
+pub mod mqtt;
+pub mod oauth2;
+pub mod scripts;
+pub mod socket_io;
+pub mod stomp;
+pub mod udp;
+pub mod variables;
+
+/// Write part of WS session
+type WsSink =
+    SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, tokio_tungstenite::tungstenite::Message>;
+/// Read part of WS session
+type WsSource = SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>;
+
+/// Time to wait peer answer on our Close frame, ms
+const WS_CLOSE_HANDSHAKE_TIMEOUT: u64 = 3000;
+
 /// Executor stares
 #[derive(PartialEq, Clone, Debug)]
 pub enum State {
@@ -106,7 +151,7 @@ impl From<&RequestData> for Message {
 #[derive(Debug)]
 pub enum Command {
     /// make reqeust with payload
-    EXECUTE(CommandExecute),
+    EXECUTE(Box<CommandExecute>),
     /// terminate currently pending job
     TERRMINATE,
 }
@@ -114,14 +159,14 @@ pub enum Command {
 /// from ReqeustData payload -> command to execute
 impl From<&RequestData> for Command {
     fn from(value: &RequestData) -> Self {
-        Self::EXECUTE(value.into())
+        Self::EXECUTE(Box::new(value.into()))
     }
 }
 
 impl Command {
     /// Get Command as execute
     pub fn execute(data: &RequestData) -> Self {
-        Self::EXECUTE(data.into())
+        Self::EXECUTE(Box::new(data.into()))
     }
 
     // Get Command as termiate
@@ -131,7 +176,10 @@ impl Command {
 
     pub fn drop_message(&mut self) {
         match self {
-            Command::EXECUTE(command_execute) => command_execute.message = "".into(),
+            Command::EXECUTE(command_execute) => {
+                command_execute.message = "".into();
+                command_execute.connection_only = true;
+            }
             Command::TERRMINATE => {}
         }
     }
@@ -151,6 +199,18 @@ pub struct CommandExecute {
     pub headers: Vec<Header>,
     pub body: RequestBody,
     pub message: String,
+    pub setup: RequestSetup,
+    pub auth: RequestAuth,
+    /// Response values stored into variables
+    pub captures: Vec<Capture>,
+    /// Checks of response
+    pub assertions: Vec<Assertion>,
+    /// Post-response scripts, collection ones first. Set by executor
+    pub scripts: Vec<String>,
+    /// Resolved variables, for scripts. Set by executor
+    pub variables: Vec<Header>,
+    /// Only initiate connection, without sending message
+    pub connection_only: bool,
 }
 
 /// From RequestData -> command to execute on executor
@@ -171,6 +231,13 @@ impl From<&RequestData> for CommandExecute {
             headers,
             body: value.body.clone(),
             message: value.message.message.clone(),
+            setup: value.setup.clone(),
+            auth: value.auth.clone(),
+            captures: value.captures.clone(),
+            assertions: value.assertions.clone(),
+            scripts: vec![],
+            variables: vec![],
+            connection_only: false,
         }
     }
 }
@@ -184,6 +251,8 @@ pub struct Executor {
     responses: Arc<Mutex<Vec<Response>>>,
     /// channel to throw commands from main thread to executor thread
     channel_sender: Option<Sender<Message>>,
+    /// values captured from responses, not yet stored into variables
+    captured: Arc<Mutex<Vec<Captured>>>,
 }
 
 impl Executor {
@@ -192,20 +261,105 @@ impl Executor {
             state: Arc::new(Mutex::new(State::FREE)),
             responses,
             channel_sender: None,
+            captured: Arc::new(Mutex::new(vec![])),
         }
     }
 
+    /// Take values captured from responses since last call
+    pub fn take_captured(&self) -> Vec<Captured> {
+        std::mem::take(&mut *self.captured.lock().unwrap())
+    }
+
     /// execute action based on payload.
     /// data - request data.
     /// connection_only - mean we need initiate only connection, without sending 1st message.
     ///     Ignored when WS already connected or when protocot is HTTP-like.
+    ///     For gRPC only streaming from client calls could be opened without message.
+    /// variables - values for {{name}} placeholders, unresolved ones sent as is.
+    /// scripts - run around send, collection ones first.
+    ///     Pre-request ones run synchronously on caller, before command sent to thread.
+    ///     They see request with resolved variables, ones they set resolved after.
     pub fn execute(
         &mut self,
         data: &RequestData,
         connection_only: bool,
+        variables: &[Header],
+        scripts: &[&Scripts],
         events: Arc<Mutex<Events>>,
     ) {
-        let message: Message = data.into();
+        let mut data = data.clone();
+        let mut variables = variables.to_vec();
+        let mut unresolved = match variables::resolve(&mut data, &variables) {
+            Ok(unresolved) => unresolved,
+            Err(err) => {
+                events.lock().unwrap().event_error(&err);
+                return;
+            }
+        };
+
+        let pre_request: Vec<&str> = scripts
+            .iter()
+            .map(|scripts| scripts.pre_request.as_str())
+            .filter(|script| !script.trim().is_empty())
+            .collect();
+        if !pre_request.is_empty() {
+            match scripts::pre_request(&pre_request, &mut data, &variables, &events) {
+                Ok(set) => {
+                    if !set.is_empty() {
+                        // Set variables override existing ones
+                        for captured in set.iter().rev() {
+                            variables.insert(
+                                0,
+                                Header {
+                                    key: captured.variable.clone(),
+                                    value: captured.value.clone(),
+                                },
+                            );
+                        }
+                        unresolved = match variables::resolve(&mut data, &variables) {
+                            Ok(unresolved) => unresolved,
+                            Err(err) => {
+                                events.lock().unwrap().event_error(&err);
+                                return;
+                            }
+                        };
+                        self.captured.lock().unwrap().extend(set);
+                    }
+                }
+                Err(err) => {
+                    events.lock().unwrap().event_error(&err);
+                    return;
+                }
+            }
+        }
+
+        if !unresolved.is_empty() {
+            events.lock().unwrap().event_warning(&format!(
+                "Unresolved variables: {}. Sent as is",
+                unresolved
+                    .iter()
+                    .map(|name| format!("{{{{{name}}}}}"))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ));
+        }
+        if !data.assertions.is_empty() && !data.protocot_is_http() {
+            events.lock().unwrap().event_warning(&format!(
+                "Assertions checked only for HTTP responses, {} ignored",
+                data.assertions.len()
+            ));
+        }
+        let data = &data;
+
+        let mut message: Message = data.into();
+        if let Message::COMMAND(Command::EXECUTE(command_execute)) = &mut message {
+            command_execute.scripts = scripts
+                .iter()
+                .map(|scripts| scripts.post_response.clone())
+                .filter(|script| !script.trim().is_empty())
+                .collect();
+            command_execute.variables = variables;
+        }
 
         let state = match self.state.lock() {
             Ok(state) => (*state).clone(),
@@ -217,14 +371,15 @@ impl Executor {
             }
         };
 
-        // If executor connected == currently websocket session going on
+        // If executor connected == currently websocket session, MQTT session or gRPC client stream going on
         // Passing message to session
         // In usual way you cant pass data with protocol other than Websocket without termination connection
         // This must be guaranted by UI
         if state == State::CONNECTED {
-            events.lock().unwrap().event_info(&format!(
-                "Detected connected websocket session, sending request..."
-            ));
+            events
+                .lock()
+                .unwrap()
+                .event_info(&format!("Detected connected session, sending request..."));
             self.send_ws(message);
             return;
         }
@@ -274,6 +429,70 @@ impl Executor {
 
             return;
         }
+
+        // if executor is free and requested MQTT session
+        if state == State::FREE && data.protocot_is_mqtt() {
+            events
+                .lock()
+                .unwrap()
+                .event_info(&"Detected free executor, connecting to MQTT broker...".into());
+            *self.state.lock().unwrap() = State::CONNECTED;
+            if let Some(mut command) = message.get_command() {
+                if connection_only {
+                    command.drop_message();
+                };
+
+                let setup = match data.setup.mqtt() {
+                    Some(val) => val,
+                    None => &RequestMqttSetup::default(),
+                };
+
+                self.spawn_mqtt_connection(command, setup, events);
+            }
+
+            return;
+        }
+
+        // if executor is free and requested gRPC call
+        if state == State::FREE && data.protocot_is_grpc() {
+            events
+                .lock()
+                .unwrap()
+                .event_info(&"Detected free executor, sending gRPC request...".into());
+            *self.state.lock().unwrap() = State::BUSY;
+            if let Some(mut command) = message.get_command() {
+                // Message kept: unary calls always need it
+                if let Command::EXECUTE(command_execute) = &mut command {
+                    command_execute.connection_only = connection_only;
+                }
+
+                let setup = match data.setup.grpc() {
+                    Some(val) => val,
+                    None => &RequestGrpcSetup::default(),
+                };
+
+                self.spawn_grpc_connection(command, setup, events);
+            }
+
+            return;
+        }
+
+        // if executor is free and requested UDP datagram
+        if state == State::FREE && data.protocot_is_udp() {
+            events
+                .lock()
+                .unwrap()
+                .event_info(&"Detected free executor, sending UDP datagram...".into());
+            *self.state.lock().unwrap() = State::BUSY;
+            if let Some(command) = message.get_command() {
+                let setup = match data.setup.udp() {
+                    Some(val) => val,
+                    None => &RequestUdpSetup::default(),
+                };
+
+                self.spawn_udp_connection(command, setup, events);
+            }
+        }
     }
 
     /// Terminate currently pending requests, if any
@@ -306,6 +525,7 @@ impl Executor {
             message,
             setup.clone(),
             Arc::clone(&self.responses),
+            Arc::clone(&self.captured),
             Arc::clone(&events),
             receiver,
             self.state.clone(),
@@ -317,6 +537,7 @@ impl Executor {
         message: Command,
         setup: RequestHttpSetup,
         responses: Arc<Mutex<Vec<Response>>>,
+        captured: Arc<Mutex<Vec<Captured>>>,
         events: Arc<Mutex<Events>>,
         mut command_channel: Receiver<Message>,
         executor_state: Arc<Mutex<State>>,
@@ -351,45 +572,160 @@ impl Executor {
 
                     let mut result = client.request(command_execute.method.into(), uri.clone());
 
-                    // Settings BODY part
-                    if command_execute.body.raw.rows > 0 {
-                        result = result.body(command_execute.body.raw.message);
-                    } else if command_execute.body.binary_path.len() > 0 {
-                        let file = fs::read(command_execute.body.binary_path.clone());
-                        match file {
-                            Ok(f) => {
-                                let content_type =
-                                    mime_guess::from_path(command_execute.body.binary_path)
-                                        .first_or_octet_stream()
-                                        .to_string();
-                                result = result.body(f).header(CONTENT_TYPE, content_type);
+                    // Sent JSON-RPC calls, to label reply
+                    let mut json_rpc_calls = JsonRpcCalls::new();
+
+                    // Settings BODY part. Only selected mode sent, other parts may keep old values
+                    match command_execute.body.mode {
+                        BodyMode::GRAPHQL => {
+                            match graphql::envelope(&command_execute.body.graphql) {
+                                Ok(envelope) => result = result.json(&envelope),
+                                Err(err) => {
+                                    events.lock().unwrap().event_error(&format!("Error: {err}"));
+                                    return;
+                                }
+                            };
+                        }
+                        BodyMode::JSONRPC => {
+                            match json_rpc::envelope(&command_execute.body.json_rpc) {
+                                Ok(envelope) => {
+                                    json_rpc_calls = json_rpc::pending_calls(&envelope.to_string());
+                                    result = result.json(&envelope);
+                                }
+                                Err(err) => {
+                                    events.lock().unwrap().event_error(&format!("Error: {err}"));
+                                    return;
+                                }
+                            };
+                        }
+                        BodyMode::RAW => {
+                            if !command_execute.body.raw.message.is_empty() {
+                                result = result.body(command_execute.body.raw.message);
                             }
-                            Err(err) => {
-                                events.lock().unwrap().event_error(&format!(
-                                    "Error: Could not read file: {}; Error: {err}",
-                                    command_execute.body.binary_path,
-                                ));
-                                return;
+                        }
+                        BodyMode::BINARY => {
+                            if !command_execute.body.binary_path.is_empty() {
+                                let file = fs::read(command_execute.body.binary_path.clone());
+                                match file {
+                                    Ok(f) => {
+                                        let content_type =
+                                            mime_guess::from_path(command_execute.body.binary_path)
+                                                .first_or_octet_stream()
+                                                .to_string();
+                                        result = result.body(f).header(CONTENT_TYPE, content_type);
+                                    }
+                                    Err(err) => {
+                                        events.lock().unwrap().event_error(&format!(
+                                            "Error: Could not read file: {}; Error: {err}",
+                                            command_execute.body.binary_path,
+                                        ));
+                                        return;
+                                    }
+                                };
                             }
-                        };
-                    } else {
-                        let form: HashMap<String, String> = command_execute
-                            .body
-                            .form_data
-                            .iter()
-                            .map(|f| (f.key.clone(), f.value.clone()))
-                            .collect();
-
-                        result = result.form(&form);
+                        }
+                        BodyMode::FORMDATA => {
+                            let form: HashMap<String, String> = command_execute
+                                .body
+                                .form_data
+                                .iter()
+                                .map(|f| (f.key.clone(), f.value.clone()))
+                                .collect();
+
+                            result = result.form(&form);
+                        }
                     };
 
-                    for header in command_execute.headers {
-                        result = result.header(header.key, header.value);
-                    }
+                    let result = match with_auth(
+                        result,
+                        command_execute.headers,
+                        &command_execute.auth,
+                        &events,
+                    )
+                    .await
+                    {
+                        Ok(val) => val,
+                        Err(err) => {
+                            events.lock().unwrap().event_error(&format!("Error: {err}"));
+                            return;
+                        }
+                    };
+
+                    let started = Instant::now();
+
+                    let result = match result.build() {
+                        Ok(mut request) => {
+                            if let Err(err) = sign_request(&mut request, &command_execute.auth) {
+                                events.lock().unwrap().event_error(&format!("Error: {err}"));
+                                return;
+                            }
+
+                            // Digest answers server challenge, so copy kept for second leg
+                            let retry = if command_execute.auth.auth_type == AuthType::DIGEST {
+                                request.try_clone()
+                            } else {
+                                None
+                            };
+
+                            match (client.execute(request).await, retry) {
+                                (Ok(val), Some(mut retry))
+                                    if val.status() == StatusCode::UNAUTHORIZED =>
+                                {
+                                    let challenge = digest::challenge(val.headers());
+
+                                    // First leg shown as separate response
+                                    let mut challenge_response =
+                                        match Response::from_http_response(val).await {
+                                            Ok(r) => r,
+                                            Err((r, _)) => r,
+                                        };
+                                    challenge_response.event = Some("Digest challenge".into());
+                                    responses.lock().unwrap().push(challenge_response);
 
-                    let result = result.send().await;
+                                    let challenge = match challenge {
+                                        Ok(val) => val,
+                                        Err(err) => {
+                                            events
+                                                .lock()
+                                                .unwrap()
+                                                .event_error(&format!("Error: {err}"));
+                                            return;
+                                        }
+                                    };
+                                    let authorization = digest::authorization(
+                                        &challenge,
+                                        &command_execute.auth.username,
+                                        &command_execute.auth.password,
+                                        retry.method().as_str(),
+                                        retry.url(),
+                                    );
+                                    let header = Header {
+                                        key: "Authorization".into(),
+                                        value: authorization,
+                                    };
+                                    if let Err(err) =
+                                        insert_headers(retry.headers_mut(), vec![header])
+                                    {
+                                        events
+                                            .lock()
+                                            .unwrap()
+                                            .event_error(&format!("Error: {err}"));
+                                        return;
+                                    }
+
+                                    events
+                                        .lock()
+                                        .unwrap()
+                                        .event_info(&"Answering Digest challenge...".into());
+                                    client.execute(retry).await
+                                }
+                                (result, _) => result,
+                            }
+                        }
+                        Err(err) => Err(err),
+                    };
 
-                    let response = match result {
+                    let mut response = match result {
                         Ok(val) => match Response::from_http_response(val).await {
                             Ok(r) => {
                                 events
@@ -418,6 +754,41 @@ impl Executor {
                         },
                     };
 
+                    if !command_execute.body.json_rpc.is_empty() {
+                        response.event = json_rpc::reply_label(&response.data.raw, &json_rpc_calls);
+                    }
+                    response.duration = Some(started.elapsed());
+
+                    captures::store(
+                        &command_execute.captures,
+                        &response,
+                        true,
+                        &captured,
+                        &events,
+                    );
+
+                    response.assertions =
+                        assertions::evaluate(&command_execute.assertions, &response);
+                    scripts::post_response(
+                        &command_execute.scripts,
+                        &mut response,
+                        &command_execute.variables,
+                        &captured,
+                        &events,
+                    );
+                    let (passed, total) = response.assertions_passed();
+                    if passed < total {
+                        events
+                            .lock()
+                            .unwrap()
+                            .event_warning(&format!("Tests failed: {} of {total}", total - passed));
+                    } else if total > 0 {
+                        events
+                            .lock()
+                            .unwrap()
+                            .event_info(&format!("Tests passed: {total}"));
+                    }
+
                     match responses.lock() {
                         Ok(mut r) => {
                             r.push(response);
@@ -464,6 +835,7 @@ impl Executor {
             command,
             settings.clone(),
             Arc::clone(&self.responses),
+            Arc::clone(&self.captured),
             Arc::clone(&events),
             receiver,
             self.state.clone(),
@@ -475,22 +847,50 @@ impl Executor {
         command: Command,
         settings: RequestWsSetup,
         responses: Arc<Mutex<Vec<Response>>>,
+        captured: Arc<Mutex<Vec<Captured>>>,
         events: Arc<Mutex<Events>>,
         mut command_channel: Receiver<Message>,
         executor_state: Arc<Mutex<State>>,
     ) {
         match command {
             Command::EXECUTE(command_execute) => {
+                let uri = match settings.mode {
+                    WsMode::RAW | WsMode::STOMP | WsMode::GRAPHQL | WsMode::JSONRPC => {
+                        command_execute.uri.clone()
+                    }
+                    WsMode::SOCKETIO => socket_io::socket_io_uri(&command_execute.uri),
+                };
                 let uri = format!(
                     "{}://{}",
                     command_execute.protocol.to_string().to_lowercase(),
-                    command_execute.uri
+                    uri
                 );
+                let uri = with_query_params(uri, &command_execute.auth.query_params());
 
                 let mut request: Request = uri.clone().into_client_request().unwrap();
 
-                // Add headers
-                for header in command_execute.headers {
+                // OAuth2 authorization could wait for user in browser
+                let auth_headers = select! {
+                    result = auth_headers(&command_execute.auth, &events) => match result {
+                        Ok(val) => val,
+                        Err(err) => {
+                            events.lock().unwrap().event_error(&format!("Error: {err}"));
+                            *executor_state.lock().unwrap() = State::FREE;
+                            return;
+                        }
+                    },
+                    _ = command_channel.recv() => {
+                        events
+                            .lock()
+                            .unwrap()
+                            .event_info(&"Authorization terminated.".into());
+                        *executor_state.lock().unwrap() = State::FREE;
+                        return;
+                    }
+                };
+
+                // Add headers, authorization goes over custom headers with same name
+                for header in command_execute.headers.iter().chain(auth_headers.iter()) {
                     let key = match HeaderName::from_bytes(header.key.as_bytes()) {
                         Ok(val) => val,
                         Err(err) => {
@@ -517,6 +917,48 @@ impl Executor {
                     request.headers_mut().insert(key, value);
                 }
 
+                if command_execute.auth.auth_type == AuthType::DIGEST {
+                    events
+                        .lock()
+                        .unwrap()
+                        .event_warning(&"Digest auth is not supported for WS handshake".into());
+                }
+
+                // Handshake is signed as GET without body
+                let signed = reqwest::Url::parse(&uri)
+                    .map_err(|err| err.to_string())
+                    .and_then(|url| {
+                        signature_headers(
+                            &command_execute.auth,
+                            "GET",
+                            &url,
+                            request.headers(),
+                            Some(&[]),
+                        )
+                    });
+                if let Err(err) =
+                    signed.and_then(|headers| insert_headers(request.headers_mut(), headers))
+                {
+                    events.lock().unwrap().event_error(&format!("Error: {err}"));
+                    *executor_state.lock().unwrap() = State::FREE;
+                    return;
+                }
+
+                // STOMP brokers choose protocol version by subprotocol, GraphQL servers - protocol itself
+                let subprotocol = match settings.mode {
+                    WsMode::STOMP => Some(stomp::STOMP_SUBPROTOCOLS),
+                    WsMode::GRAPHQL => Some(graphql::GRAPHQL_WS_SUBPROTOCOL),
+                    WsMode::RAW | WsMode::SOCKETIO | WsMode::JSONRPC => None,
+                };
+                if let Some(subprotocol) = subprotocol {
+                    if !request.headers().contains_key(SEC_WEBSOCKET_PROTOCOL) {
+                        request.headers_mut().insert(
+                            SEC_WEBSOCKET_PROTOCOL,
+                            HeaderValue::from_static(subprotocol),
+                        );
+                    }
+                }
+
                 // Try to connect
                 let io_result = get_ws_io(
                     request.clone(),
@@ -537,6 +979,8 @@ impl Executor {
 
                 let (mut write, mut read) = io_result.unwrap();
 
+                start_ws_session(&command_execute.uri, &settings, &mut write, &events).await;
+
                 let mut request_interval = time::interval(Duration::from_millis(60));
 
                 events
@@ -544,13 +988,56 @@ impl Executor {
                     .unwrap()
                     .event_info(&"Websocket: start messages loop...".into());
 
+                // Initial message on protocols with own handshake must wait it to complete
+                let mut pending_message = None;
+
+                // JSON-RPC calls waiting for reply: id -> method
+                let mut json_rpc_calls = JsonRpcCalls::new();
+
+                // Captures and scripts of last sent message, applied to every received one
+                let mut captures = command_execute.captures.clone();
+                let mut scripts = command_execute.scripts.clone();
+                let mut variables = command_execute.variables.clone();
+
+                // STOMP frames could be sent without body, like SUBSCRIBE
+                let has_initial_message = match settings.mode {
+                    WsMode::STOMP => !command_execute.connection_only,
+                    WsMode::GRAPHQL => {
+                        !command_execute.connection_only
+                            && !command_execute.body.graphql.query.message.is_empty()
+                    }
+                    WsMode::JSONRPC => {
+                        !command_execute.connection_only
+                            && !command_execute.body.json_rpc.is_empty()
+                    }
+                    WsMode::RAW | WsMode::SOCKETIO => command_execute.message.len() > 0,
+                };
+
                 // if have some initial command on ws connection sending it
-                if command_execute.message.len() > 0 {
-                    let _ = write
-                        .send(tokio_tungstenite::tungstenite::Message::text(
-                            command_execute.message.clone(),
-                        ))
-                        .await;
+                if has_initial_message {
+                    match encode_ws_message(&command_execute, &settings) {
+                        Ok(text) => match settings.mode {
+                            WsMode::RAW => {
+                                let _ = write
+                                    .send(tokio_tungstenite::tungstenite::Message::text(text))
+                                    .await;
+                            }
+                            WsMode::JSONRPC => {
+                                json_rpc_calls.extend(json_rpc::pending_calls(&text));
+                                let _ = write
+                                    .send(tokio_tungstenite::tungstenite::Message::text(text))
+                                    .await;
+                            }
+                            WsMode::SOCKETIO | WsMode::STOMP | WsMode::GRAPHQL => {
+                                pending_message = Some(text)
+                            }
+                        },
+                        Err(err) => {
+                            events.lock().unwrap().event_error(&format!(
+                                "Error: Could not prepare initial message. Error: {err}"
+                            ));
+                        }
+                    };
                 }
 
                 // Executing main loop
@@ -562,24 +1049,99 @@ impl Executor {
                                     // Received MSG text
                                     match val {
                                         TokioMessage::Text(utf8_bytes) => {
-                                            events
-                                                .lock()
-                                                .unwrap()
-                                                .event_info(&format!("Websocket: received response"));
-                                            responses.lock().unwrap().push(
-                                                Response::from_utf8_bytes(utf8_bytes)
-                                            );
+                                            // Protocol handlers push any number of responses
+                                            let received = responses.lock().unwrap().len();
+
+                                            if settings.mode == WsMode::SOCKETIO {
+                                                process_socket_io_text(
+                                                    utf8_bytes.as_str(),
+                                                    &settings,
+                                                    &mut write,
+                                                    &mut pending_message,
+                                                    &responses,
+                                                    &events,
+                                                )
+                                                .await;
+                                            } else if settings.mode == WsMode::GRAPHQL {
+                                                process_graphql_ws_text(
+                                                    utf8_bytes.as_str(),
+                                                    &mut write,
+                                                    &mut pending_message,
+                                                    &responses,
+                                                    &events,
+                                                )
+                                                .await;
+                                            } else if settings.mode == WsMode::JSONRPC {
+                                                process_json_rpc_text(
+                                                    utf8_bytes.as_str(),
+                                                    &mut json_rpc_calls,
+                                                    &responses,
+                                                    &events,
+                                                );
+                                            } else if settings.mode == WsMode::STOMP {
+                                                process_stomp_text(
+                                                    utf8_bytes.as_str(),
+                                                    &mut write,
+                                                    &mut pending_message,
+                                                    &responses,
+                                                    &events,
+                                                )
+                                                .await;
+                                            } else {
+                                                events
+                                                    .lock()
+                                                    .unwrap()
+                                                    .event_info(&format!("Websocket: received response"));
+                                                responses.lock().unwrap().push(
+                                                    Response::from_utf8_bytes(utf8_bytes)
+                                                );
+                                            }
+
+                                            if !captures.is_empty() {
+                                                let new_responses: Vec<Response> = responses
+                                                    .lock()
+                                                    .unwrap()
+                                                    .iter()
+                                                    .skip(received)
+                                                    .cloned()
+                                                    .collect();
+                                                for response in new_responses.iter() {
+                                                    captures::store(&captures, response, false, &captured, &events);
+                                                }
+                                            }
+                                            // Post-response scripts run on copies, so responses are not locked during long script
+                                            if !scripts.is_empty() {
+                                                let new_responses: Vec<Response> = responses
+                                                    .lock()
+                                                    .unwrap()
+                                                    .iter()
+                                                    .skip(received)
+                                                    .cloned()
+                                                    .collect();
+                                                for (i, mut response) in new_responses.into_iter().enumerate() {
+                                                    scripts::post_response(&scripts, &mut response, &variables, &captured, &events);
+                                                    if let Some(stored) = responses.lock().unwrap().get_mut(received + i) {
+                                                        stored.assertions = response.assertions;
+                                                    }
+                                                }
+                                            }
                                             continue;
                                         },
                                         // Received Remote Close
-                                        TokioMessage::Close(_) => {
+                                        TokioMessage::Close(close_frame) => {
                                             events
                                                 .lock()
                                                 .unwrap()
-                                                .event_warning(&"Peer close connection.".into());
+                                                .event_warning(&format!("Peer close connection. Frame: {}", match &close_frame {
+                                                    Some(frame) => frame.to_string(),
+                                                    None => "<empty>".into(),
+                                                }));
+
+                                            // Answering with Close frame to complete handshake
+                                            let _ = write.close().await;
 
                                             responses.lock().unwrap().push(
-                                                Response::closed_connection()
+                                                Response::closed_connection(close_frame)
                                             );
                                             *executor_state.lock().unwrap() = State::FREE;
                                             break;
@@ -597,7 +1159,7 @@ impl Executor {
                                         .unwrap()
                                         .event_error(&format!("Error: During WS connection on new message error occured. Error: {e}"));
                                     responses.lock().unwrap().push(
-                                        Response::closed_connection()
+                                        Response::closed_connection(None)
                                     );
 
                                     // If error occured, try to reconnect with settings
@@ -619,6 +1181,8 @@ impl Executor {
                                     }
 
                                     (write, read) = io_result.unwrap();
+
+                                    start_ws_session(&command_execute.uri, &settings, &mut write, &events).await;
                                 }
                             };
                         }
@@ -641,9 +1205,28 @@ impl Executor {
                                             .lock()
                                             .unwrap()
                                             .event_info(&format!("Websocket: sending message"));
+                                        captures = command_execute.captures.clone();
+                                        scripts = command_execute.scripts.clone();
+                                        variables = command_execute.variables.clone();
+
+                                        let text = match encode_ws_message(&command_execute, &settings) {
+                                            Ok(val) => val,
+                                            Err(err) => {
+                                                events
+                                                    .lock()
+                                                    .unwrap()
+                                                    .event_error(&format!("Error: Could not prepare message. Error: {err}"));
+                                                continue;
+                                            }
+                                        };
+
+                                        if settings.mode == WsMode::JSONRPC {
+                                            json_rpc_calls.extend(json_rpc::pending_calls(&text));
+                                        }
+
                                         let result = write
                                             .send(tokio_tungstenite::tungstenite::Message::text(
-                                                command_execute.message.clone(),
+                                                text,
                                             ))
                                             .await;
 
@@ -664,6 +1247,50 @@ impl Executor {
                                             .unwrap()
                                             .event_info(&"Websocket: requested WS termination.".into());
 
+                                        // Graceful STOMP session end before closing WS
+                                        if settings.mode == WsMode::STOMP {
+                                            let _ = write.send(TokioMessage::text(stomp::disconnect_frame())).await;
+                                        }
+
+                                        let close_frame = CloseFrame {
+                                            code: CloseCode::from(settings.close_code()),
+                                            reason: settings.close_reason().into(),
+                                        };
+
+                                        match write.send(TokioMessage::Close(Some(close_frame))).await {
+                                            Ok(_) => {
+                                                // Waiting for peer Close frame to complete handshake
+                                                let peer_close_frame = time::timeout(
+                                                    Duration::from_millis(WS_CLOSE_HANDSHAKE_TIMEOUT),
+                                                    wait_ws_close(&mut read),
+                                                )
+                                                .await;
+
+                                                match peer_close_frame {
+                                                    Ok(close_frame) => {
+                                                        responses.lock().unwrap().push(
+                                                            Response::closed_connection(close_frame)
+                                                        );
+                                                    }
+                                                    Err(_) => {
+                                                        events
+                                                            .lock()
+                                                            .unwrap()
+                                                            .event_warning(&"Websocket: peer did not answer on Close frame.".into());
+                                                        responses.lock().unwrap().push(
+                                                            Response::closed_connection(None)
+                                                        );
+                                                    }
+                                                };
+                                            }
+                                            Err(err) => {
+                                                events
+                                                    .lock()
+                                                    .unwrap()
+                                                    .event_error(&format!("Error: Could not send Close frame to WS channel. Error: {err}"));
+                                            }
+                                        };
+
                                         *executor_state.lock().unwrap() = State::FREE;
                                         break;
                                     }
@@ -694,13 +1321,1145 @@ impl Executor {
         }
     }
 
-    /// Send command to queue
-    fn send_ws(&mut self, message: Message) {
-        if self.channel_sender.is_none() {
-            *self.state.lock().unwrap() = State::FREE;
-            return;
+    /// Spawn separate thread for MQTT session
+    fn spawn_mqtt_connection(
+        &mut self,
+        command: Command,
+        settings: &RequestMqttSetup,
+        events: Arc<Mutex<Events>>,
+    ) {
+        let (sender, receiver) = channel::<Message>(100);
+        self.channel_sender = Some(sender);
+
+        tokio::spawn(Self::mqtt_thread(
+            command,
+            settings.clone(),
+            Arc::clone(&self.responses),
+            Arc::clone(&events),
+            receiver,
+            self.state.clone(),
+        ));
+    }
+
+    /// Thread for MQTT session.
+    /// Initial message waits for CONNACK, received publishes stored with their topic
+    async fn mqtt_thread(
+        command: Command,
+        settings: RequestMqttSetup,
+        responses: Arc<Mutex<Vec<Response>>>,
+        events: Arc<Mutex<Events>>,
+        mut command_channel: Receiver<Message>,
+        executor_state: Arc<Mutex<State>>,
+    ) {
+        let command_execute = match command {
+            Command::EXECUTE(command_execute) => command_execute,
+            Command::TERRMINATE => {
+                *executor_state.lock().unwrap() = State::FREE;
+                return;
+            }
         };
-        let _ = block_on(self.channel_sender.as_mut().unwrap().send(message));
+
+        let (client, mut eventloop) =
+            match mqtt::session(&command_execute.protocol, &command_execute.uri, &settings) {
+                Ok(val) => val,
+                Err(err) => {
+                    events.lock().unwrap().event_error(&format!("Error: {err}"));
+                    *executor_state.lock().unwrap() = State::FREE;
+                    return;
+                }
+            };
+
+        // Subscribe could be sent without payload, so only connection flag matters
+        let mut pending_message = if command_execute.connection_only {
+            None
+        } else {
+            Some(command_execute)
+        };
+        // Set after DISCONNECT sent, connection end is expected then
+        let mut is_disconnecting = false;
+
+        loop {
+            select! {
+                event = eventloop.poll() => match event {
+                    Ok(MqttEvent::ConnAck { success: true, code: _, session_present }) => {
+                        events.lock().unwrap().event_info(&format!(
+                            "MQTT: connected. Session present: {session_present}"
+                        ));
+                        if let Some(command_execute) = pending_message.take() {
+                            mqtt_send(&client, &command_execute, &settings, &events).await;
+                        }
+                    }
+                    Ok(MqttEvent::ConnAck { success: false, code, session_present: _ }) => {
+                        events
+                            .lock()
+                            .unwrap()
+                            .event_error(&format!("Error: MQTT broker refused connection: {code}"));
+                        break;
+                    }
+                    Ok(MqttEvent::Publish { topic, payload, headers }) => {
+                        events
+                            .lock()
+                            .unwrap()
+                            .event_info(&"MQTT: received publish".into());
+                        responses.lock().unwrap().push(Response::from_frame(
+                            format!("PUBLISH {topic}"),
+                            headers,
+                            payload,
+                        ));
+                    }
+                    Ok(MqttEvent::SubAck { success: true, codes }) => {
+                        events
+                            .lock()
+                            .unwrap()
+                            .event_info(&format!("MQTT: subscribed. Granted: {codes}"));
+                    }
+                    Ok(MqttEvent::SubAck { success: false, codes }) => {
+                        events
+                            .lock()
+                            .unwrap()
+                            .event_error(&format!("Error: MQTT subscription rejected: {codes}"));
+                    }
+                    Ok(MqttEvent::UnsubAck) => {
+                        events.lock().unwrap().event_info(&"MQTT: unsubscribed".into());
+                    }
+                    Ok(MqttEvent::Other) => {}
+                    Err(err) => {
+                        if !is_disconnecting {
+                            events.lock().unwrap().event_error(&format!("Error: {err}"));
+                        }
+                        break;
+                    }
+                },
+                message = command_channel.recv() => match message {
+                    Some(Message::COMMAND(Command::EXECUTE(command_execute))) => {
+                        events.lock().unwrap().event_info(&"MQTT: sending message".into());
+                        mqtt_send(&client, &command_execute, &settings, &events).await;
+                    }
+                    Some(Message::COMMAND(Command::TERRMINATE)) | None => {
+                        events
+                            .lock()
+                            .unwrap()
+                            .event_info(&"MQTT: requested disconnect.".into());
+                        if is_disconnecting {
+                            break;
+                        }
+                        if let Err(err) = client.disconnect().await {
+                            events.lock().unwrap().event_error(&format!("Error: {err}"));
+                            break;
+                        }
+                        is_disconnecting = true;
+                    }
+                    Some(Message::RESULT(result)) => {
+                        events.lock().unwrap().event_warning(&format!(
+                            "Error: Received in command channel Result type: {result:#?}"
+                        ));
+                    }
+                }
+            }
+        }
+
+        events
+            .lock()
+            .unwrap()
+            .event_info(&"MQTT: session closed.".into());
+        *executor_state.lock().unwrap() = State::FREE;
+    }
+
+    /// Spawn separate thread for gRPC call
+    fn spawn_grpc_connection(
+        &mut self,
+        command: Command,
+        setup: &RequestGrpcSetup,
+        events: Arc<Mutex<Events>>,
+    ) {
+        let (sender, receiver) = channel::<Message>(100);
+        self.channel_sender = Some(sender);
+
+        tokio::spawn(Self::grpc_thread(
+            command,
+            setup.clone(),
+            Arc::clone(&self.responses),
+            Arc::clone(&events),
+            receiver,
+            self.state.clone(),
+        ));
+    }
+
+    /// Thread for gRPC calls.
+    /// Streaming from client calls keep CONNECTED state, so messages passed like in WS session.
+    /// First termination half-close client stream, second one - cancel call
+    async fn grpc_thread(
+        command: Command,
+        setup: RequestGrpcSetup,
+        responses: Arc<Mutex<Vec<Response>>>,
+        events: Arc<Mutex<Events>>,
+        mut command_channel: Receiver<Message>,
+        executor_state: Arc<Mutex<State>>,
+    ) {
+        let command_execute = match command {
+            Command::EXECUTE(command_execute) => command_execute,
+            Command::TERRMINATE => {
+                *executor_state.lock().unwrap() = State::FREE;
+                return;
+            }
+        };
+
+        let prepared = select! {
+            val = grpc_prepare(&command_execute, &setup) => val,
+            _ = command_channel.recv() => {
+                events.lock().unwrap().event_info(&"gRPC: call terminated.".into());
+                *executor_state.lock().unwrap() = State::FREE;
+                return;
+            }
+        };
+
+        let (mut client, method, metadata) = match prepared {
+            Ok(val) => val,
+            Err(err) => {
+                events.lock().unwrap().event_error(&format!("Error: {err}"));
+                *executor_state.lock().unwrap() = State::FREE;
+                return;
+            }
+        };
+
+        let kind = GrpcCallKind::from_method(&method);
+        let path = match grpc::method_path(&method) {
+            Ok(val) => val,
+            Err(err) => {
+                events.lock().unwrap().event_error(&format!("Error: {err}"));
+                *executor_state.lock().unwrap() = State::FREE;
+                return;
+            }
+        };
+        let codec = DynamicCodec::new(&method);
+
+        events
+            .lock()
+            .unwrap()
+            .event_info(&format!("gRPC: calling {} ({kind})", method.full_name()));
+
+        if !kind.is_client_streaming() {
+            let message = match grpc::message_from_json(method.input(), &command_execute.message) {
+                Ok(val) => val,
+                Err(err) => {
+                    events.lock().unwrap().event_error(&format!("Error: {err}"));
+                    *executor_state.lock().unwrap() = State::FREE;
+                    return;
+                }
+            };
+            let mut request = tonic::Request::new(message);
+            *request.metadata_mut() = metadata;
+
+            let call = async {
+                if kind == GrpcCallKind::Unary {
+                    let result = client.unary(request, path, codec).await;
+                    grpc_read_single(result, &responses, &events);
+                } else {
+                    let result = client.server_streaming(request, path, codec).await;
+                    grpc_read_stream(result, &responses, &events).await;
+                }
+            };
+
+            select! {
+                _ = call => {}
+                _ = command_channel.recv() => {
+                    events.lock().unwrap().event_info(&"gRPC: call cancelled.".into());
+                }
+            };
+
+            *executor_state.lock().unwrap() = State::FREE;
+            return;
+        }
+
+        // Client stream: messages from UI go to channel until half-close
+        let (sender, receiver) = channel::<DynamicMessage>(100);
+        let mut sender = Some(sender);
+
+        if !command_execute.connection_only && !command_execute.message.trim().is_empty() {
+            grpc_send(&sender, &method, &command_execute.message, &events).await;
+        }
+
+        let mut request = tonic::Request::new(ReceiverStream::new(receiver));
+        *request.metadata_mut() = metadata;
+
+        *executor_state.lock().unwrap() = State::CONNECTED;
+
+        let call = async {
+            if kind == GrpcCallKind::ClientStreaming {
+                let result = client.client_streaming(request, path, codec).await;
+                grpc_read_single(result, &responses, &events);
+            } else {
+                let result = client.streaming(request, path, codec).await;
+                grpc_read_stream(result, &responses, &events).await;
+            }
+        };
+        tokio::pin!(call);
+
+        loop {
+            select! {
+                _ = &mut call => break,
+                message = command_channel.recv() => match message {
+                    Some(Message::COMMAND(Command::EXECUTE(command_execute))) => {
+                        grpc_send(&sender, &method, &command_execute.message, &events).await;
+                    }
+                    Some(Message::COMMAND(Command::TERRMINATE)) => {
+                        // Dropping sender ends client stream
+                        if sender.take().is_some() {
+                            events.lock().unwrap().event_info(
+                                &"gRPC: client stream closed, waiting for server. Terminate again to cancel call.".into(),
+                            );
+                        } else {
+                            events.lock().unwrap().event_info(&"gRPC: call cancelled.".into());
+                            break;
+                        }
+                    }
+                    Some(Message::RESULT(result)) => {
+                        events.lock().unwrap().event_warning(&format!(
+                            "Error: Received in command channel Result type: {result:#?}"
+                        ));
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        *executor_state.lock().unwrap() = State::FREE;
+    }
+
+    /// Spawn separate thread for UDP datagram
+    fn spawn_udp_connection(
+        &mut self,
+        command: Command,
+        setup: &RequestUdpSetup,
+        events: Arc<Mutex<Events>>,
+    ) {
+        let (sender, receiver) = channel::<Message>(100);
+        self.channel_sender = Some(sender);
+
+        tokio::spawn(Self::udp_thread(
+            command,
+            setup.clone(),
+            Arc::clone(&self.responses),
+            Arc::clone(&events),
+            receiver,
+            self.state.clone(),
+        ));
+    }
+
+    /// Thread for UDP datagram.
+    /// After sending, every datagram received on bound socket within reply window stored as response
+    async fn udp_thread(
+        command: Command,
+        setup: RequestUdpSetup,
+        responses: Arc<Mutex<Vec<Response>>>,
+        events: Arc<Mutex<Events>>,
+        mut command_channel: Receiver<Message>,
+        executor_state: Arc<Mutex<State>>,
+    ) {
+        let command_execute = match command {
+            Command::EXECUTE(command_execute) => command_execute,
+            Command::TERRMINATE => {
+                *executor_state.lock().unwrap() = State::FREE;
+                return;
+            }
+        };
+
+        let payload = match udp::encode_payload(&command_execute.message, &setup.payload_format) {
+            Ok(val) => val,
+            Err(err) => {
+                events.lock().unwrap().event_error(&format!("Error: {err}"));
+                *executor_state.lock().unwrap() = State::FREE;
+                return;
+            }
+        };
+
+        let (socket, target) = match udp::bind(&command_execute.uri).await {
+            Ok(val) => val,
+            Err(err) => {
+                events.lock().unwrap().event_error(&format!("Error: {err}"));
+                *executor_state.lock().unwrap() = State::FREE;
+                return;
+            }
+        };
+
+        if let Err(err) = socket.send_to(&payload, target).await {
+            events
+                .lock()
+                .unwrap()
+                .event_error(&format!("Error: Could not send UDP datagram: {err}"));
+            *executor_state.lock().unwrap() = State::FREE;
+            return;
+        }
+        events.lock().unwrap().event_info(&format!(
+            "UDP: sent {} bytes to {target}, waiting replies for {} ms",
+            payload.len(),
+            setup.reply_window()
+        ));
+
+        let deadline = time::Instant::now() + Duration::from_millis(setup.reply_window());
+        let mut buffer = vec![0u8; udp::MAX_DATAGRAM_SIZE];
+        let mut replies = 0;
+
+        loop {
+            select! {
+                received = socket.recv_from(&mut buffer) => match received {
+                    Ok((size, source)) => {
+                        replies += 1;
+                        responses.lock().unwrap().push(Response::from_frame(
+                            format!("FROM {source}"),
+                            udp::reply_headers(&source, size),
+                            udp::decode_payload(&buffer[..size], &setup.payload_format),
+                        ));
+                    }
+                    // ICMP port unreachable reported on next receive by some systems
+                    Err(err) => {
+                        events
+                            .lock()
+                            .unwrap()
+                            .event_error(&format!("Error: UDP receive failed: {err}"));
+                        break;
+                    }
+                },
+                _ = time::sleep_until(deadline) => {
+                    events
+                        .lock()
+                        .unwrap()
+                        .event_info(&format!("UDP: reply window closed, replies: {replies}"));
+                    break;
+                }
+                message = command_channel.recv() => match message {
+                    Some(Message::COMMAND(Command::TERRMINATE)) | None => {
+                        events
+                            .lock()
+                            .unwrap()
+                            .event_info(&"UDP: stopped waiting replies.".into());
+                        break;
+                    }
+                    Some(Message::COMMAND(Command::EXECUTE(_))) => {
+                        events
+                            .lock()
+                            .unwrap()
+                            .event_warning(&"UDP: still waiting replies, message ignored".into());
+                    }
+                    Some(Message::RESULT(result)) => {
+                        events.lock().unwrap().event_warning(&format!(
+                            "Error: Received in command channel Result type: {result:#?}"
+                        ));
+                    }
+                }
+            }
+        }
+
+        *executor_state.lock().unwrap() = State::FREE;
+    }
+
+    /// Send command to queue
+    fn send_ws(&mut self, message: Message) {
+        if self.channel_sender.is_none() {
+            *self.state.lock().unwrap() = State::FREE;
+            return;
+        };
+        let _ = block_on(self.channel_sender.as_mut().unwrap().send(message));
+    }
+}
+
+/// Send message in MQTT session. Packet details took from command setup, like in WS modes
+async fn mqtt_send(
+    client: &MqttClient,
+    command_execute: &CommandExecute,
+    settings: &RequestMqttSetup,
+    events: &Arc<Mutex<Events>>,
+) {
+    let setup = match command_execute.setup.mqtt() {
+        Some(val) => val,
+        None => settings,
+    };
+
+    if let Err(err) = client.send(setup, &command_execute.message).await {
+        events.lock().unwrap().event_error(&format!("Error: {err}"));
+    }
+}
+
+/// Connect to server and find called method
+async fn grpc_prepare(
+    command_execute: &CommandExecute,
+    setup: &RequestGrpcSetup,
+) -> std::result::Result<(tonic::client::Grpc<Channel>, MethodDescriptor, MetadataMap), String> {
+    if setup.service.is_empty() || setup.method.is_empty() {
+        return Err("Select gRPC service and method in Setup".into());
+    }
+
+    let metadata = grpc::metadata(&command_execute.headers)?;
+    let channel = grpc::connect(&command_execute.protocol, &command_execute.uri).await?;
+    let pool = if setup.use_reflection {
+        grpc::reflection_pool(channel.clone()).await?
+    } else {
+        grpc::proto_pool(&setup.proto_files, &setup.import_paths())?
+    };
+    let method = grpc::find_method(&pool, &setup.service, &setup.method)?;
+
+    let mut client = tonic::client::Grpc::new(channel);
+    if let Err(err) = client.ready().await {
+        return Err(format!("gRPC channel is not ready: {err}"));
+    }
+
+    Ok((client, method, metadata))
+}
+
+/// Send message to client stream, if it is still open
+async fn grpc_send(
+    sender: &Option<Sender<DynamicMessage>>,
+    method: &MethodDescriptor,
+    json: &str,
+    events: &Arc<Mutex<Events>>,
+) {
+    let sender = match sender {
+        Some(val) => val,
+        None => {
+            events
+                .lock()
+                .unwrap()
+                .event_warning(&"gRPC: client stream already closed, message not sent.".into());
+            return;
+        }
+    };
+
+    let message = match grpc::message_from_json(method.input(), json) {
+        Ok(val) => val,
+        Err(err) => {
+            events.lock().unwrap().event_error(&format!("Error: {err}"));
+            return;
+        }
+    };
+
+    match sender.send(message).await {
+        Ok(_) => events
+            .lock()
+            .unwrap()
+            .event_info(&"gRPC: message sent".into()),
+        Err(_) => events
+            .lock()
+            .unwrap()
+            .event_warning(&"gRPC: call already ended, message not sent.".into()),
+    }
+}
+
+/// Store single reply. Tonic merges trailers into reply metadata, so status shown without them
+fn grpc_read_single(
+    result: std::result::Result<tonic::Response<DynamicMessage>, Status>,
+    responses: &Arc<Mutex<Vec<Response>>>,
+    events: &Arc<Mutex<Events>>,
+) {
+    match result {
+        Ok(response) => {
+            events
+                .lock()
+                .unwrap()
+                .event_info(&"gRPC: received reply".into());
+            let headers = grpc::metadata_to_headers(response.metadata());
+            let mut responses = responses.lock().unwrap();
+            responses.push(Response::from_frame(
+                "message".into(),
+                headers,
+                grpc::message_to_json(response.get_ref()),
+            ));
+            responses.push(Response::from_grpc_status(&Status::ok(""), vec![]));
+        }
+        Err(status) => grpc_store_error(status, responses, events),
+    }
+}
+
+/// Store server stream: initial metadata, each message and final status with trailers
+async fn grpc_read_stream(
+    result: std::result::Result<tonic::Response<Streaming<DynamicMessage>>, Status>,
+    responses: &Arc<Mutex<Vec<Response>>>,
+    events: &Arc<Mutex<Events>>,
+) {
+    let response = match result {
+        Ok(val) => val,
+        Err(status) => return grpc_store_error(status, responses, events),
+    };
+
+    responses.lock().unwrap().push(Response::from_frame(
+        "headers".into(),
+        grpc::metadata_to_headers(response.metadata()),
+        "".into(),
+    ));
+
+    let mut stream = response.into_inner();
+    loop {
+        match stream.message().await {
+            Ok(Some(message)) => {
+                events
+                    .lock()
+                    .unwrap()
+                    .event_info(&"gRPC: received message".into());
+                responses.lock().unwrap().push(Response::from_event(
+                    "message".into(),
+                    grpc::message_to_json(&message),
+                ));
+            }
+            Ok(None) => break,
+            Err(status) => return grpc_store_error(status, responses, events),
+        }
+    }
+
+    let trailers = match stream.trailers().await {
+        Ok(Some(val)) => grpc::metadata_to_headers(&val),
+        Ok(None) => vec![],
+        Err(status) => return grpc_store_error(status, responses, events),
+    };
+    events
+        .lock()
+        .unwrap()
+        .event_info(&"gRPC: call completed".into());
+    responses
+        .lock()
+        .unwrap()
+        .push(Response::from_grpc_status(&Status::ok(""), trailers));
+}
+
+fn grpc_store_error(
+    status: Status,
+    responses: &Arc<Mutex<Vec<Response>>>,
+    events: &Arc<Mutex<Events>>,
+) {
+    events.lock().unwrap().event_error(&format!(
+        "Error: gRPC call failed. Status: {:?}. Message: {}",
+        status.code(),
+        status.message()
+    ));
+    let trailers = grpc::metadata_to_headers(status.metadata());
+    responses
+        .lock()
+        .unwrap()
+        .push(Response::from_grpc_status(&status, trailers));
+}
+
+/// Prepare text to send in WS session, based on session mode.
+/// Per message details (like event name) took from command setup, session details - from connection settings
+fn encode_ws_message(
+    command_execute: &CommandExecute,
+    settings: &RequestWsSetup,
+) -> std::result::Result<String, String> {
+    match settings.mode {
+        WsMode::RAW => Ok(command_execute.message.clone()),
+        WsMode::SOCKETIO => {
+            let event = match command_execute.setup.ws() {
+                Some(setup) => setup.socket_io.event.clone(),
+                None => settings.socket_io.event.clone(),
+            };
+            socket_io::event_packet(
+                &settings.socket_io.namespace,
+                &event,
+                &command_execute.message,
+            )
+        }
+        WsMode::STOMP => {
+            let stomp = match command_execute.setup.ws() {
+                Some(setup) => &setup.stomp,
+                None => &settings.stomp,
+            };
+            stomp::client_frame(
+                &stomp.command,
+                &stomp.destination,
+                &stomp.headers,
+                &command_execute.message,
+            )
+        }
+        WsMode::GRAPHQL => graphql::subscribe(&command_execute.body.graphql),
+        WsMode::JSONRPC => {
+            json_rpc::envelope(&command_execute.body.json_rpc).map(|envelope| envelope.to_string())
+        }
+    }
+}
+
+/// React on incoming Socket.IO packet: answear on handshake and pings, store events to responses
+async fn process_socket_io_text(
+    text: &str,
+    settings: &RequestWsSetup,
+    write: &mut WsSink,
+    pending_message: &mut Option<String>,
+    responses: &Arc<Mutex<Vec<Response>>>,
+    events: &Arc<Mutex<Events>>,
+) {
+    let answear = match socket_io::parse_packet(text) {
+        SocketIoPacket::Open => {
+            events
+                .lock()
+                .unwrap()
+                .event_info(&"Socket.IO: handshake done, connecting to namespace...".into());
+            match socket_io::connect_packet(&settings.socket_io.namespace, &settings.socket_io.auth)
+            {
+                Ok(val) => Some(val),
+                Err(err) => {
+                    events.lock().unwrap().event_error(&format!("Error: {err}"));
+                    None
+                }
+            }
+        }
+        SocketIoPacket::Ping => Some(socket_io::pong_packet()),
+        SocketIoPacket::Connect { namespace } => {
+            events
+                .lock()
+                .unwrap()
+                .event_info(&format!("Socket.IO: connected to namespace {namespace}"));
+            responses
+                .lock()
+                .unwrap()
+                .push(Response::from_event("connect".into(), namespace));
+            pending_message.take()
+        }
+        SocketIoPacket::Disconnect { namespace } => {
+            events.lock().unwrap().event_warning(&format!(
+                "Socket.IO: disconnected from namespace {namespace}"
+            ));
+            responses
+                .lock()
+                .unwrap()
+                .push(Response::from_event("disconnect".into(), namespace));
+            None
+        }
+        SocketIoPacket::ConnectError { namespace, data } => {
+            events.lock().unwrap().event_error(&format!(
+                "Error: Socket.IO could not connect to namespace {namespace}. Error: {data}"
+            ));
+            responses.lock().unwrap().push(Response::from_event(
+                "connect_error".into(),
+                data.to_string(),
+            ));
+            None
+        }
+        SocketIoPacket::Event {
+            namespace,
+            event,
+            args,
+            ack_id,
+        } => {
+            events
+                .lock()
+                .unwrap()
+                .event_info(&format!("Socket.IO: received event {event}"));
+            // Server callback waits for acknowledgement, answered right away
+            let label = match ack_id {
+                Some(ack_id) => format!("{event} (ack {ack_id})"),
+                None => event,
+            };
+            responses.lock().unwrap().push(Response::from_event(
+                label,
+                socket_io::args_to_string(&args),
+            ));
+            ack_id.map(|ack_id| socket_io::ack_packet(&namespace, ack_id))
+        }
+        SocketIoPacket::Ack {
+            namespace: _,
+            ack_id,
+            args,
+        } => {
+            responses.lock().unwrap().push(Response::from_event(
+                format!("ack {ack_id}"),
+                socket_io::args_to_string(&args),
+            ));
+            None
+        }
+        SocketIoPacket::Close => {
+            events
+                .lock()
+                .unwrap()
+                .event_warning(&"Socket.IO: server requested close.".into());
+            None
+        }
+        SocketIoPacket::Noop => None,
+        SocketIoPacket::Unknown(text) => {
+            events
+                .lock()
+                .unwrap()
+                .event_warning(&format!("Socket.IO: could not parse packet: {text}"));
+            responses
+                .lock()
+                .unwrap()
+                .push(Response::from_utf8_bytes(text.into()));
+            None
+        }
+    };
+
+    if let Some(answear) = answear {
+        if let Err(err) = write.send(TokioMessage::text(answear)).await {
+            events.lock().unwrap().event_error(&format!(
+                "Error: Could not send Socket.IO packet. Error: {err}"
+            ));
+        }
+    }
+}
+
+/// Protocols on top of WS with client side handshake start it right after connection
+async fn start_ws_session(
+    uri: &str,
+    settings: &RequestWsSetup,
+    write: &mut WsSink,
+    events: &Arc<Mutex<Events>>,
+) {
+    match settings.mode {
+        WsMode::STOMP => stomp_connect(uri, settings, write, events).await,
+        WsMode::GRAPHQL => {
+            let message = match graphql::connection_init(&settings.graphql.connection_params) {
+                Ok(val) => val,
+                Err(err) => {
+                    events.lock().unwrap().event_error(&format!("Error: {err}"));
+                    return;
+                }
+            };
+            if let Err(err) = write.send(TokioMessage::text(message)).await {
+                events.lock().unwrap().event_error(&format!(
+                    "Error: Could not send GraphQL connection_init. Error: {err}"
+                ));
+            }
+        }
+        WsMode::RAW | WsMode::SOCKETIO | WsMode::JSONRPC => {}
+    }
+}
+
+/// Store incoming JSON-RPC messages to responses, labeled with method of call they reply on
+fn process_json_rpc_text(
+    text: &str,
+    calls: &mut JsonRpcCalls,
+    responses: &Arc<Mutex<Vec<Response>>>,
+    events: &Arc<Mutex<Events>>,
+) {
+    let messages = match json_rpc::parse_messages(text) {
+        Ok(val) => val,
+        Err(err) => {
+            events
+                .lock()
+                .unwrap()
+                .event_warning(&format!("JSON-RPC: {err}"));
+            responses
+                .lock()
+                .unwrap()
+                .push(Response::from_utf8_bytes(text.into()));
+            return;
+        }
+    };
+
+    for message in messages {
+        let label = message.label(calls);
+        let response = match message {
+            JsonRpcMessage::Notification {
+                method: _,
+                params: _,
+            } => Response::from_notification(label, message.data()),
+            JsonRpcMessage::Error { id: _, error: _ } => {
+                events
+                    .lock()
+                    .unwrap()
+                    .event_error(&format!("Error: JSON-RPC {label}: {}", message.data()));
+                Response::from_event(label, message.data())
+            }
+            _ => Response::from_event(label, message.data()),
+        };
+
+        // Reply received, call not pending anymore
+        if let Some(id) = message.id() {
+            calls.remove(id);
+        }
+
+        responses.lock().unwrap().push(response);
+    }
+}
+
+/// React on incoming graphql-transport-ws messages: send pending subscription after ack, store results to responses
+async fn process_graphql_ws_text(
+    text: &str,
+    write: &mut WsSink,
+    pending_message: &mut Option<String>,
+    responses: &Arc<Mutex<Vec<Response>>>,
+    events: &Arc<Mutex<Events>>,
+) {
+    let answear = match graphql::parse_message(text) {
+        GraphQlWsMessage::ConnectionAck => {
+            events
+                .lock()
+                .unwrap()
+                .event_info(&"GraphQL: connection acknowledged".into());
+            pending_message.take()
+        }
+        GraphQlWsMessage::Ping => Some(graphql::pong()),
+        GraphQlWsMessage::Pong => None,
+        GraphQlWsMessage::Next { id, payload } => {
+            events
+                .lock()
+                .unwrap()
+                .event_info(&"GraphQL: received subscription result".into());
+            responses.lock().unwrap().push(Response::from_event(
+                format!("next {id}"),
+                payload.to_string(),
+            ));
+            None
+        }
+        GraphQlWsMessage::Error { id, payload } => {
+            events.lock().unwrap().event_error(&format!(
+                "Error: GraphQL subscription {id} failed. Errors: {payload}"
+            ));
+            responses.lock().unwrap().push(Response::from_event(
+                format!("error {id}"),
+                payload.to_string(),
+            ));
+            None
+        }
+        GraphQlWsMessage::Complete { id } => {
+            events
+                .lock()
+                .unwrap()
+                .event_info(&format!("GraphQL: subscription {id} completed"));
+            responses
+                .lock()
+                .unwrap()
+                .push(Response::from_event(format!("complete {id}"), "".into()));
+            None
+        }
+        GraphQlWsMessage::Unknown(text) => {
+            events
+                .lock()
+                .unwrap()
+                .event_warning(&format!("GraphQL: unknown message: {text}"));
+            responses
+                .lock()
+                .unwrap()
+                .push(Response::from_utf8_bytes(text.into()));
+            None
+        }
+    };
+
+    if let Some(answear) = answear {
+        if let Err(err) = write.send(TokioMessage::text(answear)).await {
+            events.lock().unwrap().event_error(&format!(
+                "Error: Could not send GraphQL message. Error: {err}"
+            ));
+        }
+    }
+}
+
+/// Send STOMP CONNECT frame. Host defaults to host from uri
+async fn stomp_connect(
+    uri: &str,
+    settings: &RequestWsSetup,
+    write: &mut WsSink,
+    events: &Arc<Mutex<Events>>,
+) {
+    let host = if settings.stomp.host.is_empty() {
+        let authority = uri.split(['/', '?']).next().unwrap_or_default();
+        authority.split(':').next().unwrap_or_default().to_string()
+    } else {
+        settings.stomp.host.clone()
+    };
+
+    events
+        .lock()
+        .unwrap()
+        .event_info(&"STOMP: connecting...".into());
+
+    let frame = stomp::connect_frame(&host, &settings.stomp.login, &settings.stomp.passcode);
+    if let Err(err) = write.send(TokioMessage::text(frame)).await {
+        events.lock().unwrap().event_error(&format!(
+            "Error: Could not send STOMP CONNECT frame. Error: {err}"
+        ));
+    }
+}
+
+/// React on incoming STOMP frames: send pending message after CONNECTED, store frames to responses
+async fn process_stomp_text(
+    text: &str,
+    write: &mut WsSink,
+    pending_message: &mut Option<String>,
+    responses: &Arc<Mutex<Vec<Response>>>,
+    events: &Arc<Mutex<Events>>,
+) {
+    let frames = match stomp::parse_frames(text) {
+        Ok(val) => val,
+        Err(err) => {
+            events
+                .lock()
+                .unwrap()
+                .event_warning(&format!("STOMP: could not parse frame: {err}"));
+            responses
+                .lock()
+                .unwrap()
+                .push(Response::from_utf8_bytes(text.into()));
+            return;
+        }
+    };
+
+    for frame in frames {
+        let event = match frame.command.as_str() {
+            "CONNECTED" => {
+                events.lock().unwrap().event_info(&format!(
+                    "STOMP: connected. Version: {}",
+                    frame.header("version").unwrap_or("1.0")
+                ));
+
+                if let Some(message) = pending_message.take() {
+                    if let Err(err) = write.send(TokioMessage::text(message)).await {
+                        events.lock().unwrap().event_error(&format!(
+                            "Error: Could not send STOMP frame. Error: {err}"
+                        ));
+                    }
+                }
+                "CONNECTED".to_string()
+            }
+            "MESSAGE" => {
+                events
+                    .lock()
+                    .unwrap()
+                    .event_info(&"STOMP: received message".into());
+                format!(
+                    "MESSAGE {}",
+                    frame.header("destination").unwrap_or_default()
+                )
+            }
+            "ERROR" => {
+                events.lock().unwrap().event_error(&format!(
+                    "Error: STOMP broker error. Message: {}",
+                    frame.header("message").unwrap_or_default()
+                ));
+                "ERROR".to_string()
+            }
+            other => other.to_string(),
+        };
+
+        responses
+            .lock()
+            .unwrap()
+            .push(Response::from_frame(event, frame.headers, frame.body));
+    }
+}
+
+/// Read WS stream until peer Close frame.
+/// Other messages are skipped, since session is closing
+async fn wait_ws_close(read: &mut WsSource) -> Option<CloseFrame> {
+    loop {
+        match read.next().await {
+            Some(Ok(TokioMessage::Close(close_frame))) => return close_frame,
+            Some(Ok(_)) => continue,
+            Some(Err(_)) | None => return None,
+        }
+    }
+}
+
+/// Authorization headers for request. OAuth2 token acquired or refreshed when needed
+async fn auth_headers(
+    auth: &RequestAuth,
+    events: &Arc<Mutex<Events>>,
+) -> std::result::Result<Vec<Header>, String> {
+    if auth.auth_type == AuthType::OAUTH2 {
+        let token = oauth2::access_token(&auth.oauth2, events).await?;
+        return Ok(vec![Header {
+            key: "Authorization".into(),
+            value: format!("Bearer {token}"),
+        }]);
+    }
+    // Signed over whole request right before send, or answered on challenge
+    if matches!(
+        auth.auth_type,
+        AuthType::AWSSIGV4 | AuthType::HMAC | AuthType::DIGEST
+    ) {
+        return Ok(vec![]);
+    }
+
+    Ok(auth.headers())
+}
+
+/// Custom headers, authorization headers and query params on request.
+/// Authorization goes over custom headers with same name
+async fn with_auth(
+    mut request: RequestBuilder,
+    headers: Vec<Header>,
+    auth: &RequestAuth,
+    events: &Arc<Mutex<Events>>,
+) -> std::result::Result<RequestBuilder, String> {
+    let auth_headers = auth_headers(auth, events).await?;
+    for header in headers {
+        if auth_headers
+            .iter()
+            .any(|auth| auth.key.eq_ignore_ascii_case(&header.key))
+        {
+            continue;
+        }
+        request = request.header(header.key, header.value);
+    }
+    for header in auth_headers {
+        request = request.header(header.key, header.value);
+    }
+
+    let auth_query: Vec<(String, String)> = auth
+        .query_params()
+        .into_iter()
+        .map(|param| (param.key, param.value))
+        .collect();
+    if !auth_query.is_empty() {
+        request = request.query(&auth_query);
+    }
+
+    Ok(request)
+}
+
+/// Signatures cover final url, headers and body, so computed on built request
+fn sign_request(
+    request: &mut reqwest::Request,
+    auth: &RequestAuth,
+) -> std::result::Result<(), String> {
+    let payload = match request.body() {
+        Some(body) => body.as_bytes(),
+        None => Some(&[][..]),
+    };
+    let headers = signature_headers(
+        auth,
+        request.method().as_str(),
+        request.url(),
+        request.headers(),
+        payload,
+    )?;
+    insert_headers(request.headers_mut(), headers)
+}
+
+/// Headers of schemes signing whole request. Empty for other schemes
+fn signature_headers(
+    auth: &RequestAuth,
+    method: &str,
+    url: &reqwest::Url,
+    headers: &HeaderMap,
+    payload: Option<&[u8]>,
+) -> std::result::Result<Vec<Header>, String> {
+    match auth.auth_type {
+        AuthType::AWSSIGV4 => aws_sigv4::sign(&auth.aws, method, url, headers, payload, Utc::now()),
+        AuthType::HMAC => hmac_signer::sign(&auth.hmac, method, url, headers, payload, Utc::now()),
+        _ => Ok(vec![]),
+    }
+}
+
+/// Set headers on built request, replacing ones with same name
+fn insert_headers(map: &mut HeaderMap, headers: Vec<Header>) -> std::result::Result<(), String> {
+    for header in headers {
+        let key = HeaderName::from_bytes(header.key.as_bytes()).map_err(|err| err.to_string())?;
+        let value = HeaderValue::from_str(&header.value).map_err(|err| err.to_string())?;
+        map.insert(key, value);
+    }
+    Ok(())
+}
+
+/// Append params to uri query, keeping existing ones
+fn with_query_params(uri: String, params: &[Header]) -> String {
+    if params.is_empty() {
+        return uri;
+    }
+
+    match reqwest::Url::parse(&uri) {
+        Ok(mut url) => {
+            for param in params {
+                url.query_pairs_mut().append_pair(&param.key, &param.value);
+            }
+            url.to_string()
+        }
+        Err(_) => uri,
     }
 }
 
@@ -710,10 +2469,7 @@ async fn get_ws_io(
     reconnects_timeout: u64,
     executor_state: Arc<Mutex<State>>,
     events: Arc<Mutex<Events>>,
-) -> Option<(
-    SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, tokio_tungstenite::tungstenite::Message>,
-    SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
-)> {
+) -> Option<(WsSink, WsSource)> {
     let mut current_connection_retry = 0;
 
     loop {
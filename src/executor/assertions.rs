@@ -0,0 +1,279 @@
+/// Response checks declared without code, evaluated after each send
+use regex::Regex;
+use serde_json::Value;
+
+use crate::{
+    executor::captures::json_path,
+    states::main_page::{
+        request::request_data::{Assertion, AssertionKind},
+        response::{AssertionResult, Response},
+    },
+};
+
+/// Results of assertions for response. Rows without required fields failed as incomplete
+pub fn evaluate(assertions: &[Assertion], response: &Response) -> Vec<AssertionResult> {
+    assertions
+        .iter()
+        .map(|assertion| {
+            let (is_passed, message) =
+                match incomplete(assertion).map_or_else(|| check(assertion, response), Err) {
+                    Ok(actual) => (true, actual),
+                    Err(err) => (false, err),
+                };
+            AssertionResult {
+                name: name(assertion),
+                is_passed,
+                message,
+            }
+        })
+        .collect()
+}
+
+/// Human readable assertion, like `Status equals 200`
+pub fn name(assertion: &Assertion) -> String {
+    let mut name = assertion.kind.to_string();
+    for part in [&assertion.target, &assertion.expected] {
+        if !part.trim().is_empty() {
+            name.push(' ');
+            name.push_str(part.trim());
+        }
+    }
+    name
+}
+
+/// Reason when required field of row is empty
+fn incomplete(assertion: &Assertion) -> Option<String> {
+    let kind = &assertion.kind;
+    if kind.target_hint().is_some() && assertion.target.trim().is_empty() {
+        Some("Incomplete assertion: target is empty".into())
+    } else if kind.expected_hint().is_some() && assertion.expected.trim().is_empty() {
+        Some("Incomplete assertion: expected value is empty".into())
+    } else {
+        None
+    }
+}
+
+/// Ok with actual value when passed, Err with reason otherwise
+fn check(assertion: &Assertion, response: &Response) -> Result<String, String> {
+    let target = assertion.target.trim();
+    let expected = assertion.expected.trim();
+    let actual_code = format!("Actual: {}", response.code);
+
+    match assertion.kind {
+        AssertionKind::STATUS => {
+            let code: usize = expected
+                .parse()
+                .map_err(|_| format!("Invalid status {expected}"))?;
+            if response.code == code {
+                Ok(actual_code)
+            } else {
+                Err(actual_code)
+            }
+        }
+        AssertionKind::STATUSRANGE => {
+            let invalid = || format!("Invalid range {expected}, expected like 200-299");
+            let (min, max) = expected.split_once('-').ok_or_else(invalid)?;
+            let min: usize = min.trim().parse().map_err(|_| invalid())?;
+            let max: usize = max.trim().parse().map_err(|_| invalid())?;
+            if (min..=max).contains(&response.code) {
+                Ok(actual_code)
+            } else {
+                Err(actual_code)
+            }
+        }
+        AssertionKind::HEADEREXISTS => header(response, target)
+            .map(|value| format!("Actual: {value}"))
+            .ok_or(format!("Header {target} not found")),
+        AssertionKind::HEADERMATCHES => {
+            let regex = Regex::new(expected).map_err(|err| format!("Invalid regex. {err}"))?;
+            let value = header(response, target).ok_or(format!("Header {target} not found"))?;
+            if regex.is_match(value) {
+                Ok(format!("Actual: {value}"))
+            } else {
+                Err(format!("Actual: {value}"))
+            }
+        }
+        AssertionKind::JSONEQUALS | AssertionKind::JSONCONTAINS | AssertionKind::JSONTYPE => {
+            let json: Value = serde_json::from_str(&response.data.raw)
+                .map_err(|_| "Response body is not JSON".to_string())?;
+            let value = json_path(&json, target)?;
+            let actual = format!("Actual: {value}");
+            // Not JSON expected value compared as text
+            let expected_value =
+                serde_json::from_str(expected).unwrap_or(Value::String(expected.to_string()));
+
+            let is_passed = match assertion.kind {
+                AssertionKind::JSONEQUALS => {
+                    *value == expected_value || value.as_str() == Some(expected)
+                }
+                AssertionKind::JSONCONTAINS => match value {
+                    Value::Array(items) => items.contains(&expected_value),
+                    Value::String(text) => text.contains(expected),
+                    other => other.to_string().contains(expected),
+                },
+                _ => type_name(value) == expected.to_lowercase(),
+            };
+
+            if is_passed {
+                Ok(actual)
+            } else if assertion.kind == AssertionKind::JSONTYPE {
+                Err(format!("Actual type: {}", type_name(value)))
+            } else {
+                Err(actual)
+            }
+        }
+        AssertionKind::BODYMATCHES => {
+            let regex = Regex::new(expected).map_err(|err| format!("Invalid regex. {err}"))?;
+            match regex.find(&response.data.raw) {
+                Some(found) => Ok(format!("Matched: {}", found.as_str())),
+                None => Err("Body did not match".into()),
+            }
+        }
+        AssertionKind::TIMEBELOW => {
+            let limit: u128 = expected
+                .parse()
+                .map_err(|_| format!("Invalid milliseconds {expected}"))?;
+            let duration = response
+                .duration
+                .ok_or("Response time unknown".to_string())?
+                .as_millis();
+            if duration < limit {
+                Ok(format!("Actual: {duration} ms"))
+            } else {
+                Err(format!("Actual: {duration} ms"))
+            }
+        }
+    }
+}
+
+fn header<'a>(response: &'a Response, name: &str) -> Option<&'a str> {
+    response
+        .data
+        .headers
+        .iter()
+        .find(|header| header.key.trim().eq_ignore_ascii_case(name))
+        .map(|header| header.value.as_str())
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::states::main_page::generics::Header;
+
+    use super::*;
+
+    fn response() -> Response {
+        let mut response = Response::from_frame(
+            "".into(),
+            vec![Header {
+                key: "Content-Type".into(),
+                value: "application/json; charset=utf-8".into(),
+            }],
+            r#"{"id": 7, "name": "frapi", "tags": ["a", "b"], "owner": null}"#.into(),
+        );
+        response.code = 201;
+        response.duration = Some(Duration::from_millis(120));
+        response
+    }
+
+    fn assertion(kind: AssertionKind, target: &str, expected: &str) -> Assertion {
+        Assertion {
+            kind,
+            target: target.into(),
+            expected: expected.into(),
+        }
+    }
+
+    fn passed(assertions: &[Assertion]) -> Vec<bool> {
+        evaluate(assertions, &response())
+            .iter()
+            .map(|result| result.is_passed)
+            .collect()
+    }
+
+    #[test]
+    fn status() {
+        let assertions = [
+            assertion(AssertionKind::STATUS, "", "201"),
+            assertion(AssertionKind::STATUS, "", "200"),
+            assertion(AssertionKind::STATUSRANGE, "", "200-299"),
+            assertion(AssertionKind::STATUSRANGE, "", "300 - 399"),
+            assertion(AssertionKind::STATUS, "", "ok"),
+        ];
+        assert_eq!(passed(&assertions), [true, false, true, false, false]);
+    }
+
+    #[test]
+    fn headers() {
+        let assertions = [
+            assertion(AssertionKind::HEADEREXISTS, "content-type", ""),
+            assertion(AssertionKind::HEADEREXISTS, "ETag", ""),
+            assertion(
+                AssertionKind::HEADERMATCHES,
+                "Content-Type",
+                "^application/json",
+            ),
+            assertion(AssertionKind::HEADERMATCHES, "Content-Type", "xml"),
+        ];
+        assert_eq!(passed(&assertions), [true, false, true, false]);
+    }
+
+    #[test]
+    fn json() {
+        let assertions = [
+            assertion(AssertionKind::JSONEQUALS, "$.id", "7"),
+            assertion(AssertionKind::JSONEQUALS, "$.name", "frapi"),
+            assertion(AssertionKind::JSONEQUALS, "$.name", "\"frapi\""),
+            assertion(AssertionKind::JSONEQUALS, "$.id", "8"),
+            assertion(AssertionKind::JSONCONTAINS, "$.tags", "b"),
+            assertion(AssertionKind::JSONCONTAINS, "$.name", "rap"),
+            assertion(AssertionKind::JSONCONTAINS, "$.tags", "c"),
+            assertion(AssertionKind::JSONTYPE, "$.tags", "array"),
+            assertion(AssertionKind::JSONTYPE, "$.owner", "Null"),
+            assertion(AssertionKind::JSONTYPE, "$.id", "string"),
+            assertion(AssertionKind::JSONEQUALS, "$.missing", "1"),
+        ];
+        assert_eq!(
+            passed(&assertions),
+            [true, true, true, false, true, true, false, true, true, false, false]
+        );
+    }
+
+    #[test]
+    fn body_and_time() {
+        let assertions = [
+            assertion(AssertionKind::BODYMATCHES, "", r#""id":\s*\d+"#),
+            assertion(AssertionKind::BODYMATCHES, "", "error"),
+            assertion(AssertionKind::TIMEBELOW, "", "500"),
+            assertion(AssertionKind::TIMEBELOW, "", "100"),
+        ];
+        assert_eq!(passed(&assertions), [true, false, true, false]);
+    }
+
+    #[test]
+    fn incomplete_failed() {
+        let results = evaluate(
+            &[
+                assertion(AssertionKind::STATUS, "", " "),
+                assertion(AssertionKind::JSONEQUALS, "", "7"),
+            ],
+            &response(),
+        );
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|result| !result.is_passed));
+        assert!(results[0].message.starts_with("Incomplete assertion"));
+        assert!(results[1].message.starts_with("Incomplete assertion"));
+    }
+}
@@ -0,0 +1,389 @@
+/// AWS Signature Version 4 request signing
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::{header::HeaderMap, Url};
+use sha2::{Digest, Sha256};
+
+use crate::states::main_page::{generics::Header, request::request_data::AwsSigV4Auth};
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+/// Payload hash for bodies, which could not be read before send
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/// Headers signed besides host and x-amz-*
+const SIGNED_HEADERS: [&str; 2] = ["content-type", "content-md5"];
+
+/// Headers to insert into request: date, payload hash, session token and Authorization.
+/// payload - body bytes, None for streamed body
+pub fn sign(
+    config: &AwsSigV4Auth,
+    method: &str,
+    url: &Url,
+    headers: &HeaderMap,
+    payload: Option<&[u8]>,
+    now: DateTime<Utc>,
+) -> Result<Vec<Header>, String> {
+    let access_key = config.access_key.trim();
+    let region = config.region.trim();
+    let service = config.service.trim();
+    if access_key.is_empty() || config.secret_key.is_empty() || region.is_empty() {
+        return Err("AWS Signature: access key, secret key and region are required".into());
+    }
+    if service.is_empty() {
+        return Err("AWS Signature: service is required".into());
+    }
+
+    let host = match url.host_str() {
+        Some(host) => match url.port() {
+            Some(port) => format!("{host}:{port}"),
+            None => host.to_string(),
+        },
+        None => return Err(format!("AWS Signature: no host in url {url}")),
+    };
+
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let payload_hash = match payload {
+        Some(payload) => hex(&Sha256::digest(payload)),
+        None => UNSIGNED_PAYLOAD.to_string(),
+    };
+
+    let mut added = vec![
+        Header {
+            key: "X-Amz-Date".into(),
+            value: amz_date.clone(),
+        },
+        Header {
+            key: "X-Amz-Content-Sha256".into(),
+            value: payload_hash.clone(),
+        },
+    ];
+    if !config.session_token.trim().is_empty() {
+        added.push(Header {
+            key: "X-Amz-Security-Token".into(),
+            value: config.session_token.trim().to_string(),
+        });
+    }
+
+    // Lowercase name -> trimmed values, sorted by name
+    let mut canonical_headers: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (name, value) in headers {
+        let name = name.as_str().to_lowercase();
+        if !name.starts_with("x-amz-") && !SIGNED_HEADERS.contains(&name.as_str()) {
+            continue;
+        }
+        let value = String::from_utf8_lossy(value.as_bytes()).to_string();
+        canonical_headers
+            .entry(name)
+            .or_default()
+            .push(collapse_spaces(&value));
+    }
+    canonical_headers.insert("host".into(), vec![host]);
+    for header in &added {
+        canonical_headers.insert(header.key.to_lowercase(), vec![header.value.clone()]);
+    }
+
+    let (canonical_request, signed_headers) = canonical_request(
+        method,
+        url,
+        service != "s3",
+        &canonical_headers,
+        &payload_hash,
+    );
+    let scope = format!("{date}/{region}/{service}/aws4_request");
+    let signature = signature(&config.secret_key, &amz_date, &scope, &canonical_request);
+
+    added.push(Header {
+        key: "Authorization".into(),
+        value: format!(
+            "{ALGORITHM} Credential={access_key}/{scope}, SignedHeaders={signed_headers}, Signature={signature}"
+        ),
+    });
+
+    Ok(added)
+}
+
+/// Canonical request and list of signed headers.
+/// headers - lowercase name -> trimmed values, sorted by name
+fn canonical_request(
+    method: &str,
+    url: &Url,
+    encode_twice: bool,
+    headers: &BTreeMap<String, Vec<String>>,
+    payload_hash: &str,
+) -> (String, String) {
+    let signed_headers = headers.keys().cloned().collect::<Vec<String>>().join(";");
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        method.to_uppercase(),
+        canonical_uri(url, encode_twice),
+        canonical_query(url),
+        headers
+            .iter()
+            .map(|(name, values)| format!("{name}:{}\n", values.join(",")))
+            .collect::<String>(),
+        signed_headers,
+        payload_hash
+    );
+    (canonical_request, signed_headers)
+}
+
+/// Hex signature of canonical request.
+/// scope - `date/region/service/aws4_request`
+fn signature(secret_key: &str, amz_date: &str, scope: &str, canonical_request: &str) -> String {
+    let string_to_sign = format!(
+        "{ALGORITHM}\n{amz_date}\n{scope}\n{}",
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let mut key = format!("AWS4{secret_key}").into_bytes();
+    for part in scope.split('/') {
+        key = hmac_sha256(&key, part);
+    }
+    hex(&hmac_sha256(&key, &string_to_sign))
+}
+
+/// Path with URI-encoded segments. S3 encodes them once, other services - twice
+fn canonical_uri(url: &Url, encode_twice: bool) -> String {
+    let path = url
+        .path()
+        .split('/')
+        .map(|segment| {
+            let encoded = uri_encode(&percent_decode(segment));
+            if encode_twice {
+                uri_encode(encoded.as_bytes())
+            } else {
+                encoded
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("/");
+
+    if path.is_empty() {
+        "/".into()
+    } else {
+        path
+    }
+}
+
+/// Query params encoded and sorted by name, then by value.
+/// Raw query used, as form decoding turns `+` into space
+fn canonical_query(url: &Url) -> String {
+    let mut params: Vec<(String, String)> = url
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|param| !param.is_empty())
+        .map(|param| {
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+            (
+                uri_encode(&percent_decode(key)),
+                uri_encode(&percent_decode(value)),
+            )
+        })
+        .collect();
+    params.sort();
+
+    params
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+/// Percent encoding of everything except unreserved characters
+fn uri_encode(value: &[u8]) -> String {
+    value
+        .iter()
+        .map(|&byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+fn percent_decode(value: &str) -> Vec<u8> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let digits = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or_default();
+            if let Ok(byte) = u8::from_str_radix(digits, 16) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    decoded
+}
+
+/// Header value trimmed, with inner spaces sequences collapsed to one
+fn collapse_spaces(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    // HMAC accepts key of any length
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).unwrap();
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Credentials and time of AWS SigV4 test suite
+    const SECRET_KEY: &str = "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY";
+    const AMZ_DATE: &str = "20150830T123600Z";
+    const SCOPE: &str = "20150830/us-east-1/service/aws4_request";
+    const EMPTY_HASH: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    fn suite_headers(extra: &[(&str, &str)]) -> BTreeMap<String, Vec<String>> {
+        let mut headers = BTreeMap::new();
+        headers.insert("host".into(), vec!["example.amazonaws.com".into()]);
+        headers.insert("x-amz-date".into(), vec![AMZ_DATE.into()]);
+        for (name, value) in extra {
+            headers.insert(name.to_string(), vec![value.to_string()]);
+        }
+        headers
+    }
+
+    /// Signature of suite request, path encoded once as suite expects
+    fn suite_signature(
+        method: &str,
+        url: &str,
+        headers: &BTreeMap<String, Vec<String>>,
+        payload_hash: &str,
+    ) -> String {
+        let url = Url::parse(url).unwrap();
+        let (canonical_request, _) = canonical_request(method, &url, false, headers, payload_hash);
+        signature(SECRET_KEY, AMZ_DATE, SCOPE, &canonical_request)
+    }
+
+    #[test]
+    fn get_vanilla() {
+        let headers = suite_headers(&[]);
+        let url = Url::parse("https://example.amazonaws.com/").unwrap();
+        let (canonical_request, signed_headers) =
+            canonical_request("GET", &url, false, &headers, EMPTY_HASH);
+        assert_eq!(
+            canonical_request,
+            format!(
+                "GET\n/\n\nhost:example.amazonaws.com\nx-amz-date:{AMZ_DATE}\n\nhost;x-amz-date\n{EMPTY_HASH}"
+            )
+        );
+        assert_eq!(signed_headers, "host;x-amz-date");
+        assert_eq!(
+            suite_signature(
+                "GET",
+                "https://example.amazonaws.com/",
+                &headers,
+                EMPTY_HASH
+            ),
+            "5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+    }
+
+    #[test]
+    fn get_vanilla_query_order() {
+        let headers = suite_headers(&[]);
+        for (url, expected) in [
+            (
+                "https://example.amazonaws.com/?Param2=value2&Param1=value1",
+                "b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500",
+            ),
+            (
+                "https://example.amazonaws.com/?Param1=value2&Param1=Value1",
+                "eedbc4e291e521cf13422ffca22be7d2eb8146eecf653089df300a15b2382bd1",
+            ),
+            (
+                "https://example.amazonaws.com/?Param1=value2&Param1=value1",
+                "5772eed61e12b33fae39ee5e7012498b51d56abc0abb7c60486157bd471c4694",
+            ),
+        ] {
+            assert_eq!(suite_signature("GET", url, &headers, EMPTY_HASH), expected);
+        }
+    }
+
+    #[test]
+    fn get_space() {
+        let headers = suite_headers(&[]);
+        assert_eq!(
+            suite_signature(
+                "GET",
+                "https://example.amazonaws.com/example space/",
+                &headers,
+                EMPTY_HASH
+            ),
+            "652487583200325589f1fba4c7e578f72c47cb61beeca81406b39ddec1366741"
+        );
+
+        let url = Url::parse("https://example.amazonaws.com/example space/").unwrap();
+        assert_eq!(canonical_uri(&url, false), "/example%20space/");
+        assert_eq!(canonical_uri(&url, true), "/example%2520space/");
+    }
+
+    #[test]
+    fn post_x_www_form_urlencoded() {
+        let headers = suite_headers(&[("content-type", "application/x-www-form-urlencoded")]);
+        let payload_hash = hex(&Sha256::digest(b"Param1=value1"));
+        assert_eq!(
+            suite_signature(
+                "POST",
+                "https://example.amazonaws.com/",
+                &headers,
+                &payload_hash
+            ),
+            "ff11897932ad3f4e8b18135d722051e5ac45fc38421b1da7b9d196a0fe09473a"
+        );
+    }
+
+    #[test]
+    fn query_plus_kept() {
+        let url = Url::parse("https://example.amazonaws.com/?b=1+2&a=%2B&c=x%20y&flag").unwrap();
+        assert_eq!(canonical_query(&url), "a=%2B&b=1%2B2&c=x%20y&flag=");
+    }
+
+    #[test]
+    fn sign_adds_headers() {
+        let config = AwsSigV4Auth {
+            access_key: "AKIDEXAMPLE".into(),
+            secret_key: SECRET_KEY.into(),
+            session_token: "".into(),
+            region: "us-east-1".into(),
+            service: "service".into(),
+        };
+        let now = DateTime::parse_from_rfc3339("2015-08-30T12:36:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let url = Url::parse("https://example.amazonaws.com/").unwrap();
+        let headers = sign(&config, "GET", &url, &HeaderMap::new(), Some(b""), now).unwrap();
+
+        let value = |name: &str| {
+            headers
+                .iter()
+                .find(|header| header.key == name)
+                .map(|header| header.value.clone())
+                .unwrap()
+        };
+        assert_eq!(value("X-Amz-Date"), AMZ_DATE);
+        assert_eq!(value("X-Amz-Content-Sha256"), EMPTY_HASH);
+        assert!(value("Authorization").starts_with(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature="
+        ));
+    }
+}
@@ -0,0 +1,289 @@
+/// Response values extraction into variables, for request chaining
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Local};
+use regex::Regex;
+use serde_json::Value;
+
+use crate::states::{
+    main_page::{
+        request::request_data::{Capture, CaptureScope, CaptureSource},
+        response::Response,
+    },
+    Events,
+};
+
+/// Value extracted from response, waiting to be stored into variable
+#[derive(Debug, Clone)]
+pub struct Captured {
+    pub variable: String,
+    pub value: String,
+    pub scope: CaptureScope,
+    pub time: DateTime<Local>,
+}
+
+/// Extract captures from response into shared queue.
+/// report_missing - warn when value not found, off for streams where most messages dont match
+pub fn store(
+    captures: &[Capture],
+    response: &Response,
+    report_missing: bool,
+    captured: &Arc<Mutex<Vec<Captured>>>,
+    events: &Arc<Mutex<Events>>,
+) {
+    for capture in captures {
+        let variable = capture.variable.trim();
+        if variable.is_empty() || capture.expression.trim().is_empty() {
+            continue;
+        }
+
+        match extract(capture, response) {
+            Ok(value) => {
+                events.lock().unwrap().event_info(&format!(
+                    "Captured {{{{{variable}}}}} into {}",
+                    capture.scope
+                ));
+                captured.lock().unwrap().push(Captured {
+                    variable: variable.to_string(),
+                    value,
+                    scope: capture.scope.clone(),
+                    time: Local::now(),
+                });
+            }
+            Err(err) => {
+                if report_missing {
+                    events
+                        .lock()
+                        .unwrap()
+                        .event_warning(&format!("Capture {{{{{variable}}}}}: {err}"));
+                }
+            }
+        }
+    }
+}
+
+/// Value of capture in response
+pub fn extract(capture: &Capture, response: &Response) -> Result<String, String> {
+    let expression = capture.expression.trim();
+
+    match capture.source {
+        CaptureSource::JSONPATH => {
+            let json: Value = serde_json::from_str(&response.data.raw)
+                .map_err(|_| "Response body is not JSON".to_string())?;
+            let value = json_path(&json, expression)?;
+            Ok(match value {
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            })
+        }
+        CaptureSource::HEADER => response
+            .data
+            .headers
+            .iter()
+            .find(|header| header.key.trim().eq_ignore_ascii_case(expression))
+            .map(|header| header.value.clone())
+            .ok_or(format!("Header {expression} not found")),
+        CaptureSource::COOKIE => response
+            .data
+            .headers
+            .iter()
+            .filter(|header| header.key.trim().eq_ignore_ascii_case("set-cookie"))
+            .find_map(|header| {
+                let pair = header.value.split(';').next()?;
+                let (name, value) = pair.split_once('=')?;
+                (name.trim() == expression).then(|| value.trim().to_string())
+            })
+            .ok_or(format!("Cookie {expression} not found")),
+        CaptureSource::REGEX => {
+            let regex = Regex::new(expression).map_err(|err| format!("Invalid regex. {err}"))?;
+            let captures = regex
+                .captures(&response.data.raw)
+                .ok_or("Regex did not match".to_string())?;
+            // First group if any, whole match otherwise
+            let value = captures.get(1).or(captures.get(0)).unwrap();
+            Ok(value.as_str().to_string())
+        }
+    }
+}
+
+/// Value by JSONPath subset: `$.items[0].id`, `$['some key']`, `$.items[-1]`
+pub fn json_path<'a>(json: &'a Value, path: &str) -> Result<&'a Value, String> {
+    let not_found = || format!("JSONPath {path} not found");
+    let invalid = || format!("Invalid JSONPath {path}");
+
+    let mut rest = path.strip_prefix('$').unwrap_or(path);
+    let mut value = json;
+
+    while !rest.is_empty() {
+        if let Some(index) = rest.strip_prefix('[') {
+            let end = index.find(']').ok_or_else(invalid)?;
+            let key = index[..end].trim();
+            rest = &index[end + 1..];
+
+            let quoted = key
+                .strip_prefix('\'')
+                .and_then(|key| key.strip_suffix('\''))
+                .or_else(|| key.strip_prefix('"').and_then(|key| key.strip_suffix('"')));
+            value = match quoted {
+                Some(name) => value.get(name).ok_or_else(not_found)?,
+                None => {
+                    let index: i64 = key.parse().map_err(|_| invalid())?;
+                    let items = value.as_array().ok_or_else(not_found)?;
+                    let index = if index < 0 {
+                        items.len() as i64 + index
+                    } else {
+                        index
+                    };
+                    if index < 0 {
+                        return Err(not_found());
+                    }
+                    items.get(index as usize).ok_or_else(not_found)?
+                }
+            };
+            continue;
+        }
+
+        let name = rest.strip_prefix('.').unwrap_or(rest);
+        let end = name.find(['.', '[']).unwrap_or(name.len());
+        if end == 0 {
+            return Err(invalid());
+        }
+        value = value.get(&name[..end]).ok_or_else(not_found)?;
+        rest = &name[end..];
+    }
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::states::main_page::generics::Header;
+
+    fn capture(source: CaptureSource, expression: &str) -> Capture {
+        Capture {
+            source,
+            expression: expression.into(),
+            variable: "value".into(),
+            ..Default::default()
+        }
+    }
+
+    fn response() -> Response {
+        Response::from_frame(
+            "".into(),
+            vec![
+                Header {
+                    key: "X-Request-Id".into(),
+                    value: "42".into(),
+                },
+                Header {
+                    key: "Set-Cookie".into(),
+                    value: "theme=dark; Path=/".into(),
+                },
+                Header {
+                    key: "set-cookie".into(),
+                    value: "session=abc; HttpOnly".into(),
+                },
+            ],
+            r#"{"token": "t-1", "items": [{"id": 1}, {"id": 2}], "count": 2}"#.into(),
+        )
+    }
+
+    #[test]
+    fn json_path_subset() {
+        let json = json!({
+            "items": [{"id": 1, "tags": ["a", "b"]}, {"id": 2}],
+            "some key": {"nested": true},
+            "name": "frapi"
+        });
+        assert_eq!(json_path(&json, "$").unwrap(), &json);
+        assert_eq!(json_path(&json, "$.name").unwrap(), "frapi");
+        assert_eq!(json_path(&json, "name").unwrap(), "frapi");
+        assert_eq!(json_path(&json, "$.items[0].id").unwrap(), 1);
+        assert_eq!(json_path(&json, "$.items[-1].id").unwrap(), 2);
+        assert_eq!(json_path(&json, "$.items[0].tags[1]").unwrap(), "b");
+        assert_eq!(json_path(&json, "$['some key'].nested").unwrap(), true);
+        assert_eq!(json_path(&json, "$[\"some key\"]['nested']").unwrap(), true);
+    }
+
+    #[test]
+    fn json_path_errors() {
+        let json = json!({"items": [1, 2]});
+        assert_eq!(
+            json_path(&json, "$.missing").unwrap_err(),
+            "JSONPath $.missing not found"
+        );
+        assert_eq!(
+            json_path(&json, "$.items[2]").unwrap_err(),
+            "JSONPath $.items[2] not found"
+        );
+        assert_eq!(
+            json_path(&json, "$.items[-3]").unwrap_err(),
+            "JSONPath $.items[-3] not found"
+        );
+        assert_eq!(
+            json_path(&json, "$.items[0").unwrap_err(),
+            "Invalid JSONPath $.items[0"
+        );
+        assert_eq!(
+            json_path(&json, "$.items[a]").unwrap_err(),
+            "Invalid JSONPath $.items[a]"
+        );
+        assert_eq!(
+            json_path(&json, "$..items").unwrap_err(),
+            "Invalid JSONPath $..items"
+        );
+    }
+
+    #[test]
+    fn extract_sources() {
+        let response = response();
+        let value = |source, expression| extract(&capture(source, expression), &response);
+
+        assert_eq!(value(CaptureSource::JSONPATH, "$.token").unwrap(), "t-1");
+        assert_eq!(value(CaptureSource::JSONPATH, "$.count").unwrap(), "2");
+        assert_eq!(
+            value(CaptureSource::JSONPATH, "$.items[1]").unwrap(),
+            r#"{"id":2}"#
+        );
+        assert_eq!(value(CaptureSource::HEADER, "x-request-id").unwrap(), "42");
+        assert_eq!(value(CaptureSource::COOKIE, "session").unwrap(), "abc");
+        assert_eq!(value(CaptureSource::COOKIE, "theme").unwrap(), "dark");
+        assert_eq!(
+            value(CaptureSource::REGEX, r#""token": "([^"]+)""#).unwrap(),
+            "t-1"
+        );
+        assert_eq!(value(CaptureSource::REGEX, r"\d").unwrap(), "1");
+    }
+
+    #[test]
+    fn extract_errors() {
+        let response = response();
+        let value = |source, expression| extract(&capture(source, expression), &response);
+
+        assert_eq!(
+            value(CaptureSource::HEADER, "Missing").unwrap_err(),
+            "Header Missing not found"
+        );
+        assert_eq!(
+            value(CaptureSource::COOKIE, "Path").unwrap_err(),
+            "Cookie Path not found"
+        );
+        assert_eq!(
+            value(CaptureSource::REGEX, "nothing").unwrap_err(),
+            "Regex did not match"
+        );
+        assert!(value(CaptureSource::REGEX, "(")
+            .unwrap_err()
+            .starts_with("Invalid regex"));
+
+        let text = Response::from_frame("".into(), vec![], "plain".into());
+        assert_eq!(
+            extract(&capture(CaptureSource::JSONPATH, "$.a"), &text).unwrap_err(),
+            "Response body is not JSON"
+        );
+    }
+}
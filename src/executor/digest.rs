@@ -0,0 +1,342 @@
+/// HTTP Digest authentication (RFC 7616), answer on server 401 challenge
+use md5::Md5;
+use reqwest::{
+    header::{HeaderMap, WWW_AUTHENTICATE},
+    Url,
+};
+use sha2::{Digest, Sha256};
+
+/// Every challenge answered once, so nonce count is always first
+const NONCE_COUNT: &str = "00000001";
+
+/// Digest challenge of WWW-Authenticate header
+#[derive(Debug, Clone)]
+pub struct DigestChallenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    /// As sent by server, default MD5
+    algorithm: String,
+    /// Server offered qop=auth. Without it legacy RFC 2069 response computed
+    qop_auth: bool,
+}
+
+impl DigestChallenge {
+    fn is_sha256(&self) -> bool {
+        self.algorithm.to_uppercase().starts_with("SHA-256")
+    }
+
+    fn is_sess(&self) -> bool {
+        self.algorithm.to_uppercase().ends_with("-SESS")
+    }
+
+    fn hash(&self, data: &str) -> String {
+        if self.is_sha256() {
+            hex(&Sha256::digest(data.as_bytes()))
+        } else {
+            hex(&Md5::digest(data.as_bytes()))
+        }
+    }
+}
+
+/// Supported Digest challenge from response headers, SHA-256 preferred over MD5
+pub fn challenge(headers: &HeaderMap) -> Result<DigestChallenge, String> {
+    let mut unsupported = None;
+    let mut challenges = vec![];
+
+    for value in headers.get_all(WWW_AUTHENTICATE) {
+        let value = String::from_utf8_lossy(value.as_bytes()).to_string();
+        let Some(params) = strip_scheme(&value) else {
+            // Connection based schemes, could not be answered by retry
+            let scheme = value.split_whitespace().next().unwrap_or_default();
+            if ["ntlm", "negotiate"].contains(&scheme.to_lowercase().as_str()) {
+                unsupported = unsupported.or(Some(format!("scheme {scheme}")));
+            }
+            continue;
+        };
+
+        let params = parse_params(params);
+        let param = |name: &str| {
+            params
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.clone())
+        };
+
+        let algorithm = param("algorithm").unwrap_or("MD5".into());
+        if !["MD5", "MD5-SESS", "SHA-256", "SHA-256-SESS"]
+            .contains(&algorithm.to_uppercase().as_str())
+        {
+            unsupported = Some(format!("algorithm {algorithm}"));
+            continue;
+        }
+
+        let qop = param("qop");
+        let qop_auth = match &qop {
+            Some(qop) => qop
+                .split(',')
+                .any(|item| item.trim().eq_ignore_ascii_case("auth")),
+            None => false,
+        };
+        if qop.is_some() && !qop_auth {
+            unsupported = Some(format!("qop {}", qop.unwrap_or_default()));
+            continue;
+        }
+
+        let Some(nonce) = param("nonce") else {
+            continue;
+        };
+
+        challenges.push(DigestChallenge {
+            realm: param("realm").unwrap_or_default(),
+            nonce,
+            opaque: param("opaque"),
+            algorithm,
+            qop_auth,
+        });
+    }
+
+    challenges.sort_by_key(|challenge| !challenge.is_sha256());
+    match challenges.into_iter().next() {
+        Some(challenge) => Ok(challenge),
+        None => match unsupported {
+            Some(unsupported) => Err(format!("Digest: server requires unsupported {unsupported}")),
+            None => Err("Digest: no challenge in 401 response".into()),
+        },
+    }
+}
+
+/// Authorization header value answering challenge
+pub fn authorization(
+    challenge: &DigestChallenge,
+    username: &str,
+    password: &str,
+    method: &str,
+    url: &Url,
+) -> String {
+    let uri = match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_string(),
+    };
+    let cnonce = uuid::Uuid::new_v4().simple().to_string();
+    let response = response(challenge, username, password, method, &uri, &cnonce);
+
+    let mut value = format!(
+        "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", algorithm={}, response=\"{response}\"",
+        quote(username),
+        quote(&challenge.realm),
+        quote(&challenge.nonce),
+        quote(&uri),
+        challenge.algorithm,
+    );
+    if challenge.qop_auth {
+        value.push_str(&format!(
+            ", qop=auth, nc={NONCE_COUNT}, cnonce=\"{cnonce}\""
+        ));
+    }
+    if let Some(opaque) = &challenge.opaque {
+        value.push_str(&format!(", opaque=\"{}\"", quote(opaque)));
+    }
+
+    value
+}
+
+/// Response param of Authorization header.
+/// uri - path with query, as sent
+fn response(
+    challenge: &DigestChallenge,
+    username: &str,
+    password: &str,
+    method: &str,
+    uri: &str,
+    cnonce: &str,
+) -> String {
+    let mut ha1 = challenge.hash(&format!("{username}:{}:{password}", challenge.realm));
+    if challenge.is_sess() {
+        ha1 = challenge.hash(&format!("{ha1}:{}:{cnonce}", challenge.nonce));
+    }
+    let ha2 = challenge.hash(&format!("{}:{uri}", method.to_uppercase()));
+
+    if challenge.qop_auth {
+        challenge.hash(&format!(
+            "{ha1}:{}:{NONCE_COUNT}:{cnonce}:auth:{ha2}",
+            challenge.nonce
+        ))
+    } else {
+        challenge.hash(&format!("{ha1}:{}:{ha2}", challenge.nonce))
+    }
+}
+
+/// Params part of Digest challenge, None for other schemes
+fn strip_scheme(value: &str) -> Option<&str> {
+    let value = value.trim_start();
+    let (scheme, params) = value.split_once(' ')?;
+    if scheme.eq_ignore_ascii_case("digest") {
+        Some(params)
+    } else {
+        None
+    }
+}
+
+/// Comma separated key=value or key="value" pairs.
+/// Stops on token without value - start of next scheme in same header
+fn parse_params(params: &str) -> Vec<(String, String)> {
+    let mut result = vec![];
+    let mut chars = params.chars().peekable();
+
+    loop {
+        while chars.peek().is_some_and(|c| *c == ',' || c.is_whitespace()) {
+            chars.next();
+        }
+
+        let mut key = String::new();
+        while let Some(c) = chars.peek() {
+            if *c == '=' || *c == ',' || c.is_whitespace() {
+                break;
+            }
+            key.push(*c);
+            chars.next();
+        }
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        if key.is_empty() || chars.next() != Some('=') {
+            break;
+        }
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+
+        let mut value = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => {
+                        if let Some(escaped) = chars.next() {
+                            value.push(escaped);
+                        }
+                    }
+                    '"' => break,
+                    _ => value.push(c),
+                }
+            }
+        } else {
+            while let Some(c) = chars.peek() {
+                if *c == ',' {
+                    break;
+                }
+                value.push(*c);
+                chars.next();
+            }
+            value = value.trim().to_string();
+        }
+
+        result.push((key, value));
+    }
+
+    result
+}
+
+/// Escape for quoted-string
+fn quote(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    // Example of RFC 7616 section 3.9.1
+    const NONCE: &str = "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v";
+    const OPAQUE: &str = "FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS";
+    const CNONCE: &str = "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ";
+
+    fn headers(values: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append(WWW_AUTHENTICATE, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    fn rfc_challenge(algorithm: &str) -> String {
+        format!(
+            "Digest realm=\"http-auth@example.org\", qop=\"auth, auth-int\", algorithm={algorithm}, nonce=\"{NONCE}\", opaque=\"{OPAQUE}\""
+        )
+    }
+
+    fn rfc_response(algorithm: &str) -> String {
+        let challenge = challenge(&headers(&[&rfc_challenge(algorithm)])).unwrap();
+        response(
+            &challenge,
+            "Mufasa",
+            "Circle of Life",
+            "GET",
+            "/dir/index.html",
+            CNONCE,
+        )
+    }
+
+    #[test]
+    fn rfc_7616_md5() {
+        assert_eq!(rfc_response("MD5"), "8ca523f5e9506fed4657c9700eebdbec");
+    }
+
+    #[test]
+    fn rfc_7616_sha256() {
+        assert_eq!(
+            rfc_response("SHA-256"),
+            "753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1"
+        );
+    }
+
+    #[test]
+    fn sess() {
+        // H(H(user:realm:password):nonce:cnonce) as HA1, with RFC 7616 example values
+        assert_eq!(rfc_response("MD5-sess"), "e783283f46242139c486a698fec7211d");
+    }
+
+    #[test]
+    fn sha256_preferred() {
+        let challenge = challenge(&headers(&[
+            &rfc_challenge("MD5"),
+            &rfc_challenge("SHA-256"),
+        ]))
+        .unwrap();
+        assert!(challenge.is_sha256());
+        assert_eq!(challenge.opaque.as_deref(), Some(OPAQUE));
+        assert!(challenge.qop_auth);
+    }
+
+    #[test]
+    fn authorization_header() {
+        let challenge = challenge(&headers(&[&rfc_challenge("MD5")])).unwrap();
+        let url = Url::parse("http://example.org/dir/index.html?a=1").unwrap();
+        let value = authorization(&challenge, "Mufasa", "Circle of Life", "GET", &url);
+        assert!(value.starts_with(
+            "Digest username=\"Mufasa\", realm=\"http-auth@example.org\", nonce=\"7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v\", uri=\"/dir/index.html?a=1\", algorithm=MD5, response=\""
+        ));
+        assert!(value.contains(", qop=auth, nc=00000001, cnonce=\""));
+        assert!(value.ends_with(&format!(", opaque=\"{OPAQUE}\"")));
+    }
+
+    #[test]
+    fn unsupported() {
+        let err = challenge(&headers(&["NTLM", "Negotiate"])).unwrap_err();
+        assert_eq!(err, "Digest: server requires unsupported scheme NTLM");
+        let err = challenge(&headers(&[
+            "Digest realm=\"r\", nonce=\"n\", qop=\"auth-int\"",
+        ]))
+        .unwrap_err();
+        assert_eq!(err, "Digest: server requires unsupported qop auth-int");
+        let err = challenge(&headers(&["Basic realm=\"r\""])).unwrap_err();
+        assert_eq!(err, "Digest: no challenge in 401 response");
+    }
+}
@@ -0,0 +1,638 @@
+/// GraphQL helpers: request envelope, schema introspection for autocomplete
+/// and `graphql-transport-ws` protocol messages for subscriptions
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    executor::{digest, insert_headers, sign_request, with_auth},
+    states::{
+        main_page::{
+            generics::Header,
+            request::request_data::{AuthType, GraphQlBody, RequestAuth},
+        },
+        Events,
+    },
+};
+
+/// Value of Sec-WebSocket-Protocol header for subscriptions
+pub const GRAPHQL_WS_SUBPROTOCOL: &str = "graphql-transport-ws";
+
+/// Max amount of autocomplete suggestions
+const MAX_SUGGESTIONS: usize = 20;
+
+/// Introspection query, fetching only what autocomplete needs
+const INTROSPECTION_QUERY: &str = "query IntrospectionQuery { __schema { queryType { name } mutationType { name } subscriptionType { name } types { name fields(includeDeprecated: true) { name type { name ofType { name ofType { name ofType { name } } } } } } } }";
+
+/// Standard JSON envelope: query, variables and operation name.
+/// Empty variables and operation name are omitted
+pub fn envelope(body: &GraphQlBody) -> Result<Value, String> {
+    let mut envelope = Map::new();
+    envelope.insert("query".into(), Value::from(body.query.message.clone()));
+
+    if !body.variables.message.trim().is_empty() {
+        match serde_json::from_str::<Value>(&body.variables.message) {
+            Ok(Value::Object(variables)) => {
+                envelope.insert("variables".into(), Value::Object(variables));
+            }
+            Ok(_) => return Err("GraphQL variables must be JSON object".into()),
+            Err(err) => return Err(format!("GraphQL variables is not valid JSON: {err}")),
+        }
+    }
+
+    if !body.operation_name.trim().is_empty() {
+        envelope.insert(
+            "operationName".into(),
+            Value::from(body.operation_name.trim()),
+        );
+    }
+
+    Ok(Value::Object(envelope))
+}
+
+/// Schema details, used for autocomplete
+#[derive(Debug, Clone, Default)]
+pub struct GraphQlSchema {
+    pub query_type: Option<String>,
+    pub mutation_type: Option<String>,
+    pub subscription_type: Option<String>,
+    /// Type name -> list of fields: (field name, field named type)
+    pub types: HashMap<String, Vec<(String, String)>>,
+}
+
+impl GraphQlSchema {
+    /// Parse introspection query response
+    pub fn from_introspection(value: &Value) -> Result<Self, String> {
+        let schema = match value.pointer("/data/__schema") {
+            Some(val) => val,
+            None => {
+                return Err(match value.get("errors") {
+                    Some(errors) => format!("Introspection failed. Errors: {errors}"),
+                    None => "Introspection response has no schema".into(),
+                })
+            }
+        };
+
+        let root_name = |key: &str| {
+            schema
+                .pointer(&format!("/{key}/name"))
+                .and_then(|val| val.as_str())
+                .map(|val| val.to_string())
+        };
+
+        let mut types = HashMap::new();
+        for schema_type in schema
+            .get("types")
+            .and_then(|val| val.as_array())
+            .into_iter()
+            .flatten()
+        {
+            let name = match schema_type.get("name").and_then(|val| val.as_str()) {
+                Some(val) => val,
+                None => continue,
+            };
+            let fields = match schema_type.get("fields").and_then(|val| val.as_array()) {
+                Some(val) => val,
+                None => continue,
+            };
+
+            let fields = fields
+                .iter()
+                .filter_map(|field| {
+                    let field_name = field.get("name")?.as_str()?;
+                    Some((field_name.to_string(), named_type(field.get("type")?)))
+                })
+                .collect();
+            types.insert(name.to_string(), fields);
+        }
+
+        Ok(Self {
+            query_type: root_name("queryType"),
+            mutation_type: root_name("mutationType"),
+            subscription_type: root_name("subscriptionType"),
+            types,
+        })
+    }
+
+    /// Field suggestions for query text before cursor.
+    /// Returns typed prefix and fields of type in current selection set, wich starts with prefix
+    pub fn suggestions(&self, text: &str) -> Option<(String, Vec<String>)> {
+        let context = SelectionContext::parse(text)?;
+
+        let mut current_type = None;
+        for step in &context.path {
+            current_type = match step {
+                SelectionStep::Operation(operation) => match operation.as_str() {
+                    "mutation" => self.mutation_type.clone(),
+                    "subscription" => self.subscription_type.clone(),
+                    _ => self.query_type.clone(),
+                },
+                SelectionStep::OnType(type_name) => Some(type_name.clone()),
+                SelectionStep::Field(field_name) => {
+                    let parent = current_type.as_ref()?;
+                    self.types
+                        .get(parent)?
+                        .iter()
+                        .find(|(name, _)| name == field_name)
+                        .map(|(_, type_name)| type_name.clone())
+                }
+            };
+        }
+
+        let fields = self.types.get(&current_type?)?;
+        let prefix = context.prefix.to_lowercase();
+        let suggestions = fields
+            .iter()
+            .map(|(name, _)| name)
+            .filter(|name| name.to_lowercase().starts_with(&prefix) && **name != context.prefix)
+            .take(MAX_SUGGESTIONS)
+            .cloned()
+            .collect();
+
+        Some((context.prefix, suggestions))
+    }
+}
+
+/// Unwrap NON_NULL and LIST wrappers to named type
+fn named_type(value: &Value) -> String {
+    match value.get("name").and_then(|val| val.as_str()) {
+        Some(name) => name.to_string(),
+        None => match value.get("ofType") {
+            Some(of_type) => named_type(of_type),
+            None => "".into(),
+        },
+    }
+}
+
+/// Step of path from document root to selection set under cursor
+#[derive(Debug, PartialEq)]
+enum SelectionStep {
+    /// Operation keyword: query, mutation, subscription
+    Operation(String),
+    /// Fragment or inline fragment type condition
+    OnType(String),
+    Field(String),
+}
+
+/// Where in query cursor is
+#[derive(Debug)]
+struct SelectionContext {
+    path: Vec<SelectionStep>,
+    prefix: String,
+}
+
+impl SelectionContext {
+    /// Light scan of query text: strings, comments and arguments skipped.
+    /// None if cursor not inside selection set
+    fn parse(text: &str) -> Option<Self> {
+        let mut path = vec![];
+        let mut last_word = String::new();
+        let mut operation = "query".to_string();
+        let mut on_type: Option<String> = None;
+        let mut expect_on_type = false;
+        let mut word = String::new();
+        let mut parens = 0;
+        let mut chars = text.chars();
+
+        while let Some(char) = chars.next() {
+            if char.is_alphanumeric() || char == '_' {
+                word.push(char);
+                continue;
+            }
+
+            if !word.is_empty() && parens == 0 {
+                if expect_on_type {
+                    on_type = Some(word.clone());
+                    expect_on_type = false;
+                } else if word == "on" {
+                    expect_on_type = true;
+                } else if path.is_empty()
+                    && ["query", "mutation", "subscription"].contains(&word.as_str())
+                {
+                    operation = word.clone();
+                }
+                last_word = word.clone();
+            }
+            word.clear();
+
+            match char {
+                '"' => {
+                    // Skip string literal
+                    while let Some(char) = chars.next() {
+                        match char {
+                            '\\' => {
+                                chars.next();
+                            }
+                            '"' => break,
+                            _ => {}
+                        }
+                    }
+                }
+                '#' => {
+                    for char in chars.by_ref() {
+                        if char == '\n' {
+                            break;
+                        }
+                    }
+                }
+                '(' => parens += 1,
+                ')' => parens -= 1,
+                '{' if parens == 0 => {
+                    let step = match on_type.take() {
+                        Some(type_name) => SelectionStep::OnType(type_name),
+                        None if path.is_empty() => SelectionStep::Operation(operation.clone()),
+                        None => SelectionStep::Field(last_word.clone()),
+                    };
+                    path.push(step);
+                }
+                '}' if parens == 0 => {
+                    path.pop();
+                }
+                _ => {}
+            }
+        }
+
+        if path.is_empty() || parens > 0 {
+            return None;
+        }
+
+        Some(Self { path, prefix: word })
+    }
+}
+
+/// Fetch schema with introspection query, authorized same way as request
+pub async fn fetch_schema(
+    uri: String,
+    headers: Vec<Header>,
+    auth: RequestAuth,
+    events: Arc<Mutex<Events>>,
+) -> Result<GraphQlSchema, String> {
+    let client = Client::new();
+    let request = client
+        .post(uri)
+        .json(&json!({ "query": INTROSPECTION_QUERY }));
+    let mut request = with_auth(request, headers, &auth, &events)
+        .await?
+        .build()
+        .map_err(|err| format!("Could not build introspection query. Error: {err}"))?;
+    sign_request(&mut request, &auth)?;
+    let retry = if auth.auth_type == AuthType::DIGEST {
+        request.try_clone()
+    } else {
+        None
+    };
+
+    let send_error = |err| format!("Could not send introspection query. Error: {err}");
+    let mut response = client.execute(request).await.map_err(send_error)?;
+
+    // Digest answers server challenge with second request
+    if let Some(mut retry) = retry {
+        if response.status() == StatusCode::UNAUTHORIZED {
+            let challenge = digest::challenge(response.headers())?;
+            let authorization = digest::authorization(
+                &challenge,
+                &auth.username,
+                &auth.password,
+                retry.method().as_str(),
+                retry.url(),
+            );
+            insert_headers(
+                retry.headers_mut(),
+                vec![Header {
+                    key: "Authorization".into(),
+                    value: authorization,
+                }],
+            )?;
+            response = client.execute(retry).await.map_err(send_error)?;
+        }
+    }
+
+    match response.json::<Value>().await {
+        Ok(val) => GraphQlSchema::from_introspection(&val),
+        Err(err) => Err(format!(
+            "Could not read introspection response. Error: {err}"
+        )),
+    }
+}
+
+/// Parsed incoming `graphql-transport-ws` message
+#[derive(Debug, PartialEq)]
+pub enum GraphQlWsMessage {
+    ConnectionAck,
+    Ping,
+    Pong,
+    /// Subscription result
+    Next {
+        id: String,
+        payload: Value,
+    },
+    /// Subscription errors
+    Error {
+        id: String,
+        payload: Value,
+    },
+    /// Subscription ended by server
+    Complete {
+        id: String,
+    },
+    /// Message could not be parsed
+    Unknown(String),
+}
+
+/// Session init message. Payload - optional JSON object
+pub fn connection_init(payload: &str) -> Result<String, String> {
+    if payload.trim().is_empty() {
+        return Ok(json!({ "type": "connection_init" }).to_string());
+    }
+
+    match serde_json::from_str::<Value>(payload) {
+        Ok(payload) => Ok(json!({ "type": "connection_init", "payload": payload }).to_string()),
+        Err(err) => Err(format!(
+            "GraphQL connection params is not valid JSON: {err}"
+        )),
+    }
+}
+
+/// Subscribe message with new operation id
+pub fn subscribe(body: &GraphQlBody) -> Result<String, String> {
+    if body.query.message.trim().is_empty() {
+        return Err("GraphQL query is empty".into());
+    }
+
+    Ok(json!({
+        "id": uuid::Uuid::new_v4().to_string(),
+        "type": "subscribe",
+        "payload": envelope(body)?,
+    })
+    .to_string())
+}
+
+/// Answear on server ping
+pub fn pong() -> String {
+    json!({ "type": "pong" }).to_string()
+}
+
+/// Parse incoming text frame
+pub fn parse_message(text: &str) -> GraphQlWsMessage {
+    let value = match serde_json::from_str::<Value>(text) {
+        Ok(val) => val,
+        Err(_) => return GraphQlWsMessage::Unknown(text.into()),
+    };
+
+    let id = value
+        .get("id")
+        .and_then(|val| val.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let payload = value.get("payload").cloned().unwrap_or(Value::Null);
+
+    match value.get("type").and_then(|val| val.as_str()) {
+        Some("connection_ack") => GraphQlWsMessage::ConnectionAck,
+        Some("ping") => GraphQlWsMessage::Ping,
+        Some("pong") => GraphQlWsMessage::Pong,
+        Some("next") => GraphQlWsMessage::Next { id, payload },
+        Some("error") => GraphQlWsMessage::Error { id, payload },
+        Some("complete") => GraphQlWsMessage::Complete { id },
+        _ => GraphQlWsMessage::Unknown(text.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+    use crate::states::main_page::request::request_data::ApiKeyLocation;
+
+    fn body(query: &str, variables: &str, operation_name: &str) -> GraphQlBody {
+        let mut body = GraphQlBody {
+            operation_name: operation_name.into(),
+            ..Default::default()
+        };
+        body.query.set(query.into());
+        body.variables.set(variables.into());
+        body
+    }
+
+    fn schema() -> GraphQlSchema {
+        let introspection = json!({"data": {"__schema": {
+            "queryType": {"name": "Query"},
+            "mutationType": {"name": "Mutation"},
+            "subscriptionType": null,
+            "types": [
+                {"name": "Query", "fields": [
+                    {"name": "user", "type": {"name": "User", "ofType": null}},
+                    {"name": "users", "type": {"name": null, "ofType":
+                        {"name": null, "ofType": {"name": "User", "ofType": null}}}},
+                ]},
+                {"name": "Mutation", "fields": [
+                    {"name": "createUser", "type": {"name": "User", "ofType": null}},
+                ]},
+                {"name": "User", "fields": [
+                    {"name": "id", "type": {"name": "ID", "ofType": null}},
+                    {"name": "name", "type": {"name": "String", "ofType": null}},
+                    {"name": "nickname", "type": {"name": "String", "ofType": null}},
+                    {"name": "friends", "type": {"name": null, "ofType": {"name": "User"}}},
+                ]},
+                {"name": "String", "fields": null},
+            ]
+        }}});
+        GraphQlSchema::from_introspection(&introspection).unwrap()
+    }
+
+    #[test]
+    fn request_envelope() {
+        assert_eq!(
+            envelope(&body("{ user { id } }", "", " ")).unwrap(),
+            json!({"query": "{ user { id } }"})
+        );
+        assert_eq!(
+            envelope(&body(
+                "query Get($id: ID) { user(id: $id) { id } }",
+                r#"{"id": 1}"#,
+                " Get "
+            ))
+            .unwrap(),
+            json!({
+                "query": "query Get($id: ID) { user(id: $id) { id } }",
+                "variables": {"id": 1},
+                "operationName": "Get"
+            })
+        );
+        assert_eq!(
+            envelope(&body("{ a }", "[1]", "")).unwrap_err(),
+            "GraphQL variables must be JSON object"
+        );
+        assert!(envelope(&body("{ a }", "{", ""))
+            .unwrap_err()
+            .starts_with("GraphQL variables is not valid JSON"));
+    }
+
+    #[test]
+    fn introspection() {
+        let schema = schema();
+        assert_eq!(schema.query_type.as_deref(), Some("Query"));
+        assert_eq!(schema.mutation_type.as_deref(), Some("Mutation"));
+        assert_eq!(schema.subscription_type, None);
+        // Wrapped NON_NULL and LIST types unwrapped to named one
+        assert!(schema.types["Query"].contains(&("users".into(), "User".into())));
+        assert!(!schema.types.contains_key("String"));
+
+        assert_eq!(
+            GraphQlSchema::from_introspection(&json!({"errors": [{"message": "off"}]}))
+                .unwrap_err(),
+            r#"Introspection failed. Errors: [{"message":"off"}]"#
+        );
+        assert_eq!(
+            GraphQlSchema::from_introspection(&json!({})).unwrap_err(),
+            "Introspection response has no schema"
+        );
+    }
+
+    #[test]
+    fn selection_context() {
+        let context = SelectionContext::parse("query { user(id: \"{\") { friends { na").unwrap();
+        assert_eq!(
+            context.path,
+            vec![
+                SelectionStep::Operation("query".into()),
+                SelectionStep::Field("user".into()),
+                SelectionStep::Field("friends".into()),
+            ]
+        );
+        assert_eq!(context.prefix, "na");
+
+        let context = SelectionContext::parse("mutation { createUser { id } # {\n").unwrap();
+        assert_eq!(
+            context.path,
+            vec![SelectionStep::Operation("mutation".into())]
+        );
+        assert_eq!(context.prefix, "");
+
+        let context = SelectionContext::parse("fragment F on User { ").unwrap();
+        assert_eq!(context.path, vec![SelectionStep::OnType("User".into())]);
+
+        assert!(SelectionContext::parse("query Get").is_none());
+        assert!(SelectionContext::parse("{ user(id: ").is_none());
+    }
+
+    #[test]
+    fn field_suggestions() {
+        let schema = schema();
+        assert_eq!(
+            schema.suggestions("{ user { n"),
+            Some(("n".into(), vec!["name".into(), "nickname".into()]))
+        );
+        assert_eq!(
+            schema.suggestions("{ users { friends { I"),
+            Some(("I".into(), vec!["id".into()]))
+        );
+        assert_eq!(
+            schema.suggestions("mutation { c"),
+            Some(("c".into(), vec!["createUser".into()]))
+        );
+        // Complete name not suggested again
+        assert_eq!(
+            schema.suggestions("{ user { name"),
+            Some(("name".into(), vec![]))
+        );
+        assert_eq!(schema.suggestions("{ unknown { "), None);
+        assert_eq!(schema.suggestions("subscription { "), None);
+    }
+
+    #[test]
+    fn ws_messages() {
+        assert_eq!(
+            parse_message(r#"{"type": "connection_ack"}"#),
+            GraphQlWsMessage::ConnectionAck
+        );
+        assert_eq!(parse_message(r#"{"type": "ping"}"#), GraphQlWsMessage::Ping);
+        assert_eq!(
+            parse_message(r#"{"id": "1", "type": "next", "payload": {"data": {"a": 1}}}"#),
+            GraphQlWsMessage::Next {
+                id: "1".into(),
+                payload: json!({"data": {"a": 1}}),
+            }
+        );
+        assert_eq!(
+            parse_message(r#"{"id": "1", "type": "error", "payload": [{"message": "x"}]}"#),
+            GraphQlWsMessage::Error {
+                id: "1".into(),
+                payload: json!([{"message": "x"}]),
+            }
+        );
+        assert_eq!(
+            parse_message(r#"{"id": "1", "type": "complete"}"#),
+            GraphQlWsMessage::Complete { id: "1".into() }
+        );
+        assert_eq!(
+            parse_message(r#"{"type": "other"}"#),
+            GraphQlWsMessage::Unknown(r#"{"type": "other"}"#.into())
+        );
+        assert_eq!(
+            parse_message("text"),
+            GraphQlWsMessage::Unknown("text".into())
+        );
+
+        assert_eq!(
+            connection_init("").unwrap(),
+            r#"{"type":"connection_init"}"#
+        );
+        assert!(connection_init("{").is_err());
+        let message: Value =
+            serde_json::from_str(&subscribe(&body("subscription { a }", "", "")).unwrap()).unwrap();
+        assert_eq!(message["type"], "subscribe");
+        assert_eq!(message["payload"], json!({"query": "subscription { a }"}));
+        assert!(subscribe(&body(" ", "", "")).is_err());
+    }
+
+    #[tokio::test]
+    async fn schema_fetched_with_auth() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let uri = format!("http://{}/graphql", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = vec![0u8; 16384];
+            let size = stream.read(&mut buffer).await.unwrap();
+            let reply = r#"{"data": {"__schema": {"queryType": {"name": "Query"}, "types": []}}}"#;
+            stream
+                .write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{reply}",
+                        reply.len()
+                    )
+                    .as_bytes(),
+                )
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&buffer[..size]).to_lowercase()
+        });
+
+        let auth = RequestAuth {
+            auth_type: AuthType::APIKEY,
+            api_key_name: "key".into(),
+            api_key_value: "secret".into(),
+            api_key_location: ApiKeyLocation::QUERY,
+            ..Default::default()
+        };
+        let headers = vec![Header {
+            key: "X-Tenant".into(),
+            value: "a".into(),
+        }];
+        let events = Arc::new(Mutex::new(Events::new()));
+
+        let schema = fetch_schema(uri, headers, auth, events).await.unwrap();
+        assert_eq!(schema.query_type.as_deref(), Some("Query"));
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("post /graphql?key=secret http/1.1"));
+        assert!(request.contains("x-tenant: a"));
+    }
+}
@@ -0,0 +1,544 @@
+/// gRPC client helpers: descriptors from .proto files or server reflection,
+/// JSON <-> dynamic messages and codec for calls without generated code
+use std::{collections::HashMap, path::Path, str::FromStr};
+
+use prost::Message as _;
+use prost_reflect::{
+    prost_types::FileDescriptorProto, DescriptorPool, DynamicMessage, MessageDescriptor,
+    MethodDescriptor, SerializeOptions,
+};
+use tokio::sync::mpsc::{channel, Sender};
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
+use tonic::{
+    codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder},
+    codegen::http::uri::PathAndQuery,
+    metadata::{MetadataKey, MetadataMap, MetadataValue},
+    transport::{Channel, ClientTlsConfig, Endpoint},
+    Status,
+};
+use tonic_reflection::pb::v1alpha::{
+    server_reflection_client::ServerReflectionClient, server_reflection_request::MessageRequest,
+    server_reflection_response::MessageResponse, ServerReflectionRequest, ServerReflectionResponse,
+};
+
+use crate::{
+    settings::main_settings::entity::request_settings::protocol_settings::Protocol,
+    states::main_page::{generics::Header, request::RequestGrpcSetup},
+};
+
+/// Kind of call, defined by streaming on both sides
+#[derive(Debug, Clone, PartialEq)]
+pub enum GrpcCallKind {
+    Unary,
+    ServerStreaming,
+    ClientStreaming,
+    Bidi,
+}
+
+impl GrpcCallKind {
+    pub fn from_method(method: &MethodDescriptor) -> Self {
+        match (method.is_client_streaming(), method.is_server_streaming()) {
+            (false, false) => GrpcCallKind::Unary,
+            (false, true) => GrpcCallKind::ServerStreaming,
+            (true, false) => GrpcCallKind::ClientStreaming,
+            (true, true) => GrpcCallKind::Bidi,
+        }
+    }
+
+    /// Client could send messages during call
+    pub fn is_client_streaming(&self) -> bool {
+        matches!(self, GrpcCallKind::ClientStreaming | GrpcCallKind::Bidi)
+    }
+}
+
+impl std::fmt::Display for GrpcCallKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GrpcCallKind::Unary => write!(f, "unary"),
+            GrpcCallKind::ServerStreaming => write!(f, "server streaming"),
+            GrpcCallKind::ClientStreaming => write!(f, "client streaming"),
+            GrpcCallKind::Bidi => write!(f, "bidi streaming"),
+        }
+    }
+}
+
+/// Open channel to server. GRPCS uses TLS with webpki roots
+pub async fn connect(protocol: &Protocol, uri: &str) -> Result<Channel, String> {
+    let scheme = match protocol {
+        Protocol::GRPCS | Protocol::HTTPS | Protocol::WSS => "https",
+        _ => "http",
+    };
+    // Path is not used by gRPC, method path added per call
+    let authority = uri.split('/').next().unwrap_or_default();
+
+    let mut endpoint = match Endpoint::from_shared(format!("{scheme}://{authority}")) {
+        Ok(val) => val,
+        Err(err) => return Err(format!("Invalid gRPC uri: {err}")),
+    };
+    if scheme == "https" {
+        endpoint = match endpoint.tls_config(ClientTlsConfig::new().with_webpki_roots()) {
+            Ok(val) => val,
+            Err(err) => return Err(format!("Could not setup TLS: {err}")),
+        };
+    }
+
+    match endpoint.connect().await {
+        Ok(val) => Ok(val),
+        Err(err) => Err(format!("Could not connect to gRPC server: {err}")),
+    }
+}
+
+/// Compile .proto files. Directories of files are used as import paths too
+pub fn proto_pool(files: &[String], import_paths: &[String]) -> Result<DescriptorPool, String> {
+    if files.is_empty() {
+        return Err("No .proto files selected".into());
+    }
+
+    let mut includes: Vec<String> = import_paths.to_vec();
+    for file in files {
+        if let Some(parent) = Path::new(file).parent() {
+            let parent = parent.to_string_lossy().to_string();
+            if !includes.contains(&parent) {
+                includes.push(parent);
+            }
+        }
+    }
+
+    let mut compiler = match protox::Compiler::new(includes) {
+        Ok(val) => val,
+        Err(err) => return Err(format!("Could not prepare .proto compiler: {err}")),
+    };
+    compiler.include_imports(true);
+    if let Err(err) = compiler.open_files(files) {
+        return Err(format!("Could not compile .proto files: {err}"));
+    }
+
+    Ok(compiler.descriptor_pool())
+}
+
+/// Fetch descriptors of all services with server reflection
+pub async fn reflection_pool(grpc_channel: Channel) -> Result<DescriptorPool, String> {
+    let mut client = ServerReflectionClient::new(grpc_channel);
+    let (sender, receiver) = channel::<ServerReflectionRequest>(16);
+    let mut stream = match client
+        .server_reflection_info(ReceiverStream::new(receiver))
+        .await
+    {
+        Ok(val) => val.into_inner(),
+        Err(status) => {
+            return Err(format!(
+                "Server reflection not available: {}",
+                status.message()
+            ))
+        }
+    };
+
+    let services = match reflection_request(
+        &sender,
+        &mut stream,
+        MessageRequest::ListServices("".into()),
+    )
+    .await?
+    {
+        MessageResponse::ListServicesResponse(val) => val.service,
+        other => return Err(format!("Unexpected reflection response: {other:?}")),
+    };
+
+    let mut files: HashMap<String, FileDescriptorProto> = HashMap::new();
+    let mut requests: Vec<MessageRequest> = services
+        .into_iter()
+        // Reflection service itself not needed in list
+        .filter(|service| !service.name.starts_with("grpc.reflection."))
+        .map(|service| MessageRequest::FileContainingSymbol(service.name))
+        .collect();
+
+    while let Some(request) = requests.pop() {
+        let response = reflection_request(&sender, &mut stream, request).await?;
+        let descriptors = match response {
+            MessageResponse::FileDescriptorResponse(val) => val.file_descriptor_proto,
+            other => return Err(format!("Unexpected reflection response: {other:?}")),
+        };
+
+        for bytes in descriptors {
+            let file = match FileDescriptorProto::decode(bytes.as_slice()) {
+                Ok(val) => val,
+                Err(err) => return Err(format!("Invalid file descriptor from server: {err}")),
+            };
+            // Dependencies could be not sent with file
+            for dependency in &file.dependency {
+                if !files.contains_key(dependency) {
+                    requests.push(MessageRequest::FileByFilename(dependency.clone()));
+                }
+            }
+            files.insert(file.name().to_string(), file);
+        }
+        requests.retain(|request| match request {
+            MessageRequest::FileByFilename(name) => !files.contains_key(name),
+            _ => true,
+        });
+    }
+
+    let mut pool = DescriptorPool::new();
+    match pool.add_file_descriptor_protos(files.into_values()) {
+        Ok(_) => Ok(pool),
+        Err(err) => Err(format!("Invalid descriptors from server: {err}")),
+    }
+}
+
+/// Single request-response on reflection stream
+async fn reflection_request(
+    sender: &Sender<ServerReflectionRequest>,
+    stream: &mut tonic::Streaming<ServerReflectionResponse>,
+    request: MessageRequest,
+) -> Result<MessageResponse, String> {
+    let request = ServerReflectionRequest {
+        host: "".into(),
+        message_request: Some(request),
+    };
+    if sender.send(request).await.is_err() {
+        return Err("Reflection stream closed".into());
+    }
+
+    match stream.next().await {
+        Some(Ok(ServerReflectionResponse {
+            message_response: Some(MessageResponse::ErrorResponse(err)),
+            ..
+        })) => Err(format!("Reflection error: {}", err.error_message)),
+        Some(Ok(ServerReflectionResponse {
+            message_response: Some(response),
+            ..
+        })) => Ok(response),
+        Some(Ok(_)) => Err("Empty reflection response".into()),
+        Some(Err(status)) => Err(format!("Reflection error: {}", status.message())),
+        None => Err("Reflection stream closed".into()),
+    }
+}
+
+/// Services with their methods, for UI
+pub fn services(pool: &DescriptorPool) -> Vec<(String, Vec<MethodDescriptor>)> {
+    pool.services()
+        .map(|service| (service.full_name().to_string(), service.methods().collect()))
+        .collect()
+}
+
+pub fn find_method(
+    pool: &DescriptorPool,
+    service: &str,
+    method: &str,
+) -> Result<MethodDescriptor, String> {
+    let service = match pool.get_service_by_name(service) {
+        Some(val) => val,
+        None => return Err(format!("Service {service} not found")),
+    };
+
+    let found = service.methods().find(|val| val.name() == method);
+    match found {
+        Some(val) => Ok(val),
+        None => Err(format!(
+            "Method {method} not found in service {}",
+            service.full_name()
+        )),
+    }
+}
+
+/// Path of method for HTTP/2 request
+pub fn method_path(method: &MethodDescriptor) -> Result<PathAndQuery, String> {
+    let path = format!("/{}/{}", method.parent_service().full_name(), method.name());
+    match PathAndQuery::from_str(&path) {
+        Ok(val) => Ok(val),
+        Err(err) => Err(format!("Invalid method path {path}: {err}")),
+    }
+}
+
+/// Message from JSON in protobuf JSON mapping. Empty text means default message
+pub fn message_from_json(
+    descriptor: MessageDescriptor,
+    json: &str,
+) -> Result<DynamicMessage, String> {
+    let json = if json.trim().is_empty() { "{}" } else { json };
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    match DynamicMessage::deserialize(descriptor, &mut deserializer) {
+        Ok(val) => Ok(val),
+        Err(err) => Err(format!("Message does not match .proto definition: {err}")),
+    }
+}
+
+/// Message as pretty JSON. Default values shown, so message could be used as template
+pub fn message_to_json(message: &DynamicMessage) -> String {
+    let mut serializer = serde_json::Serializer::pretty(vec![]);
+    let options = SerializeOptions::new().skip_default_fields(false);
+    match message.serialize_with_options(&mut serializer, &options) {
+        Ok(_) => String::from_utf8(serializer.into_inner()).unwrap_or_default(),
+        Err(err) => format!("Could not serialize message: {err}"),
+    }
+}
+
+/// Request headers as call metadata
+pub fn metadata(headers: &[Header]) -> Result<MetadataMap, String> {
+    let mut metadata = MetadataMap::new();
+    for header in headers {
+        let key = match MetadataKey::from_bytes(header.key.to_lowercase().as_bytes()) {
+            Ok(val) => val,
+            Err(err) => return Err(format!("Invalid metadata key {}: {err}", header.key)),
+        };
+        let value = match MetadataValue::try_from(header.value.as_str()) {
+            Ok(val) => val,
+            Err(err) => return Err(format!("Invalid metadata value {}: {err}", header.value)),
+        };
+        metadata.insert(key, value);
+    }
+    Ok(metadata)
+}
+
+/// Metadata to show in response headers
+pub fn metadata_to_headers(metadata: &MetadataMap) -> Vec<Header> {
+    metadata
+        .clone()
+        .into_headers()
+        .iter()
+        .map(|(key, value)| Header {
+            key: key.to_string(),
+            value: value.to_str().unwrap_or("<binary>").to_string(),
+        })
+        .collect()
+}
+
+/// Codec for messages known only in runtime
+#[derive(Debug, Clone)]
+pub struct DynamicCodec {
+    output: MessageDescriptor,
+}
+
+impl DynamicCodec {
+    pub fn new(method: &MethodDescriptor) -> Self {
+        Self {
+            output: method.output(),
+        }
+    }
+}
+
+impl Codec for DynamicCodec {
+    type Encode = DynamicMessage;
+    type Decode = DynamicMessage;
+    type Encoder = DynamicEncoder;
+    type Decoder = DynamicDecoder;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        DynamicEncoder {}
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        DynamicDecoder {
+            output: self.output.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DynamicEncoder {}
+
+impl Encoder for DynamicEncoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        item.encode(dst)
+            .map_err(|err| Status::internal(format!("Could not encode message: {err}")))
+    }
+}
+
+#[derive(Debug)]
+pub struct DynamicDecoder {
+    output: MessageDescriptor,
+}
+
+impl Decoder for DynamicDecoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        match DynamicMessage::decode(self.output.clone(), src) {
+            Ok(val) => Ok(Some(val)),
+            Err(err) => Err(Status::internal(format!("Could not decode message: {err}"))),
+        }
+    }
+}
+
+/// Descriptors for request setup: from server reflection or .proto files
+pub async fn load_pool(
+    protocol: &Protocol,
+    uri: &str,
+    setup: &RequestGrpcSetup,
+) -> Result<DescriptorPool, String> {
+    if setup.use_reflection {
+        reflection_pool(connect(protocol, uri).await?).await
+    } else {
+        proto_pool(&setup.proto_files, &setup.import_paths())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    const COMMON: &str = r#"
+syntax = "proto3";
+package common;
+
+message Point {
+  int64 x = 1;
+  int64 y = 2;
+}
+"#;
+
+    const ROUTE: &str = r#"
+syntax = "proto3";
+package route;
+
+import "common/point.proto";
+
+message Request {
+  string name = 1;
+  repeated common.Point points = 2;
+  bool closed = 3;
+}
+
+message Reply {
+  string message = 1;
+}
+
+service Router {
+  rpc Get(Request) returns (Reply);
+  rpc Watch(Request) returns (stream Reply);
+  rpc Upload(stream Request) returns (Reply);
+  rpc Chat(stream Request) returns (stream Reply);
+}
+"#;
+
+    /// Route proto in its own directory, common proto in separate import path
+    fn pool(name: &str) -> DescriptorPool {
+        let root = std::env::temp_dir().join(format!("frapi-grpc-{}-{name}", std::process::id()));
+        let protos = root.join("protos");
+        let imports = root.join("imports");
+        fs::create_dir_all(&protos).unwrap();
+        fs::create_dir_all(imports.join("common")).unwrap();
+        fs::write(protos.join("route.proto"), ROUTE).unwrap();
+        fs::write(imports.join("common").join("point.proto"), COMMON).unwrap();
+
+        let pool = proto_pool(
+            &[protos.join("route.proto").to_string_lossy().to_string()],
+            &[imports.to_string_lossy().to_string()],
+        );
+        fs::remove_dir_all(root).unwrap();
+        pool.unwrap()
+    }
+
+    #[test]
+    fn compile_protos() {
+        let pool = pool("compile");
+        let services = services(&pool);
+        assert_eq!(services.len(), 1);
+        assert_eq!(services[0].0, "route.Router");
+        let methods: Vec<&str> = services[0].1.iter().map(|val| val.name()).collect();
+        assert_eq!(methods, vec!["Get", "Watch", "Upload", "Chat"]);
+
+        assert_eq!(
+            proto_pool(&[], &[]).unwrap_err(),
+            "No .proto files selected"
+        );
+        assert!(proto_pool(&["/missing/route.proto".into()], &[])
+            .unwrap_err()
+            .starts_with("Could not compile .proto files"));
+    }
+
+    #[test]
+    fn method_lookup() {
+        let pool = pool("lookup");
+        let method = find_method(&pool, "route.Router", "Get").unwrap();
+        assert_eq!(method_path(&method).unwrap().as_str(), "/route.Router/Get");
+        assert_eq!(method.input().full_name(), "route.Request");
+        assert_eq!(method.output().full_name(), "route.Reply");
+
+        let kinds: Vec<GrpcCallKind> = ["Get", "Watch", "Upload", "Chat"]
+            .iter()
+            .map(|name| {
+                GrpcCallKind::from_method(&find_method(&pool, "route.Router", name).unwrap())
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                GrpcCallKind::Unary,
+                GrpcCallKind::ServerStreaming,
+                GrpcCallKind::ClientStreaming,
+                GrpcCallKind::Bidi,
+            ]
+        );
+
+        assert_eq!(
+            find_method(&pool, "route.Missing", "Get").unwrap_err(),
+            "Service route.Missing not found"
+        );
+        assert_eq!(
+            find_method(&pool, "route.Router", "Missing").unwrap_err(),
+            "Method Missing not found in service route.Router"
+        );
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let pool = pool("json");
+        let method = find_method(&pool, "route.Router", "Get").unwrap();
+
+        // int64 written as string in protobuf JSON mapping
+        let json = r#"{"name": "square", "points": [{"x": "1", "y": 2}], "closed": true}"#;
+        let message = message_from_json(method.input(), json).unwrap();
+        let decoded =
+            DynamicMessage::decode(method.input(), message.encode_to_vec().as_slice()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&message_to_json(&decoded)).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "name": "square",
+                "points": [{"x": "1", "y": "2"}],
+                "closed": true,
+            })
+        );
+
+        // Empty text is default message, defaults shown as template
+        let message = message_from_json(method.input(), " ").unwrap();
+        let value: serde_json::Value = serde_json::from_str(&message_to_json(&message)).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({"name": "", "points": [], "closed": false})
+        );
+
+        assert!(message_from_json(method.input(), r#"{"unknown": 1}"#)
+            .unwrap_err()
+            .starts_with("Message does not match .proto definition"));
+    }
+
+    #[test]
+    fn metadata_headers() {
+        let headers = vec![Header {
+            key: "X-Trace".into(),
+            value: "42".into(),
+        }];
+        let result = metadata_to_headers(&metadata(&headers).unwrap());
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            (result[0].key.as_str(), result[0].value.as_str()),
+            ("x-trace", "42")
+        );
+
+        let headers = vec![Header {
+            key: "bad key".into(),
+            value: "1".into(),
+        }];
+        assert!(metadata(&headers)
+            .unwrap_err()
+            .starts_with("Invalid metadata key bad key"));
+    }
+}
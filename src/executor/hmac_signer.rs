@@ -0,0 +1,259 @@
+/// HMAC request signing with user defined string to sign
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, SecondsFormat, Utc};
+use hmac::{digest::KeyInit, Hmac, Mac};
+use md5::Md5;
+use reqwest::{header::HeaderMap, Url};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::states::main_page::{
+    generics::Header,
+    request::request_data::{HmacAlgorithm, HmacAuth, HmacEncoding, HmacTimestamp},
+};
+
+/// Placeholders of string to sign, shown in UI
+pub const PLACEHOLDERS: &str = "{method} {host} {path} {query} {url} {timestamp} {body} {body_sha256} {body_md5} {header:Name}";
+
+/// Timestamp and signature headers to insert into request.
+/// payload - body bytes, None for streamed body
+pub fn sign(
+    config: &HmacAuth,
+    method: &str,
+    url: &Url,
+    headers: &HeaderMap,
+    payload: Option<&[u8]>,
+    now: DateTime<Utc>,
+) -> Result<Vec<Header>, String> {
+    if config.signature_header.trim().is_empty() {
+        return Err("HMAC: signature header is required".into());
+    }
+    let Some(payload) = payload else {
+        return Err("HMAC: body could not be read before send".into());
+    };
+
+    let key = if config.secret_base64 {
+        STANDARD
+            .decode(config.secret.trim())
+            .map_err(|err| format!("HMAC: secret is not valid base64: {err}"))?
+    } else {
+        config.secret.as_bytes().to_vec()
+    };
+
+    let timestamp = match config.timestamp_format {
+        HmacTimestamp::UNIXSECONDS => now.timestamp().to_string(),
+        HmacTimestamp::UNIXMILLIS => now.timestamp_millis().to_string(),
+        HmacTimestamp::ISO8601 => now.to_rfc3339_opts(SecondsFormat::Secs, true),
+    };
+
+    let path = url.path().to_string();
+    let query = url.query().unwrap_or_default().to_string();
+    let host = match url.host_str() {
+        Some(host) => match url.port() {
+            Some(port) => format!("{host}:{port}"),
+            None => host.to_string(),
+        },
+        None => "".into(),
+    };
+
+    let values = [
+        ("method", method.to_uppercase()),
+        ("host", host),
+        ("url", url.to_string()),
+        ("path", path),
+        ("query", query),
+        ("timestamp", timestamp.clone()),
+        ("body", String::from_utf8_lossy(payload).to_string()),
+        ("body_sha256", hex(&Sha256::digest(payload))),
+        ("body_md5", hex(&Md5::digest(payload))),
+    ];
+
+    let string_to_sign = render(&config.template, &values, headers)?;
+    let signature = match config.algorithm {
+        HmacAlgorithm::SHA1 => mac::<Hmac<Sha1>>(&key, string_to_sign.as_bytes()),
+        HmacAlgorithm::SHA256 => mac::<Hmac<Sha256>>(&key, string_to_sign.as_bytes()),
+        HmacAlgorithm::SHA512 => mac::<Hmac<Sha512>>(&key, string_to_sign.as_bytes()),
+    };
+    let signature = match config.encoding {
+        HmacEncoding::HEX => hex(&signature),
+        HmacEncoding::BASE64 => STANDARD.encode(signature),
+    };
+
+    let mut result = vec![];
+    if !config.timestamp_header.trim().is_empty() {
+        result.push(Header {
+            key: config.timestamp_header.trim().to_string(),
+            value: timestamp.clone(),
+        });
+    }
+    result.push(Header {
+        key: config.signature_header.trim().to_string(),
+        value: render(
+            &config.signature_value,
+            &[("signature", signature), ("timestamp", timestamp)],
+            headers,
+        )?,
+    });
+
+    Ok(result)
+}
+
+/// Replace {name} placeholders with values. {header:Name} takes request header
+fn render(
+    template: &str,
+    values: &[(&str, String)],
+    headers: &HeaderMap,
+) -> Result<String, String> {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            return Err(format!("HMAC: not closed placeholder in {template:?}"));
+        };
+        let name = &rest[start + 1..start + end];
+
+        if let Some(header) = name.strip_prefix("header:") {
+            let value = headers
+                .get_all(header.trim())
+                .iter()
+                .map(|value| String::from_utf8_lossy(value.as_bytes()).to_string())
+                .collect::<Vec<String>>()
+                .join(",");
+            result.push_str(&value);
+        } else {
+            match values.iter().find(|(key, _)| *key == name) {
+                Some((_, value)) => result.push_str(value),
+                None => return Err(format!("HMAC: unknown placeholder {{{name}}}")),
+            }
+        }
+
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+
+    Ok(result)
+}
+
+fn mac<M: Mac + KeyInit>(key: &[u8], data: &[u8]) -> Vec<u8> {
+    // HMAC accepts key of any length
+    let mut mac = <M as Mac>::new_from_slice(key).unwrap();
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    fn config(secret: &str, template: &str) -> HmacAuth {
+        HmacAuth {
+            algorithm: HmacAlgorithm::SHA256,
+            encoding: HmacEncoding::HEX,
+            secret: secret.into(),
+            secret_base64: false,
+            template: template.into(),
+            signature_header: "X-Signature".into(),
+            signature_value: "{signature}".into(),
+            timestamp_header: "X-Timestamp".into(),
+            timestamp_format: HmacTimestamp::UNIXSECONDS,
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn signature(config: &HmacAuth, payload: &[u8]) -> String {
+        let url = Url::parse("https://api.example.com/v1/items?b=2").unwrap();
+        let headers = sign(
+            config,
+            "post",
+            &url,
+            &HeaderMap::new(),
+            Some(payload),
+            now(),
+        )
+        .unwrap();
+        headers
+            .iter()
+            .find(|header| header.key == "X-Signature")
+            .map(|header| header.value.clone())
+            .unwrap()
+    }
+
+    #[test]
+    fn known_vector() {
+        // RFC 4231 test case 2
+        let expected = "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843";
+        let mut config = config("Jefe", "what do ya want for nothing?");
+        assert_eq!(signature(&config, b""), expected);
+
+        config.secret = "SmVmZQ==".into();
+        config.secret_base64 = true;
+        assert_eq!(signature(&config, b""), expected);
+
+        config.encoding = HmacEncoding::BASE64;
+        assert_eq!(
+            signature(&config, b""),
+            "W9zBRr9gdU5qBCQmCJV1x1oAPwidJzmDnexYuWTsOEM="
+        );
+    }
+
+    #[test]
+    fn template_placeholders() {
+        let mut config = config("secret", "{method}\n{path}\n{timestamp}\n{body_sha256}");
+        config.signature_value = "v1={signature}, t={timestamp}".into();
+        let url = Url::parse("https://api.example.com/v1/items?b=2").unwrap();
+        let headers = sign(
+            &config,
+            "post",
+            &url,
+            &HeaderMap::new(),
+            Some(br#"{"a":1}"#),
+            now(),
+        )
+        .unwrap();
+
+        assert_eq!(headers[0].key, "X-Timestamp");
+        assert_eq!(headers[0].value, "1704164645");
+        assert_eq!(headers[1].key, "X-Signature");
+        assert_eq!(
+            headers[1].value,
+            "v1=62579b4c1e9b81f7c53d76a4d34c03d6ddcef00b442e4adf0cf23acbdedaaef0, t=1704164645"
+        );
+    }
+
+    #[test]
+    fn render_values() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", HeaderValue::from_static("abc"));
+        let values = [
+            ("method", "GET".to_string()),
+            ("host", "example.com:8080".to_string()),
+            ("query", "a=1".to_string()),
+        ];
+
+        assert_eq!(
+            render(
+                "{method} {host}?{query} {header:X-Api-Key}",
+                &values,
+                &headers
+            )
+            .unwrap(),
+            "GET example.com:8080?a=1 abc"
+        );
+        assert_eq!(render("{header:Missing}.", &values, &headers).unwrap(), ".");
+        assert!(render("{nope}", &values, &headers).is_err());
+        assert!(render("{method", &values, &headers).is_err());
+    }
+}
@@ -0,0 +1,360 @@
+/// JSON-RPC 2.0 helpers: envelope with generated ids, replies correlation
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use serde_json::{json, Map, Value};
+
+use crate::states::main_page::request::request_data::JsonRpcBody;
+
+/// Calls wich wait for reply: id -> method
+pub type JsonRpcCalls = HashMap<String, String>;
+
+/// Ids are unique for whole app session, so replies never mixed between requests
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Envelope for single call or batch.
+/// Batch expected as JSON array of `{"method": .., "params": .., "notification": bool}`
+pub fn envelope(body: &JsonRpcBody) -> Result<Value, String> {
+    if !body.batch {
+        return call(&body.method, &body.params.message, body.notification);
+    }
+
+    let items = match serde_json::from_str::<Value>(&body.params.message) {
+        Ok(Value::Array(items)) if !items.is_empty() => items,
+        Ok(_) => return Err("JSON-RPC batch must be non empty JSON array".into()),
+        Err(err) => return Err(format!("JSON-RPC batch is not valid JSON: {err}")),
+    };
+
+    let mut batch = vec![];
+    for item in items {
+        let method = match item.get("method").and_then(|val| val.as_str()) {
+            Some(val) => val,
+            None => return Err(format!("JSON-RPC batch item without method: {item}")),
+        };
+        let params = match item.get("params") {
+            Some(val) => val.to_string(),
+            None => "".into(),
+        };
+        let notification = item
+            .get("notification")
+            .and_then(|val| val.as_bool())
+            .unwrap_or(false);
+
+        batch.push(call(method, &params, notification)?);
+    }
+
+    Ok(Value::Array(batch))
+}
+
+/// Single call. Notification sent without id
+fn call(method: &str, params: &str, notification: bool) -> Result<Value, String> {
+    if method.trim().is_empty() {
+        return Err("JSON-RPC method is empty".into());
+    }
+
+    let mut call = Map::new();
+    call.insert("jsonrpc".into(), Value::from("2.0"));
+    call.insert("method".into(), Value::from(method.trim()));
+
+    if !params.trim().is_empty() {
+        match serde_json::from_str::<Value>(params) {
+            Ok(params) if params.is_array() || params.is_object() => {
+                call.insert("params".into(), params);
+            }
+            Ok(_) => return Err("JSON-RPC params must be JSON array or object".into()),
+            Err(err) => return Err(format!("JSON-RPC params is not valid JSON: {err}")),
+        }
+    }
+
+    if !notification {
+        call.insert(
+            "id".into(),
+            Value::from(NEXT_ID.fetch_add(1, Ordering::Relaxed)),
+        );
+    }
+
+    Ok(Value::Object(call))
+}
+
+/// Calls from sent envelope, wich wait for reply
+pub fn pending_calls(text: &str) -> JsonRpcCalls {
+    let envelope = match serde_json::from_str::<Value>(text) {
+        Ok(val) => val,
+        Err(_) => return JsonRpcCalls::new(),
+    };
+    let calls = match &envelope {
+        Value::Array(items) => items.iter().collect(),
+        other => vec![other],
+    };
+
+    calls
+        .into_iter()
+        .filter_map(|call| {
+            let id = id_to_string(call.get("id")?);
+            let method = call.get("method")?.as_str()?.to_string();
+            Some((id, method))
+        })
+        .collect()
+}
+
+/// Parsed incoming message
+#[derive(Debug, PartialEq)]
+pub enum JsonRpcMessage {
+    Result {
+        id: String,
+        result: Value,
+    },
+    /// Error could be without id, when server could not parse request
+    Error {
+        id: Option<String>,
+        error: Value,
+    },
+    /// Server side call without id
+    Notification {
+        method: String,
+        params: Value,
+    },
+    Unknown(Value),
+}
+
+impl JsonRpcMessage {
+    /// Label for response list. Calls methods taken from pending calls
+    pub fn label(&self, calls: &JsonRpcCalls) -> String {
+        let method = |id: &str| match calls.get(id) {
+            Some(method) => format!("{method} "),
+            None => "".into(),
+        };
+
+        match self {
+            JsonRpcMessage::Result { id, result: _ } => format!("result {}#{id}", method(id)),
+            JsonRpcMessage::Error {
+                id: Some(id),
+                error: _,
+            } => format!("error {}#{id}", method(id)),
+            JsonRpcMessage::Error { id: None, error: _ } => "error".into(),
+            JsonRpcMessage::Notification { method, params: _ } => {
+                format!("notification {method}")
+            }
+            JsonRpcMessage::Unknown(_) => "unknown".into(),
+        }
+    }
+
+    /// Payload to show in response
+    pub fn data(&self) -> String {
+        match self {
+            JsonRpcMessage::Result { id: _, result } => result.to_string(),
+            JsonRpcMessage::Error { id: _, error } => error.to_string(),
+            JsonRpcMessage::Notification { method: _, params } => params.to_string(),
+            JsonRpcMessage::Unknown(value) => value.to_string(),
+        }
+    }
+
+    /// Id of call this message replies on
+    pub fn id(&self) -> Option<&String> {
+        match self {
+            JsonRpcMessage::Result { id, result: _ } => Some(id),
+            JsonRpcMessage::Error { id, error: _ } => id.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// Label for HTTP reply: single reply labeled as WS one, batch - with amount of replies
+pub fn reply_label(text: &str, calls: &JsonRpcCalls) -> Option<String> {
+    match parse_messages(text) {
+        Ok(messages) if messages.len() == 1 => Some(messages[0].label(calls)),
+        Ok(messages) if !messages.is_empty() => Some(format!("batch {} replies", messages.len())),
+        _ => None,
+    }
+}
+
+/// Parse incoming text. Batch replies split to separate messages
+pub fn parse_messages(text: &str) -> Result<Vec<JsonRpcMessage>, String> {
+    let value = match serde_json::from_str::<Value>(text) {
+        Ok(val) => val,
+        Err(err) => return Err(format!("JSON-RPC message is not valid JSON: {err}")),
+    };
+
+    let items = match value {
+        Value::Array(items) => items,
+        other => vec![other],
+    };
+
+    Ok(items.into_iter().map(parse_message).collect())
+}
+
+fn parse_message(value: Value) -> JsonRpcMessage {
+    let id = value.get("id").filter(|id| !id.is_null()).map(id_to_string);
+
+    if let Some(error) = value.get("error") {
+        return JsonRpcMessage::Error {
+            id,
+            error: error.clone(),
+        };
+    }
+
+    match (id, value.get("result"), value.get("method")) {
+        (Some(id), Some(result), _) => JsonRpcMessage::Result {
+            id,
+            result: result.clone(),
+        },
+        // Server side requests shown as notifications too
+        (_, None, Some(Value::String(method))) => JsonRpcMessage::Notification {
+            method: method.clone(),
+            params: value.get("params").cloned().unwrap_or(json!(null)),
+        },
+        _ => JsonRpcMessage::Unknown(value),
+    }
+}
+
+/// Ids could be numbers or strings, both compared as text
+fn id_to_string(id: &Value) -> String {
+    match id {
+        Value::String(val) => val.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body(method: &str, params: &str, notification: bool, batch: bool) -> JsonRpcBody {
+        let mut body = JsonRpcBody {
+            method: method.into(),
+            notification,
+            batch,
+            ..Default::default()
+        };
+        body.params.set(params.into());
+        body
+    }
+
+    /// Ids are shared between tests running in parallel, so taken out to compare
+    fn take_id(call: &mut Value) -> Option<u64> {
+        call.as_object_mut()?.remove("id")?.as_u64()
+    }
+
+    #[test]
+    fn single_call() {
+        let mut call = envelope(&body(" sum ", "[1, 2]", false, false)).unwrap();
+        assert!(take_id(&mut call).is_some());
+        assert_eq!(
+            call,
+            json!({"jsonrpc": "2.0", "method": "sum", "params": [1, 2]})
+        );
+
+        let notification = envelope(&body("ping", "", true, false)).unwrap();
+        assert_eq!(notification, json!({"jsonrpc": "2.0", "method": "ping"}));
+    }
+
+    #[test]
+    fn batch() {
+        let params = r#"[
+            {"method": "a", "params": {"x": 1}},
+            {"method": "b", "notification": true},
+            {"method": "c"}
+        ]"#;
+        let Value::Array(mut calls) = envelope(&body("", params, false, true)).unwrap() else {
+            panic!("batch must be array");
+        };
+
+        let first = take_id(&mut calls[0]).unwrap();
+        assert_eq!(take_id(&mut calls[1]), None);
+        let third = take_id(&mut calls[2]).unwrap();
+        assert!(third > first);
+        assert_eq!(
+            calls,
+            vec![
+                json!({"jsonrpc": "2.0", "method": "a", "params": {"x": 1}}),
+                json!({"jsonrpc": "2.0", "method": "b"}),
+                json!({"jsonrpc": "2.0", "method": "c"}),
+            ]
+        );
+    }
+
+    #[test]
+    fn envelope_errors() {
+        let error =
+            |method, params, batch| envelope(&body(method, params, false, batch)).unwrap_err();
+
+        assert_eq!(error(" ", "", false), "JSON-RPC method is empty");
+        assert_eq!(
+            error("a", "1", false),
+            "JSON-RPC params must be JSON array or object"
+        );
+        assert!(error("a", "{", false).starts_with("JSON-RPC params is not valid JSON"));
+        assert_eq!(
+            error("", "[]", true),
+            "JSON-RPC batch must be non empty JSON array"
+        );
+        assert_eq!(
+            error("", r#"[{"params": []}]"#, true),
+            r#"JSON-RPC batch item without method: {"params":[]}"#
+        );
+        assert!(error("", "[", true).starts_with("JSON-RPC batch is not valid JSON"));
+    }
+
+    #[test]
+    fn messages() {
+        let text = r#"[
+            {"jsonrpc": "2.0", "id": 1, "result": 3},
+            {"jsonrpc": "2.0", "id": "x", "error": {"code": -32601}},
+            {"jsonrpc": "2.0", "id": null, "error": {"code": -32700}},
+            {"jsonrpc": "2.0", "method": "update", "params": [5]},
+            {"jsonrpc": "2.0", "id": 2}
+        ]"#;
+        assert_eq!(
+            parse_messages(text).unwrap(),
+            vec![
+                JsonRpcMessage::Result {
+                    id: "1".into(),
+                    result: json!(3),
+                },
+                JsonRpcMessage::Error {
+                    id: Some("x".into()),
+                    error: json!({"code": -32601}),
+                },
+                JsonRpcMessage::Error {
+                    id: None,
+                    error: json!({"code": -32700}),
+                },
+                JsonRpcMessage::Notification {
+                    method: "update".into(),
+                    params: json!([5]),
+                },
+                JsonRpcMessage::Unknown(json!({"jsonrpc": "2.0", "id": 2})),
+            ]
+        );
+        assert!(parse_messages("nope")
+            .unwrap_err()
+            .starts_with("JSON-RPC message is not valid JSON"));
+    }
+
+    #[test]
+    fn labels_from_pending_calls() {
+        let calls = pending_calls(
+            r#"[{"jsonrpc": "2.0", "id": 7, "method": "sum"}, {"jsonrpc": "2.0", "method": "log"}]"#,
+        );
+        assert_eq!(calls, JsonRpcCalls::from([("7".into(), "sum".into())]));
+
+        assert_eq!(
+            reply_label(r#"{"id": 7, "result": 3}"#, &calls),
+            Some("result sum #7".into())
+        );
+        assert_eq!(
+            reply_label(r#"{"id": 8, "error": {}}"#, &calls),
+            Some("error #8".into())
+        );
+        assert_eq!(
+            reply_label(
+                r#"[{"id": 7, "result": 3}, {"id": 8, "result": 4}]"#,
+                &calls
+            ),
+            Some("batch 2 replies".into())
+        );
+        assert_eq!(reply_label("[]", &calls), None);
+    }
+}
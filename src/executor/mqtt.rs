@@ -0,0 +1,426 @@
+/// MQTT 3.1.1 and 5 client session helpers, on top of rumqttc
+use std::time::Duration;
+
+use rumqttc::{v5, AsyncClient, Event, EventLoop, MqttOptions, Packet, Transport};
+
+use crate::{
+    settings::main_settings::entity::request_settings::protocol_settings::Protocol,
+    states::main_page::{
+        generics::Header,
+        request::{MqttCommand, MqttVersion, RequestMqttSetup},
+    },
+};
+
+/// Capacity of client requests queue
+const REQUESTS_CAPACITY: usize = 100;
+
+/// Incoming packets, wich matter for user
+#[derive(Debug)]
+pub enum MqttEvent {
+    ConnAck {
+        success: bool,
+        code: String,
+        session_present: bool,
+    },
+    Publish {
+        topic: String,
+        payload: String,
+        headers: Vec<Header>,
+    },
+    SubAck {
+        success: bool,
+        codes: String,
+    },
+    UnsubAck,
+    /// Pings, acks of own publishes and outgoing packets
+    Other,
+}
+
+/// Client to send packets, for selected protocol version
+pub enum MqttClient {
+    V311(AsyncClient),
+    V5(v5::AsyncClient),
+}
+
+/// Network side of session, must be polled to make progress.
+/// Event loops keep buffers inline, so boxed to keep enum small
+pub enum MqttEventLoop {
+    V311(Box<EventLoop>),
+    V5(Box<v5::EventLoop>),
+}
+
+/// Prepare session. Connection happens on first poll
+pub fn session(
+    protocol: &Protocol,
+    uri: &str,
+    setup: &RequestMqttSetup,
+) -> Result<(MqttClient, MqttEventLoop), String> {
+    let (host, port) = host_port(protocol, uri)?;
+    let client_id = match setup.client_id.trim() {
+        "" => format!("frapi-{}", &uuid::Uuid::new_v4().simple().to_string()[..8]),
+        val => val.to_string(),
+    };
+    let keep_alive = Duration::from_secs(setup.keep_alive());
+
+    match setup.version {
+        MqttVersion::V311 => {
+            let mut options = MqttOptions::new(client_id, host, port);
+            options
+                .set_keep_alive(keep_alive)
+                .set_clean_session(setup.clean_session);
+            if !setup.username.is_empty() {
+                options.set_credentials(setup.username.clone(), setup.password.clone());
+            }
+            if *protocol == Protocol::MQTTS {
+                options.set_transport(Transport::tls_with_default_config());
+            }
+
+            let (client, eventloop) = AsyncClient::new(options, REQUESTS_CAPACITY);
+            Ok((
+                MqttClient::V311(client),
+                MqttEventLoop::V311(Box::new(eventloop)),
+            ))
+        }
+        MqttVersion::V5 => {
+            let mut options = v5::MqttOptions::new(client_id, host, port);
+            options
+                .set_keep_alive(keep_alive)
+                .set_clean_start(setup.clean_session);
+            if !setup.username.is_empty() {
+                options.set_credentials(setup.username.clone(), setup.password.clone());
+            }
+            if *protocol == Protocol::MQTTS {
+                options.set_transport(Transport::tls_with_default_config());
+            }
+
+            let (client, eventloop) = v5::AsyncClient::new(options, REQUESTS_CAPACITY);
+            Ok((
+                MqttClient::V5(client),
+                MqttEventLoop::V5(Box::new(eventloop)),
+            ))
+        }
+    }
+}
+
+impl MqttEventLoop {
+    /// Wait for next network event
+    pub async fn poll(&mut self) -> Result<MqttEvent, String> {
+        match self {
+            MqttEventLoop::V311(eventloop) => match eventloop.poll().await {
+                Ok(Event::Incoming(packet)) => Ok(v311_event(packet)),
+                Ok(Event::Outgoing(_)) => Ok(MqttEvent::Other),
+                Err(err) => Err(format!("MQTT connection error: {err}")),
+            },
+            MqttEventLoop::V5(eventloop) => match eventloop.poll().await {
+                Ok(v5::Event::Incoming(packet)) => Ok(v5_event(packet)),
+                Ok(v5::Event::Outgoing(_)) => Ok(MqttEvent::Other),
+                Err(err) => Err(format!("MQTT connection error: {err}")),
+            },
+        }
+    }
+}
+
+impl MqttClient {
+    /// Send packet built from message setup: publish payload, subscribe or unsubscribe topic
+    pub async fn send(&self, setup: &RequestMqttSetup, payload: &str) -> Result<(), String> {
+        let topic = setup.topic.trim();
+        if topic.is_empty() {
+            return Err(format!("MQTT {} requires topic", setup.command));
+        }
+
+        let result = match self {
+            MqttClient::V311(client) => {
+                let qos = rumqttc::qos(setup.qos).unwrap_or(rumqttc::QoS::AtMostOnce);
+                match setup.command {
+                    MqttCommand::PUBLISH => {
+                        client
+                            .publish(topic, qos, setup.retain, payload.to_string())
+                            .await
+                    }
+                    MqttCommand::SUBSCRIBE => client.subscribe(topic, qos).await,
+                    MqttCommand::UNSUBSCRIBE => client.unsubscribe(topic).await,
+                }
+                .map_err(|err| err.to_string())
+            }
+            MqttClient::V5(client) => {
+                let qos = v5::mqttbytes::qos(setup.qos).unwrap_or(v5::mqttbytes::QoS::AtMostOnce);
+                match setup.command {
+                    MqttCommand::PUBLISH => {
+                        client
+                            .publish(topic, qos, setup.retain, payload.to_string())
+                            .await
+                    }
+                    MqttCommand::SUBSCRIBE => client.subscribe(topic, qos).await,
+                    MqttCommand::UNSUBSCRIBE => client.unsubscribe(topic).await,
+                }
+                .map_err(|err| err.to_string())
+            }
+        };
+
+        result.map_err(|err| format!("Could not send MQTT {}: {err}", setup.command))
+    }
+
+    /// Queue DISCONNECT packet, it is sent by next polls
+    pub async fn disconnect(&self) -> Result<(), String> {
+        match self {
+            MqttClient::V311(client) => client.disconnect().await.map_err(|err| err.to_string()),
+            MqttClient::V5(client) => client.disconnect().await.map_err(|err| err.to_string()),
+        }
+    }
+}
+
+/// Broker host and port from uri. Default ports: 1883 for MQTT, 8883 for MQTTS.
+/// IPv6 address could be bare `::1` or in brackets `[::1]:1883`
+pub fn host_port(protocol: &Protocol, uri: &str) -> Result<(String, u16), String> {
+    let authority = uri.split(['/', '?']).next().unwrap_or_default();
+    if authority.is_empty() {
+        return Err("MQTT broker host is empty".into());
+    }
+
+    let (host, port) = if let Some(bracketed) = authority.strip_prefix('[') {
+        match bracketed.split_once(']') {
+            Some((host, "")) => (host, None),
+            Some((host, rest)) => match rest.strip_prefix(':') {
+                Some(port) => (host, Some(port)),
+                None => return Err(format!("Invalid MQTT broker address: {authority}")),
+            },
+            None => return Err(format!("Invalid MQTT broker address: {authority}")),
+        }
+    } else {
+        match authority.split_once(':') {
+            // Several colons only in bare IPv6 address
+            Some((host, port)) if !port.contains(':') => (host, Some(port)),
+            _ => (authority, None),
+        }
+    };
+    if host.is_empty() {
+        return Err("MQTT broker host is empty".into());
+    }
+
+    let port = match port {
+        Some(port) => match port.parse::<u16>() {
+            Ok(port) => port,
+            Err(_) => return Err(format!("Invalid MQTT broker port: {port}")),
+        },
+        None => match protocol {
+            Protocol::MQTTS => 8883,
+            _ => 1883,
+        },
+    };
+    Ok((host.to_string(), port))
+}
+
+fn v311_event(packet: Packet) -> MqttEvent {
+    match packet {
+        Packet::ConnAck(connack) => MqttEvent::ConnAck {
+            success: connack.code == rumqttc::ConnectReturnCode::Success,
+            code: format!("{:?}", connack.code),
+            session_present: connack.session_present,
+        },
+        Packet::Publish(publish) => MqttEvent::Publish {
+            topic: publish.topic,
+            payload: String::from_utf8_lossy(&publish.payload).to_string(),
+            headers: vec![
+                header("qos", &(publish.qos as u8).to_string()),
+                header("retain", &publish.retain.to_string()),
+            ],
+        },
+        Packet::SubAck(suback) => MqttEvent::SubAck {
+            success: !suback
+                .return_codes
+                .contains(&rumqttc::SubscribeReasonCode::Failure),
+            codes: format!("{:?}", suback.return_codes),
+        },
+        Packet::UnsubAck(_) => MqttEvent::UnsubAck,
+        _ => MqttEvent::Other,
+    }
+}
+
+fn v5_event(packet: v5::mqttbytes::v5::Packet) -> MqttEvent {
+    use v5::mqttbytes::v5::{ConnectReturnCode, Packet, SubscribeReasonCode};
+
+    match packet {
+        Packet::ConnAck(connack) => MqttEvent::ConnAck {
+            success: connack.code == ConnectReturnCode::Success,
+            code: format!("{:?}", connack.code),
+            session_present: connack.session_present,
+        },
+        Packet::Publish(publish) => {
+            let mut headers = vec![
+                header("qos", &(publish.qos as u8).to_string()),
+                header("retain", &publish.retain.to_string()),
+            ];
+            if let Some(properties) = publish.properties {
+                if let Some(content_type) = properties.content_type {
+                    headers.push(header("content-type", &content_type));
+                }
+                if let Some(response_topic) = properties.response_topic {
+                    headers.push(header("response-topic", &response_topic));
+                }
+                for (key, value) in properties.user_properties {
+                    headers.push(header(&key, &value));
+                }
+            }
+
+            MqttEvent::Publish {
+                topic: String::from_utf8_lossy(&publish.topic).to_string(),
+                payload: String::from_utf8_lossy(&publish.payload).to_string(),
+                headers,
+            }
+        }
+        Packet::SubAck(suback) => MqttEvent::SubAck {
+            success: suback
+                .return_codes
+                .iter()
+                .all(|code| matches!(code, SubscribeReasonCode::Success(_))),
+            codes: format!("{:?}", suback.return_codes),
+        },
+        Packet::UnsubAck(_) => MqttEvent::UnsubAck,
+        _ => MqttEvent::Other,
+    }
+}
+
+fn header(key: &str, value: &str) -> Header {
+    Header {
+        key: key.into(),
+        value: value.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rumqttc::{
+        v5::mqttbytes::{
+            v5::{self as mqtt5, PublishProperties},
+            QoS as QoS5,
+        },
+        Publish, QoS, SubAck, SubscribeReasonCode,
+    };
+
+    use super::*;
+
+    fn pairs(headers: &[Header]) -> Vec<(&str, &str)> {
+        headers
+            .iter()
+            .map(|header| (header.key.as_str(), header.value.as_str()))
+            .collect()
+    }
+
+    fn host(protocol: &Protocol, uri: &str) -> (String, u16) {
+        host_port(protocol, uri).unwrap()
+    }
+
+    #[test]
+    fn broker_address() {
+        assert_eq!(
+            host(&Protocol::MQTT, "broker.local"),
+            ("broker.local".into(), 1883)
+        );
+        assert_eq!(
+            host(&Protocol::MQTTS, "broker.local/ignored"),
+            ("broker.local".into(), 8883)
+        );
+        assert_eq!(
+            host(&Protocol::MQTT, "10.0.0.1:1884?a=1"),
+            ("10.0.0.1".into(), 1884)
+        );
+        assert_eq!(host(&Protocol::MQTT, "[::1]:1885"), ("::1".into(), 1885));
+        assert_eq!(
+            host(&Protocol::MQTTS, "[fe80::1]"),
+            ("fe80::1".into(), 8883)
+        );
+        assert_eq!(host(&Protocol::MQTT, "::1"), ("::1".into(), 1883));
+        assert_eq!(
+            host(&Protocol::MQTT, "2001:db8::5/topic"),
+            ("2001:db8::5".into(), 1883)
+        );
+    }
+
+    #[test]
+    fn broker_address_errors() {
+        let error = |uri| host_port(&Protocol::MQTT, uri).unwrap_err();
+        assert_eq!(error(""), "MQTT broker host is empty");
+        assert_eq!(error(":1883"), "MQTT broker host is empty");
+        assert_eq!(error("host:port"), "Invalid MQTT broker port: port");
+        assert_eq!(error("host:70000"), "Invalid MQTT broker port: 70000");
+        assert_eq!(error("[::1"), "Invalid MQTT broker address: [::1");
+        assert_eq!(error("[::1]1883"), "Invalid MQTT broker address: [::1]1883");
+    }
+
+    #[test]
+    fn v311_packets() {
+        let mut publish = Publish::new("a/b", QoS::AtLeastOnce, "hello");
+        publish.retain = true;
+        let MqttEvent::Publish {
+            topic,
+            payload,
+            headers,
+        } = v311_event(Packet::Publish(publish))
+        else {
+            panic!("publish expected");
+        };
+        assert_eq!((topic.as_str(), payload.as_str()), ("a/b", "hello"));
+        assert_eq!(pairs(&headers), vec![("qos", "1"), ("retain", "true")]);
+
+        let suback = SubAck::new(
+            1,
+            vec![
+                SubscribeReasonCode::Success(QoS::ExactlyOnce),
+                SubscribeReasonCode::Failure,
+            ],
+        );
+        assert!(matches!(
+            v311_event(Packet::SubAck(suback)),
+            MqttEvent::SubAck { success: false, .. }
+        ));
+    }
+
+    #[test]
+    fn v5_publish_properties() {
+        let properties = PublishProperties {
+            content_type: Some("application/json".into()),
+            response_topic: Some("replies".into()),
+            user_properties: vec![("trace".into(), "42".into())],
+            ..Default::default()
+        };
+        let publish = mqtt5::Publish::new("a/b", QoS5::ExactlyOnce, "{}", Some(properties));
+        let MqttEvent::Publish {
+            topic,
+            payload,
+            headers,
+        } = v5_event(mqtt5::Packet::Publish(publish))
+        else {
+            panic!("publish expected");
+        };
+        assert_eq!((topic.as_str(), payload.as_str()), ("a/b", "{}"));
+        assert_eq!(
+            pairs(&headers),
+            vec![
+                ("qos", "2"),
+                ("retain", "false"),
+                ("content-type", "application/json"),
+                ("response-topic", "replies"),
+                ("trace", "42"),
+            ]
+        );
+
+        let suback = mqtt5::SubAck {
+            pkid: 1,
+            return_codes: vec![mqtt5::SubscribeReasonCode::Success(QoS5::AtMostOnce)],
+            properties: None,
+        };
+        assert!(matches!(
+            v5_event(mqtt5::Packet::SubAck(suback)),
+            MqttEvent::SubAck { success: true, .. }
+        ));
+        let suback = mqtt5::SubAck {
+            pkid: 2,
+            return_codes: vec![mqtt5::SubscribeReasonCode::NotAuthorized],
+            properties: None,
+        };
+        assert!(matches!(
+            v5_event(mqtt5::Packet::SubAck(suback)),
+            MqttEvent::SubAck { success: false, .. }
+        ));
+    }
+}
@@ -0,0 +1,575 @@
+/// OAuth 2.0 tokens acquiring, caching and refreshing
+use std::{
+    collections::HashMap,
+    process,
+    sync::{Arc, LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use reqwest::{Client, Url};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    time,
+};
+
+use crate::states::{
+    main_page::request::request_data::{OAuth2Auth, OAuth2Grant},
+    Events,
+};
+
+/// Token refreshed this long before expiry, seconds
+const REFRESH_MARGIN: u64 = 30;
+/// Time to complete authorization in browser, seconds
+const AUTH_CODE_TIMEOUT: u64 = 300;
+/// Path of local redirect listener
+const REDIRECT_PATH: &str = "/callback";
+
+/// Tokens shared by all requests with same configuration
+static TOKENS: LazyLock<Mutex<HashMap<String, OAuth2Token>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone)]
+pub struct OAuth2Token {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    /// None - server did not report lifetime
+    pub expires_at: Option<Instant>,
+}
+
+impl OAuth2Token {
+    /// Seconds left before expiry, if known
+    pub fn expires_in(&self) -> Option<u64> {
+        self.expires_at
+            .map(|at| at.saturating_duration_since(Instant::now()).as_secs())
+    }
+
+    fn is_fresh(&self) -> bool {
+        match self.expires_in() {
+            Some(secs) => secs > REFRESH_MARGIN,
+            None => true,
+        }
+    }
+}
+
+/// Token from cache, without acquiring
+pub fn cached_token(config: &OAuth2Auth) -> Option<OAuth2Token> {
+    TOKENS.lock().unwrap().get(&cache_key(config)).cloned()
+}
+
+/// Drop cached token, next request acquires new one
+pub fn clear_token(config: &OAuth2Auth) {
+    TOKENS.lock().unwrap().remove(&cache_key(config));
+}
+
+/// Redirect uri registered for local listener on given port
+pub fn redirect_uri(port: u16) -> String {
+    format!("http://127.0.0.1:{port}{REDIRECT_PATH}")
+}
+
+/// Acquire new token in background, replacing cached one
+pub fn fetch_token(config: OAuth2Auth, events: Arc<Mutex<Events>>) {
+    clear_token(&config);
+
+    tokio::spawn(async move {
+        if let Err(err) = access_token(&config, &events).await {
+            events.lock().unwrap().event_error(&format!("Error: {err}"));
+        }
+    });
+}
+
+/// Valid access token: cached, refreshed or newly acquired with configured grant
+pub async fn access_token(
+    config: &OAuth2Auth,
+    events: &Arc<Mutex<Events>>,
+) -> Result<String, String> {
+    let cached = cached_token(config);
+    if let Some(token) = &cached {
+        if token.is_fresh() {
+            return Ok(token.access_token.clone());
+        }
+    }
+
+    let mut token = None;
+    if let Some(refresh_token) = cached.and_then(|token| token.refresh_token) {
+        events
+            .lock()
+            .unwrap()
+            .event_info(&"OAuth2: refreshing token...".into());
+        match refresh(config, &refresh_token).await {
+            Ok(val) => token = Some(val),
+            Err(err) => events.lock().unwrap().event_warning(&format!(
+                "OAuth2: refresh failed, requesting new token. Error: {err}"
+            )),
+        }
+    }
+
+    let token = match token {
+        Some(val) => val,
+        None => {
+            events.lock().unwrap().event_info(&format!(
+                "OAuth2: requesting token with {}...",
+                config.grant
+            ));
+            match config.grant {
+                OAuth2Grant::CLIENTCREDENTIALS => {
+                    request_token(config, vec![("grant_type", "client_credentials".into())]).await?
+                }
+                OAuth2Grant::PASSWORD => {
+                    request_token(
+                        config,
+                        vec![
+                            ("grant_type", "password".into()),
+                            ("username", config.username.clone()),
+                            ("password", config.password.clone()),
+                        ],
+                    )
+                    .await?
+                }
+                OAuth2Grant::AUTHCODE => authorization_code(config, events).await?,
+            }
+        }
+    };
+
+    events
+        .lock()
+        .unwrap()
+        .event_info(&match token.expires_in() {
+            Some(secs) => format!("OAuth2: token acquired. Expires in {secs} s"),
+            None => "OAuth2: token acquired".into(),
+        });
+    let access_token = token.access_token.clone();
+    TOKENS.lock().unwrap().insert(cache_key(config), token);
+
+    Ok(access_token)
+}
+
+/// Exchange refresh token. Old refresh token kept, if server did not rotate it
+async fn refresh(config: &OAuth2Auth, refresh_token: &str) -> Result<OAuth2Token, String> {
+    let mut token = request_token(
+        config,
+        vec![
+            ("grant_type", "refresh_token".into()),
+            ("refresh_token", refresh_token.into()),
+        ],
+    )
+    .await?;
+    if token.refresh_token.is_none() {
+        token.refresh_token = Some(refresh_token.into());
+    }
+    Ok(token)
+}
+
+/// Authorization code grant with PKCE.
+/// Code received by local listener on 127.0.0.1, after user authorized in browser
+async fn authorization_code(
+    config: &OAuth2Auth,
+    events: &Arc<Mutex<Events>>,
+) -> Result<OAuth2Token, String> {
+    let listener = match TcpListener::bind(("127.0.0.1", config.redirect_port())).await {
+        Ok(val) => val,
+        Err(err) => return Err(format!("Could not start OAuth2 redirect listener: {err}")),
+    };
+    let port = match listener.local_addr() {
+        Ok(addr) => addr.port(),
+        Err(err) => return Err(format!("Could not start OAuth2 redirect listener: {err}")),
+    };
+    let redirect_uri = redirect_uri(port);
+
+    let (verifier, challenge) = pkce_pair();
+    let state = uuid::Uuid::new_v4().simple().to_string();
+
+    let mut auth_url = match Url::parse(config.auth_url.trim()) {
+        Ok(val) => val,
+        Err(err) => return Err(format!("Invalid OAuth2 authorization URL: {err}")),
+    };
+    {
+        let mut query = auth_url.query_pairs_mut();
+        query
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &config.client_id)
+            .append_pair("redirect_uri", &redirect_uri)
+            .append_pair("state", &state)
+            .append_pair("code_challenge", &challenge)
+            .append_pair("code_challenge_method", "S256");
+        if !config.scope.trim().is_empty() {
+            query.append_pair("scope", config.scope.trim());
+        }
+    }
+
+    events.lock().unwrap().event_info(&format!(
+        "OAuth2: authorize in browser, waiting redirect to {redirect_uri}. URL: {auth_url}"
+    ));
+    open_browser(auth_url.as_str());
+
+    let code = match time::timeout(
+        Duration::from_secs(AUTH_CODE_TIMEOUT),
+        wait_redirect(&listener, &state),
+    )
+    .await
+    {
+        Ok(result) => result?,
+        Err(_) => return Err("OAuth2 authorization timed out".into()),
+    };
+
+    request_token(
+        config,
+        vec![
+            ("grant_type", "authorization_code".into()),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("code_verifier", verifier),
+        ],
+    )
+    .await
+}
+
+/// Accept browser redirects until one with authorization result arrives
+async fn wait_redirect(listener: &TcpListener, state: &str) -> Result<String, String> {
+    loop {
+        let (mut stream, _) = match listener.accept().await {
+            Ok(val) => val,
+            Err(err) => return Err(format!("OAuth2 redirect listener failed: {err}")),
+        };
+
+        let mut buffer = vec![0u8; 8192];
+        let size = stream.read(&mut buffer).await.unwrap_or(0);
+        let head = String::from_utf8_lossy(&buffer[..size]).to_string();
+        // Request line: GET /callback?code=...&state=... HTTP/1.1
+        let path = head.split_whitespace().nth(1).unwrap_or_default();
+
+        let url = match Url::parse(&format!("http://127.0.0.1{path}")) {
+            Ok(val) if val.path() == REDIRECT_PATH => val,
+            // Browsers also ask for favicon and such
+            _ => {
+                let _ = stream
+                    .write_all(
+                        b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    )
+                    .await;
+                continue;
+            }
+        };
+        let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+
+        let result = if let Some(error) = params.get("error") {
+            Err(format!(
+                "OAuth2 authorization denied: {error} {}",
+                params.get("error_description").cloned().unwrap_or_default()
+            ))
+        } else if params.get("state").map(|val| val.as_str()) != Some(state) {
+            Err("OAuth2 redirect state mismatch".into())
+        } else {
+            match params.get("code") {
+                Some(code) => Ok(code.clone()),
+                None => Err("OAuth2 redirect without code".into()),
+            }
+        };
+
+        let page = match &result {
+            Ok(_) => "Authorization completed. You can close this window and return to FrAPI.",
+            Err(_) => "Authorization failed. Details are in FrAPI events.",
+        };
+        let _ = stream
+            .write_all(
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{page}",
+                    page.len()
+                )
+                .as_bytes(),
+            )
+            .await;
+
+        return result;
+    }
+}
+
+/// POST token request. Client authenticated by Basic header or form fields
+async fn request_token(
+    config: &OAuth2Auth,
+    mut params: Vec<(&str, String)>,
+) -> Result<OAuth2Token, String> {
+    if config.token_url.trim().is_empty() {
+        return Err("OAuth2 token URL is empty".into());
+    }
+    // Code already bound to scopes user granted
+    let is_code_exchange = params.contains(&("grant_type", "authorization_code".into()));
+    if !config.scope.trim().is_empty() && !is_code_exchange {
+        params.push(("scope", config.scope.trim().into()));
+    }
+
+    let client = Client::new();
+    let mut request = client.post(config.token_url.trim());
+    if config.credentials_in_body || config.client_secret.is_empty() {
+        params.push(("client_id", config.client_id.clone()));
+        if !config.client_secret.is_empty() {
+            params.push(("client_secret", config.client_secret.clone()));
+        }
+    } else {
+        // RFC 6749 2.3.1: credentials form-urlencoded before Basic encoding
+        request = request.basic_auth(
+            form_urlencode(&config.client_id),
+            Some(form_urlencode(&config.client_secret)),
+        );
+    }
+
+    let response = match request
+        .header("Accept", "application/json")
+        .form(&params)
+        .send()
+        .await
+    {
+        Ok(val) => val,
+        Err(err) => return Err(format!("OAuth2 token request failed: {err}")),
+    };
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    let json: Value = serde_json::from_str(&body).unwrap_or_default();
+
+    if !status.is_success() {
+        return Err(match json.get("error").and_then(|val| val.as_str()) {
+            Some(error) => format!(
+                "OAuth2 token request rejected: {status} {error} {}",
+                json.get("error_description")
+                    .and_then(|val| val.as_str())
+                    .unwrap_or_default()
+            ),
+            None => format!("OAuth2 token request rejected: {status} {body}"),
+        });
+    }
+
+    let access_token = match json.get("access_token").and_then(|val| val.as_str()) {
+        Some(val) => val.to_string(),
+        None => {
+            return Err(format!(
+                "OAuth2 token response without access_token: {body}"
+            ))
+        }
+    };
+
+    Ok(OAuth2Token {
+        access_token,
+        refresh_token: json
+            .get("refresh_token")
+            .and_then(|val| val.as_str())
+            .map(|val| val.to_string()),
+        expires_at: json
+            .get("expires_in")
+            .and_then(|val| val.as_u64().or_else(|| val.as_str()?.parse().ok()))
+            .map(|secs| Instant::now() + Duration::from_secs(secs)),
+    })
+}
+
+/// Tokens of different servers, clients, scopes or users dont mix.
+/// Secrets kept as hash, so token is not reused after credentials changed
+fn cache_key(config: &OAuth2Auth) -> String {
+    let secrets = Sha256::digest(format!("{}|{}", config.client_secret, config.password));
+    format!(
+        "{}|{}|{}|{}|{}|{}|{}",
+        config.grant,
+        config.token_url.trim(),
+        config.auth_url.trim(),
+        config.client_id,
+        config.scope.trim(),
+        config.username,
+        URL_SAFE_NO_PAD.encode(secrets),
+    )
+}
+
+/// application/x-www-form-urlencoded value
+fn form_urlencode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'*' => {
+                (byte as char).to_string()
+            }
+            b' ' => "+".into(),
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+/// PKCE verifier and its S256 challenge
+fn pkce_pair() -> (String, String) {
+    let verifier = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    let challenge = pkce_challenge(&verifier);
+    (verifier, challenge)
+}
+
+fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// Open url in default browser. URL also reported in events, if this fails
+fn open_browser(url: &str) {
+    #[cfg(target_os = "windows")]
+    let command = process::Command::new("rundll32")
+        .args(["url.dll,FileProtocolHandler", url])
+        .spawn();
+    #[cfg(target_os = "macos")]
+    let command = process::Command::new("open").arg(url).spawn();
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let command = process::Command::new("xdg-open").arg(url).spawn();
+
+    let _ = command;
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::engine::general_purpose::STANDARD;
+
+    use super::*;
+
+    /// Local stand-in of authorization server: answers token requests with
+    /// given bodies in turn and returns received requests
+    async fn token_server(
+        replies: Vec<&'static str>,
+    ) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let url = format!("http://{}/token", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let mut requests = vec![];
+            for reply in replies {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut received = vec![];
+                let mut buffer = [0u8; 4096];
+                // Whole request: head and body of announced length
+                loop {
+                    let size = stream.read(&mut buffer).await.unwrap();
+                    received.extend_from_slice(&buffer[..size]);
+                    let text = String::from_utf8_lossy(&received).to_string();
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|line| {
+                                let (key, value) = line.split_once(':')?;
+                                key.eq_ignore_ascii_case("content-length")
+                                    .then(|| value.trim().parse::<usize>().ok())?
+                            })
+                            .unwrap_or(0);
+                        if body.len() >= length || size == 0 {
+                            requests.push(text);
+                            break;
+                        }
+                    }
+                }
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{reply}",
+                    reply.len()
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+            requests
+        });
+
+        (url, server)
+    }
+
+    fn config(token_url: String) -> OAuth2Auth {
+        OAuth2Auth {
+            token_url,
+            client_id: "my client".into(),
+            client_secret: "s3cr+t:&".into(),
+            scope: "read write".into(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn client_credentials_with_cache() {
+        let (url, server) = token_server(vec![
+            r#"{"access_token": "first", "expires_in": 3600, "token_type": "Bearer"}"#,
+            r#"{"access_token": "second"}"#,
+        ])
+        .await;
+        let events = Arc::new(Mutex::new(Events::new()));
+        let mut config = config(url);
+
+        assert_eq!(access_token(&config, &events).await.unwrap(), "first");
+        let expires_in = cached_token(&config).unwrap().expires_in().unwrap();
+        assert!(expires_in > 3500);
+        // Fresh token taken from cache, server not asked
+        assert_eq!(access_token(&config, &events).await.unwrap(), "first");
+
+        // Changed secret must not reuse token of old one
+        config.client_secret = "other".into();
+        assert!(cached_token(&config).is_none());
+        assert_eq!(access_token(&config, &events).await.unwrap(), "second");
+
+        let requests = server.await.unwrap();
+        assert_eq!(requests.len(), 2);
+        let request = requests[0].to_lowercase();
+        assert!(request.starts_with("post /token http/1.1"));
+        let credentials = STANDARD.encode("my+client:s3cr%2Bt%3A%26");
+        assert!(request.contains(&format!(
+            "authorization: basic {}",
+            credentials.to_lowercase()
+        )));
+        assert!(requests[0].ends_with("grant_type=client_credentials&scope=read+write"));
+    }
+
+    #[tokio::test]
+    async fn password_grant_credentials_in_body() {
+        let (url, server) = token_server(vec![
+            r#"{"error": "invalid_grant", "error_description": "bad password"}"#,
+        ])
+        .await;
+        let events = Arc::new(Mutex::new(Events::new()));
+        let config = OAuth2Auth {
+            grant: OAuth2Grant::PASSWORD,
+            username: "user".into(),
+            password: "pass".into(),
+            credentials_in_body: true,
+            ..config(url)
+        };
+
+        // Error in 200 reply still has no access token
+        let err = access_token(&config, &events).await.unwrap_err();
+        assert!(err.starts_with("OAuth2 token response without access_token"));
+
+        let requests = server.await.unwrap();
+        assert!(!requests[0].to_lowercase().contains("authorization:"));
+        assert!(requests[0].ends_with(
+            "grant_type=password&username=user&password=pass&scope=read+write&client_id=my+client&client_secret=s3cr%2Bt%3A%26"
+        ));
+    }
+
+    #[test]
+    fn pkce_rfc7636_vector() {
+        // RFC 7636 Appendix B
+        assert_eq!(
+            pkce_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+        let (verifier, challenge) = pkce_pair();
+        assert!(verifier.len() >= 43);
+        assert_eq!(challenge, pkce_challenge(&verifier));
+    }
+
+    #[test]
+    fn cache_key_includes_secrets() {
+        let config = config("http://localhost/token".into());
+        let changed_password = OAuth2Auth {
+            password: "new".into(),
+            ..config.clone()
+        };
+        assert_ne!(cache_key(&config), cache_key(&changed_password));
+        assert!(!cache_key(&config).contains("s3cr+t"));
+        assert_eq!(cache_key(&config), cache_key(&config.clone()));
+    }
+
+    #[test]
+    fn form_urlencoded() {
+        assert_eq!(form_urlencode("a b+c:d%é~"), "a+b%2Bc%3Ad%25%C3%A9%7E");
+        assert_eq!(form_urlencode("Az09-_.*"), "Az09-_.*");
+    }
+}
@@ -0,0 +1,381 @@
+/// Sandboxed Rhai scripts: pre-request ones change request and set variables,
+/// post-response ones check response, set variables and add test results
+use std::sync::{Arc, Mutex};
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::Local;
+use hmac::{digest::KeyInit, Hmac, Mac};
+use rhai::{Dynamic, Engine, Map, Scope};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    executor::captures::Captured,
+    settings::main_settings::entity::request_settings::method_settigns::Method,
+    states::{
+        main_page::{
+            generics::Header,
+            request::request_data::{CaptureScope, RequestData},
+            response::{AssertionResult, Response},
+        },
+        Events,
+    },
+};
+
+/// Limit of script operations, so endless loop could not hang executor
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// Functions available for scripts, for UI hint
+pub const FUNCTIONS: &str =
+    "request: #{method, uri, headers, body, message} - pre-request, editable
+  method: GET, POST, PUT, PATCH or DELETE; repeated headers kept unless changed
+response: #{status, headers, body, json, duration_ms} - post-response
+vars: #{name: value} - variables of request
+set_var(name, value), set_collection_var(name, value)
+test(name, passed), test(name, passed, message) - post-response
+log(text), warn(text), print(text)
+sha256(text), hmac_sha256(key, text), base64_encode(text), base64_decode(text)
+uuid(), timestamp(), timestamp_ms()";
+
+/// Side effects of scripts run
+#[derive(Default)]
+struct Output {
+    captured: Vec<Captured>,
+    tests: Vec<AssertionResult>,
+}
+
+/// Run pre-request scripts in order, sharing scope.
+/// Returns variables set by scripts
+pub fn pre_request(
+    scripts: &[&str],
+    data: &mut RequestData,
+    variables: &[Header],
+    events: &Arc<Mutex<Events>>,
+) -> Result<Vec<Captured>, String> {
+    let output = Arc::new(Mutex::new(Output::default()));
+    let engine = engine(&output, events);
+
+    let headers = headers_map(&data.headers);
+    let mut request = Map::new();
+    request.insert("method".into(), data.method.to_string().into());
+    request.insert("uri".into(), data.uri.clone().into());
+    request.insert("headers".into(), headers.clone().into());
+    request.insert("body".into(), data.body.raw.message.clone().into());
+    request.insert("message".into(), data.message.message.clone().into());
+
+    let mut scope = Scope::new();
+    scope.push("request", request);
+    scope.push_constant("vars", headers_map(variables));
+
+    for script in scripts {
+        engine
+            .run_with_scope(&mut scope, script)
+            .map_err(|err| format!("Pre-request script error: {err}"))?;
+    }
+
+    let request = scope
+        .get_value::<Map>("request")
+        .ok_or("Pre-request script error: `request` must stay object".to_string())?;
+    let text = |name: &str| request.get(name).map(|value| value.to_string());
+
+    if let Some(method) = text("method") {
+        data.method = match method.trim().to_uppercase().as_str() {
+            "GET" => Method::GET,
+            "POST" => Method::POST,
+            "PUT" => Method::PUT,
+            "PATCH" => Method::PATCH,
+            "DELETE" => Method::DELETE,
+            _ => {
+                return Err(format!(
+                    "Pre-request script error: unsupported method {method}"
+                ))
+            }
+        };
+    }
+    if let Some(uri) = text("uri") {
+        data.uri = uri;
+    }
+    if let Some(body) = text("body") {
+        if body != data.body.raw.message {
+            data.body.raw.set(body);
+        }
+    }
+    if let Some(message) = text("message") {
+        if message != data.message.message {
+            data.message.set(message);
+        }
+    }
+    let headers_before = headers;
+    if let Some(headers) = request
+        .get("headers")
+        .and_then(|headers| headers.clone().try_cast::<Map>())
+    {
+        data.headers = apply_headers(&data.headers, &headers_before, headers);
+    }
+
+    let captured = std::mem::take(&mut output.lock().unwrap().captured);
+    Ok(captured)
+}
+
+/// Run post-response scripts in order, sharing scope.
+/// Tests added to response assertions, script error added as failed one
+pub fn post_response(
+    scripts: &[String],
+    response: &mut Response,
+    variables: &[Header],
+    captured: &Arc<Mutex<Vec<Captured>>>,
+    events: &Arc<Mutex<Events>>,
+) {
+    let output = Arc::new(Mutex::new(Output::default()));
+    let engine = engine(&output, events);
+
+    let json = serde_json::from_str::<Value>(&response.data.raw)
+        .ok()
+        .and_then(|json| rhai::serde::to_dynamic(json).ok())
+        .unwrap_or(Dynamic::UNIT);
+    let mut map = Map::new();
+    map.insert("status".into(), (response.code as i64).into());
+    map.insert("headers".into(), headers_map(&response.data.headers).into());
+    map.insert("body".into(), response.data.raw.clone().into());
+    map.insert("json".into(), json);
+    map.insert(
+        "duration_ms".into(),
+        match response.duration {
+            Some(duration) => (duration.as_millis() as i64).into(),
+            None => Dynamic::UNIT,
+        },
+    );
+
+    let mut scope = Scope::new();
+    scope.push_constant("response", map);
+    scope.push_constant("vars", headers_map(variables));
+
+    for script in scripts.iter().filter(|script| !script.trim().is_empty()) {
+        if let Err(err) = engine.run_with_scope(&mut scope, script) {
+            events
+                .lock()
+                .unwrap()
+                .event_error(&format!("Post-response script error: {err}"));
+            output.lock().unwrap().tests.push(AssertionResult {
+                name: "Post-response script".into(),
+                is_passed: false,
+                message: err.to_string(),
+            });
+            break;
+        }
+    }
+
+    let mut output = output.lock().unwrap();
+    response.assertions.append(&mut output.tests);
+    captured.lock().unwrap().append(&mut output.captured);
+}
+
+/// Sandboxed engine: no file or network access, limited operations and sizes
+fn engine(output: &Arc<Mutex<Output>>, events: &Arc<Mutex<Events>>) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(64)
+        .set_max_expr_depths(64, 64)
+        .set_max_string_size(10 * 1024 * 1024)
+        .set_max_array_size(100_000)
+        .set_max_map_size(100_000);
+    engine.disable_symbol("eval");
+
+    let log = Arc::clone(events);
+    engine.on_print(move |text| log.lock().unwrap().event_info(&format!("Script: {text}")));
+    let log = Arc::clone(events);
+    engine.on_debug(move |text, _, _| log.lock().unwrap().event_info(&format!("Script: {text}")));
+    let log = Arc::clone(events);
+    engine.register_fn("log", move |text: &str| {
+        log.lock().unwrap().event_info(&format!("Script: {text}"))
+    });
+    let log = Arc::clone(events);
+    engine.register_fn("warn", move |text: &str| {
+        log.lock()
+            .unwrap()
+            .event_warning(&format!("Script: {text}"))
+    });
+
+    for (name, scope) in [
+        ("set_var", CaptureScope::ENVIRONMENT),
+        ("set_collection_var", CaptureScope::COLLECTION),
+    ] {
+        let output = Arc::clone(output);
+        engine.register_fn(name, move |variable: &str, value: Dynamic| {
+            output.lock().unwrap().captured.push(Captured {
+                variable: variable.trim().to_string(),
+                value: value.to_string(),
+                scope: scope.clone(),
+                time: Local::now(),
+            });
+        });
+    }
+
+    let tests = Arc::clone(output);
+    engine.register_fn("test", move |name: &str, is_passed: bool| {
+        tests.lock().unwrap().tests.push(AssertionResult {
+            name: name.to_string(),
+            is_passed,
+            message: "".into(),
+        });
+    });
+    let tests = Arc::clone(output);
+    engine.register_fn("test", move |name: &str, is_passed: bool, message: &str| {
+        tests.lock().unwrap().tests.push(AssertionResult {
+            name: name.to_string(),
+            is_passed,
+            message: message.to_string(),
+        });
+    });
+
+    engine.register_fn("sha256", |text: &str| hex(&Sha256::digest(text.as_bytes())));
+    engine.register_fn("hmac_sha256", |key: &str, text: &str| {
+        let mut mac = <Hmac<Sha256> as KeyInit>::new_from_slice(key.as_bytes())
+            .expect("HMAC takes key of any size");
+        mac.update(text.as_bytes());
+        hex(&mac.finalize().into_bytes())
+    });
+    engine.register_fn("base64_encode", |text: &str| STANDARD.encode(text));
+    engine.register_fn("base64_decode", |text: &str| {
+        STANDARD
+            .decode(text)
+            .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+            .unwrap_or_default()
+    });
+    engine.register_fn("uuid", || uuid::Uuid::new_v4().to_string());
+    engine.register_fn("timestamp", || Local::now().timestamp());
+    engine.register_fn("timestamp_ms", || Local::now().timestamp_millis());
+
+    engine
+}
+
+/// Headers by name, later ones with same name win
+fn headers_map(headers: &[Header]) -> Map {
+    headers
+        .iter()
+        .filter(|header| !header.key.trim().is_empty())
+        .map(|header| (header.key.trim().into(), header.value.clone().into()))
+        .collect()
+}
+
+/// Headers after script: rows of untouched names kept as is, including repeated ones,
+/// changed name gets one row with new value, missing removed, new appended.
+/// before - map given to script
+fn apply_headers(headers: &[Header], before: &Map, mut map: Map) -> Vec<Header> {
+    let mut result = vec![];
+    for header in headers {
+        let key = header.key.trim();
+        if key.is_empty() {
+            result.push(header.clone());
+            continue;
+        }
+        let Some(value) = map.get(key) else {
+            continue;
+        };
+
+        let is_untouched = before
+            .get(key)
+            .is_some_and(|old| old.to_string() == value.to_string());
+        if is_untouched {
+            result.push(header.clone());
+        } else if let Some(value) = map.remove(key) {
+            result.push(Header {
+                key: header.key.clone(),
+                value: value.to_string(),
+            });
+        }
+    }
+    for (key, value) in before {
+        if map
+            .get(key.as_str())
+            .is_some_and(|new| new.to_string() == value.to_string())
+        {
+            map.remove(key.as_str());
+        }
+    }
+    for (key, value) in map {
+        result.push(Header {
+            key: key.to_string(),
+            value: value.to_string(),
+        });
+    }
+    result
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(key: &str, value: &str) -> Header {
+        Header {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+
+    fn pairs(headers: &[Header]) -> Vec<(String, String)> {
+        headers
+            .iter()
+            .map(|header| (header.key.clone(), header.value.clone()))
+            .collect()
+    }
+
+    fn run(script: &str, headers: Vec<Header>) -> Result<RequestData, String> {
+        let mut data = RequestData::default();
+        data.headers = headers;
+        let events = Arc::new(Mutex::new(Events::new()));
+        pre_request(&[script], &mut data, &[], &events)?;
+        Ok(data)
+    }
+
+    #[test]
+    fn untouched_headers_kept() {
+        let headers = vec![
+            header("Cookie", "a=1"),
+            header("Cookie", "b=2"),
+            header("", ""),
+            header("Accept", "text/plain"),
+        ];
+        let data = run("let x = 1;", headers.clone()).unwrap();
+        assert_eq!(pairs(&data.headers), pairs(&headers));
+
+        let data = run(r#"request.headers["X-Id"] = "7";"#, headers.clone()).unwrap();
+        let mut expected = headers.clone();
+        expected.push(header("X-Id", "7"));
+        assert_eq!(pairs(&data.headers), pairs(&expected));
+    }
+
+    #[test]
+    fn changed_headers_applied() {
+        let headers = vec![
+            header("Accept", "text/plain"),
+            header("Cookie", "a=1"),
+            header("Accept", "text/html"),
+            header("X-Old", "1"),
+        ];
+        let script = r#"
+            request.headers["Accept"] = "application/json";
+            request.headers.remove("X-Old");
+        "#;
+        let data = run(script, headers).unwrap();
+        assert_eq!(
+            pairs(&data.headers),
+            pairs(&[
+                header("Accept", "application/json"),
+                header("Cookie", "a=1")
+            ])
+        );
+    }
+
+    #[test]
+    fn method_applied() {
+        let data = run(r#"request.method = "post";"#, vec![]).unwrap();
+        assert_eq!(data.method, Method::POST);
+        assert!(run(r#"request.method = "FETCH";"#, vec![]).is_err());
+    }
+}
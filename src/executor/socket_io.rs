@@ -0,0 +1,348 @@
+/// Socket.IO (protocol v5) client helpers on top of Engine.IO (v4) websocket transport
+use serde_json::Value;
+
+/// Engine.IO: answear on server ping
+const ENGINE_PONG: &str = "3";
+
+/// Parsed incoming Engine.IO/Socket.IO packet
+#[derive(Debug, PartialEq)]
+pub enum SocketIoPacket {
+    /// Engine.IO handshake done, need to connect to namespace
+    Open,
+    /// Engine.IO close from server side
+    Close,
+    /// Engine.IO ping, need answear with pong
+    Ping,
+    /// Namespace connected
+    Connect { namespace: String },
+    /// Namespace disconnected by server
+    Disconnect { namespace: String },
+    /// Namespace connection refused
+    ConnectError { namespace: String, data: Value },
+    /// Event with name and arguments.
+    /// Ack id set when server waits for acknowledgement
+    Event {
+        namespace: String,
+        event: String,
+        args: Vec<Value>,
+        ack_id: Option<u64>,
+    },
+    /// Acknowledgement of sent event
+    Ack {
+        namespace: String,
+        ack_id: u64,
+        args: Vec<Value>,
+    },
+    /// Engine.IO noop and other packets we dont need to react on
+    Noop,
+    /// Packet could not be parsed
+    Unknown(String),
+}
+
+/// Construct Socket.IO websocket uri from user uri (without protocol).
+/// If user passed only host - default `/socket.io/` path added.
+pub fn socket_io_uri(uri: &str) -> String {
+    if uri.contains("EIO=") {
+        return uri.into();
+    }
+
+    let (base, query) = match uri.split_once("?") {
+        Some((base, query)) => (base.to_string(), Some(query.to_string())),
+        None => (uri.to_string(), None),
+    };
+
+    let base = match base.split_once("/") {
+        Some((host, path)) if !path.is_empty() => format!("{host}/{path}"),
+        Some((host, _)) => format!("{host}/socket.io/"),
+        None => format!("{base}/socket.io/"),
+    };
+
+    match query {
+        Some(query) if !query.is_empty() => format!("{base}?{query}&EIO=4&transport=websocket"),
+        _ => format!("{base}?EIO=4&transport=websocket"),
+    }
+}
+
+/// Pong packet for server ping
+pub fn pong_packet() -> String {
+    ENGINE_PONG.into()
+}
+
+/// Namespace connect packet. Auth - optional JSON payload
+pub fn connect_packet(namespace: &str, auth: &str) -> Result<String, String> {
+    let auth = if auth.trim().is_empty() {
+        "".to_string()
+    } else {
+        match serde_json::from_str::<Value>(auth) {
+            Ok(val) => val.to_string(),
+            Err(err) => return Err(format!("Socket.IO auth payload is not valid JSON: {err}")),
+        }
+    };
+
+    Ok(format!("40{}{}", namespace_prefix(namespace), auth))
+}
+
+/// Event packet.
+/// Data treated as JSON: array spreads into arguments list, other values passed as single argument.
+/// If data not JSON - passed as string argument.
+pub fn event_packet(namespace: &str, event: &str, data: &str) -> Result<String, String> {
+    if event.is_empty() {
+        return Err("Socket.IO event name is empty".into());
+    }
+
+    let mut payload = vec![Value::from(event)];
+    if !data.trim().is_empty() {
+        match serde_json::from_str::<Value>(data) {
+            Ok(Value::Array(args)) => payload.extend(args),
+            Ok(val) => payload.push(val),
+            Err(_) => payload.push(Value::from(data)),
+        }
+    }
+
+    Ok(format!(
+        "42{}{}",
+        namespace_prefix(namespace),
+        Value::Array(payload)
+    ))
+}
+
+/// Parse incoming text frame
+pub fn parse_packet(text: &str) -> SocketIoPacket {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some('0') => SocketIoPacket::Open,
+        Some('1') => SocketIoPacket::Close,
+        Some('2') => SocketIoPacket::Ping,
+        Some('4') => parse_socket_packet(chars.as_str())
+            .unwrap_or_else(|| SocketIoPacket::Unknown(text.into())),
+        Some('3') | Some('5') | Some('6') => SocketIoPacket::Noop,
+        _ => SocketIoPacket::Unknown(text.into()),
+    }
+}
+
+/// Parse Socket.IO packet, carried in Engine.IO message:
+/// `<type>[<attachments>-][<namespace>,][<ack id>][<json payload>]`.
+/// None if packet could not be parsed
+fn parse_socket_packet(text: &str) -> Option<SocketIoPacket> {
+    let mut chars = text.chars();
+    let packet_type = chars.next()?;
+    let mut rest = chars.as_str();
+
+    // Binary packets: attachments counter
+    if packet_type == '5' || packet_type == '6' {
+        if let Some((_, tail)) = rest.split_once("-") {
+            rest = tail;
+        }
+    }
+
+    let namespace = if rest.starts_with("/") {
+        match rest.split_once(",") {
+            Some((namespace, tail)) => {
+                rest = tail;
+                namespace.to_string()
+            }
+            None => {
+                let namespace = rest.to_string();
+                rest = "";
+                namespace
+            }
+        }
+    } else {
+        "/".to_string()
+    };
+
+    let ack_digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    rest = &rest[ack_digits.len()..];
+    let ack_id = ack_digits.parse::<u64>().ok();
+
+    let payload = if !rest.is_empty() {
+        match serde_json::from_str::<Value>(rest) {
+            Ok(val) => val,
+            Err(_) => return None,
+        }
+    } else {
+        Value::Null
+    };
+
+    let packet = match packet_type {
+        '0' => SocketIoPacket::Connect { namespace },
+        '1' => SocketIoPacket::Disconnect { namespace },
+        '4' => SocketIoPacket::ConnectError {
+            namespace,
+            data: payload,
+        },
+        '2' | '5' => match payload {
+            Value::Array(mut items) if !items.is_empty() => {
+                let event = match items.remove(0) {
+                    Value::String(val) => val,
+                    other => other.to_string(),
+                };
+                SocketIoPacket::Event {
+                    namespace,
+                    event,
+                    args: items,
+                    ack_id,
+                }
+            }
+            _ => return None,
+        },
+        '3' | '6' => match (ack_id, payload) {
+            (Some(ack_id), Value::Array(args)) => SocketIoPacket::Ack {
+                namespace,
+                ack_id,
+                args,
+            },
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(packet)
+}
+
+/// Acknowledgement of server event, without arguments
+pub fn ack_packet(namespace: &str, ack_id: u64) -> String {
+    format!("43{}{ack_id}[]", namespace_prefix(namespace))
+}
+
+/// Render event arguments for response view.
+/// Single argument shown as is, multiple - as JSON array
+pub fn args_to_string(args: &[Value]) -> String {
+    let value = if args.len() == 1 {
+        args[0].clone()
+    } else {
+        Value::Array(args.to_vec())
+    };
+
+    match value {
+        Value::String(val) => val,
+        other => other.to_string(),
+    }
+}
+
+/// Default namespace omitted in packets
+fn namespace_prefix(namespace: &str) -> String {
+    let namespace = namespace.trim();
+    if namespace.is_empty() || namespace == "/" {
+        "".into()
+    } else if namespace.starts_with("/") {
+        format!("{namespace},")
+    } else {
+        format!("/{namespace},")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn engine_packets() {
+        assert_eq!(parse_packet("0{\"sid\":\"a\"}"), SocketIoPacket::Open);
+        assert_eq!(parse_packet("2"), SocketIoPacket::Ping);
+        assert_eq!(parse_packet("6"), SocketIoPacket::Noop);
+        assert_eq!(parse_packet(""), SocketIoPacket::Unknown("".into()));
+    }
+
+    #[test]
+    fn socket_packets() {
+        assert_eq!(
+            parse_packet("40/chat,{\"sid\":\"a\"}"),
+            SocketIoPacket::Connect {
+                namespace: "/chat".into()
+            }
+        );
+        assert_eq!(
+            parse_packet("42[\"message\",\"hi\",1]"),
+            SocketIoPacket::Event {
+                namespace: "/".into(),
+                event: "message".into(),
+                args: vec![json!("hi"), json!(1)],
+                ack_id: None,
+            }
+        );
+        assert_eq!(
+            parse_packet("43/chat,12[\"ok\"]"),
+            SocketIoPacket::Ack {
+                namespace: "/chat".into(),
+                ack_id: 12,
+                args: vec![json!("ok")],
+            }
+        );
+        assert_eq!(
+            parse_packet("451-[\"file\",{\"_placeholder\":true,\"num\":0}]"),
+            SocketIoPacket::Event {
+                namespace: "/".into(),
+                event: "file".into(),
+                args: vec![json!({"_placeholder": true, "num": 0})],
+                ack_id: None,
+            }
+        );
+    }
+
+    #[test]
+    fn multibyte_packet_type() {
+        // Socket.IO packet type is not ASCII, no panic on slicing
+        assert_eq!(parse_packet("4ы"), SocketIoPacket::Unknown("4ы".into()));
+        assert_eq!(parse_packet("ы"), SocketIoPacket::Unknown("ы".into()));
+    }
+
+    #[test]
+    fn outgoing_packets() {
+        assert_eq!(connect_packet("/", "").unwrap(), "40");
+        assert_eq!(
+            connect_packet("chat", "{\"token\": \"a\"}").unwrap(),
+            "40/chat,{\"token\":\"a\"}"
+        );
+        assert_eq!(
+            event_packet("/", "sum", "[1, 2]").unwrap(),
+            "42[\"sum\",1,2]"
+        );
+        assert_eq!(
+            event_packet("/chat", "say", "hello").unwrap(),
+            "42/chat,[\"say\",\"hello\"]"
+        );
+        assert!(event_packet("/", "", "").is_err());
+    }
+
+    #[test]
+    fn uri() {
+        assert_eq!(
+            socket_io_uri("localhost:3000"),
+            "localhost:3000/socket.io/?EIO=4&transport=websocket"
+        );
+        assert_eq!(
+            socket_io_uri("localhost:3000/custom?token=a"),
+            "localhost:3000/custom?token=a&EIO=4&transport=websocket"
+        );
+    }
+
+    #[test]
+    fn unknown_keeps_whole_packet() {
+        assert_eq!(
+            parse_packet("42[\"broken\""),
+            SocketIoPacket::Unknown("42[\"broken\"".into())
+        );
+        assert_eq!(parse_packet("42{}"), SocketIoPacket::Unknown("42{}".into()));
+        assert_eq!(
+            parse_packet("43[1]"),
+            SocketIoPacket::Unknown("43[1]".into())
+        );
+    }
+
+    #[test]
+    fn event_waiting_ack() {
+        assert_eq!(
+            parse_packet("42/chat,17[\"question\",\"why\"]"),
+            SocketIoPacket::Event {
+                namespace: "/chat".into(),
+                event: "question".into(),
+                args: vec![json!("why")],
+                ack_id: Some(17),
+            }
+        );
+        assert_eq!(ack_packet("/chat", 17), "43/chat,17[]");
+        assert_eq!(ack_packet("/", 3), "433[]");
+    }
+}
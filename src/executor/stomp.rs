@@ -0,0 +1,381 @@
+/// STOMP (1.0-1.2) client helpers for WS sessions
+use crate::states::main_page::{generics::Header, request::StompCommand};
+
+/// Value of Sec-WebSocket-Protocol header for STOMP sessions
+pub const STOMP_SUBPROTOCOLS: &str = "v12.stomp, v11.stomp, v10.stomp";
+
+/// Frame body terminator
+const FRAME_END: char = '\0';
+
+/// Parsed STOMP frame
+#[derive(Debug)]
+pub struct StompFrame {
+    pub command: String,
+    pub headers: Vec<Header>,
+    pub body: String,
+}
+
+impl StompFrame {
+    /// Get first header value with key
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|header| header.key == key)
+            .map(|header| header.value.as_str())
+    }
+
+    /// Serialize frame to text, ready to send in WS session
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{}\n", self.command);
+        // CONNECT frame headers are not escaped by spec
+        let need_escape = self.command != "CONNECT";
+        for header in &self.headers {
+            if need_escape {
+                text.push_str(&format!(
+                    "{}:{}\n",
+                    escape(&header.key),
+                    escape(&header.value)
+                ));
+            } else {
+                text.push_str(&format!("{}:{}\n", header.key, header.value));
+            }
+        }
+        text.push('\n');
+        text.push_str(&self.body);
+        text.push(FRAME_END);
+        text
+    }
+}
+
+/// CONNECT frame. Empty values are not sent
+pub fn connect_frame(host: &str, login: &str, passcode: &str) -> String {
+    let mut headers = vec![
+        header("accept-version", "1.2,1.1,1.0"),
+        header("heart-beat", "0,0"),
+    ];
+    for (key, value) in [("host", host), ("login", login), ("passcode", passcode)] {
+        if !value.is_empty() {
+            headers.push(header(key, value));
+        }
+    }
+
+    StompFrame {
+        command: "CONNECT".into(),
+        headers,
+        body: "".into(),
+    }
+    .to_text()
+}
+
+/// DISCONNECT frame, sent before WS Close
+pub fn disconnect_frame() -> String {
+    StompFrame {
+        command: "DISCONNECT".into(),
+        headers: vec![],
+        body: "".into(),
+    }
+    .to_text()
+}
+
+/// Client frame from user input.
+/// Extra headers - `key:value` per line, they override generated ones.
+/// Subscription id defaults to destination, so UNSUBSCRIBE needs only destination too.
+pub fn client_frame(
+    command: &StompCommand,
+    destination: &str,
+    extra_headers: &str,
+    body: &str,
+) -> Result<String, String> {
+    let extra_headers = parse_headers_text(extra_headers)?;
+    let has_header = |key: &str| extra_headers.iter().any(|header| header.key == key);
+
+    if destination.trim().is_empty() && !has_header("destination") && !has_header("id") {
+        return Err(format!("STOMP {command} frame requires destination"));
+    }
+
+    let mut headers = vec![];
+    match command {
+        StompCommand::SEND => {
+            headers.push(header("destination", destination));
+            if !has_header("content-length") {
+                headers.push(header("content-length", &body.len().to_string()));
+            }
+        }
+        StompCommand::SUBSCRIBE => {
+            headers.push(header("destination", destination));
+            headers.push(header("id", destination));
+            headers.push(header("ack", "auto"));
+        }
+        StompCommand::UNSUBSCRIBE => {
+            headers.push(header("id", destination));
+        }
+    }
+    headers.retain(|header| !has_header(&header.key));
+    headers.extend(extra_headers);
+
+    Ok(StompFrame {
+        command: command.to_string(),
+        headers,
+        // Only SEND frame may have body
+        body: match command {
+            StompCommand::SEND => body.into(),
+            _ => "".into(),
+        },
+    }
+    .to_text())
+}
+
+/// Parse incoming WS text into frames. Heart-beats (EOLs between frames) skipped
+pub fn parse_frames(text: &str) -> Result<Vec<StompFrame>, String> {
+    let mut frames = vec![];
+    let mut rest = text;
+
+    loop {
+        rest = rest.trim_start_matches(['\r', '\n']);
+        if rest.is_empty() {
+            return Ok(frames);
+        }
+
+        let (head, tail) = match split_headers(rest) {
+            Some(val) => val,
+            None => return Err(format!("STOMP frame without headers end: {rest}")),
+        };
+
+        let mut lines = head.lines();
+        let command = match lines.next() {
+            Some(val) => val.trim_end_matches('\r').to_string(),
+            None => return Err(format!("STOMP frame without command: {rest}")),
+        };
+
+        let mut headers = vec![];
+        for line in lines {
+            let line = line.trim_end_matches('\r');
+            match line.split_once(":") {
+                Some((key, value)) => headers.push(Header {
+                    key: unescape(key),
+                    value: unescape(value),
+                }),
+                None => return Err(format!("STOMP frame header is malformed: {line}")),
+            }
+        }
+
+        // Body with content-length may contain NUL, so read exactly that many bytes
+        let content_length = headers
+            .iter()
+            .find(|header| header.key == "content-length")
+            .and_then(|header| header.value.trim().parse::<usize>().ok());
+        let (body, tail) = match content_length {
+            Some(length) => {
+                if length > tail.len() || !tail.is_char_boundary(length) {
+                    return Err(format!(
+                        "STOMP frame body does not match content-length {length}"
+                    ));
+                }
+                let (body, tail) = tail.split_at(length);
+                (body, tail.strip_prefix(FRAME_END).unwrap_or(tail))
+            }
+            None => match tail.split_once(FRAME_END) {
+                Some(val) => val,
+                // Some brokers omit terminator for last frame
+                None => (tail, ""),
+            },
+        };
+
+        frames.push(StompFrame {
+            command,
+            headers,
+            body: body.into(),
+        });
+        rest = tail;
+    }
+}
+
+/// Split frame at first empty line, ending headers. Lines end with LF or CRLF
+fn split_headers(text: &str) -> Option<(&str, &str)> {
+    text.match_indices('\n').find_map(|(i, _)| {
+        let after = &text[i + 1..];
+        let body = after
+            .strip_prefix('\n')
+            .or_else(|| after.strip_prefix("\r\n"))?;
+        Some((&text[..i], body))
+    })
+}
+
+/// Headers from `key:value` lines. Empty lines skipped
+fn parse_headers_text(text: &str) -> Result<Vec<Header>, String> {
+    let mut headers = vec![];
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match line.split_once(":") {
+            Some((key, value)) => headers.push(header(key.trim(), value.trim())),
+            None => return Err(format!("STOMP header must be `key:value`, got: {line}")),
+        }
+    }
+    Ok(headers)
+}
+
+fn header(key: &str, value: &str) -> Header {
+    Header {
+        key: key.into(),
+        value: value.into(),
+    }
+}
+
+/// Header escaping from STOMP 1.2 spec
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\r', "\\r")
+        .replace('\n', "\\n")
+        .replace(':', "\\c")
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            result.push(char);
+            continue;
+        }
+        match chars.next() {
+            Some('r') => result.push('\r'),
+            Some('n') => result.push('\n'),
+            Some('c') => result.push(':'),
+            Some('\\') => result.push('\\'),
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(headers: &[Header]) -> Vec<(&str, &str)> {
+        headers
+            .iter()
+            .map(|header| (header.key.as_str(), header.value.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_several_frames() {
+        let text = "\nCONNECTED\nversion:1.2\nheart-beat:0,0\n\n\0\n\
+                    MESSAGE\r\ndestination:/queue/a\r\nmessage-id:1\r\n\r\nhello\nworld\0";
+        let frames = parse_frames(text).unwrap();
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].command, "CONNECTED");
+        assert_eq!(frames[0].header("version"), Some("1.2"));
+        assert_eq!(frames[0].body, "");
+        assert_eq!(frames[1].command, "MESSAGE");
+        assert_eq!(
+            pairs(&frames[1].headers),
+            vec![("destination", "/queue/a"), ("message-id", "1")]
+        );
+        assert_eq!(frames[1].body, "hello\nworld");
+    }
+
+    #[test]
+    fn parse_heart_beats_and_missing_terminator() {
+        assert!(parse_frames("\n\r\n\n").unwrap().is_empty());
+
+        let frames = parse_frames("RECEIPT\nreceipt-id:7\n\n").unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].header("receipt-id"), Some("7"));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            parse_frames("MESSAGE\ndestination:/a").unwrap_err(),
+            "STOMP frame without headers end: MESSAGE\ndestination:/a"
+        );
+        assert_eq!(
+            parse_frames("MESSAGE\nbroken\n\n\0").unwrap_err(),
+            "STOMP frame header is malformed: broken"
+        );
+    }
+
+    #[test]
+    fn header_escaping() {
+        assert_eq!(unescape(r"a\cb\nc\rd\\e"), "a:b\nc\rd\\e");
+        // Undefined escape kept as is
+        assert_eq!(unescape(r"a\tb\"), r"a\tb\");
+        assert_eq!(escape("a:b\nc\rd\\e"), r"a\cb\nc\rd\\e");
+
+        let value = "x:y\\z\n";
+        assert_eq!(unescape(&escape(value)), value);
+
+        let frames = parse_frames("MESSAGE\nkey\\cname:a\\cb\n\n\0").unwrap();
+        assert_eq!(pairs(&frames[0].headers), vec![("key:name", "a:b")]);
+    }
+
+    #[test]
+    fn client_frames() {
+        assert_eq!(
+            client_frame(&StompCommand::SEND, "/queue/a", "", "hi").unwrap(),
+            "SEND\ndestination:/queue/a\ncontent-length:2\n\nhi\0"
+        );
+        assert_eq!(
+            client_frame(
+                &StompCommand::SUBSCRIBE,
+                "/topic/a",
+                "id: sub-1\nack:client",
+                "body"
+            )
+            .unwrap(),
+            "SUBSCRIBE\ndestination:/topic/a\nid:sub-1\nack:client\n\n\0"
+        );
+        assert_eq!(
+            client_frame(&StompCommand::UNSUBSCRIBE, "/topic/a", "", "").unwrap(),
+            "UNSUBSCRIBE\nid:/topic/a\n\n\0"
+        );
+        assert_eq!(
+            client_frame(&StompCommand::SEND, " ", "", "").unwrap_err(),
+            "STOMP SEND frame requires destination"
+        );
+        assert!(client_frame(&StompCommand::SEND, "/a", "no colon", "").is_err());
+    }
+
+    #[test]
+    fn connect_not_escaped() {
+        assert_eq!(
+            connect_frame("host:1", "user", ""),
+            "CONNECT\naccept-version:1.2,1.1,1.0\nheart-beat:0,0\nhost:host:1\nlogin:user\n\n\0"
+        );
+    }
+
+    #[test]
+    fn parse_crlf_frame_with_blank_lines_in_body() {
+        let frames = parse_frames("MESSAGE\r\ndestination:/a\r\n\r\nfirst\n\nsecond\0").unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].header("destination"), Some("/a"));
+        assert_eq!(frames[0].body, "first\n\nsecond");
+    }
+
+    #[test]
+    fn parse_body_by_content_length() {
+        let text = "MESSAGE\ncontent-length:7\n\na\0b\0cé\0\nRECEIPT\nreceipt-id:1\n\n\0";
+        let frames = parse_frames(text).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].body, "a\0b\0cé");
+        assert_eq!(frames[1].command, "RECEIPT");
+
+        assert_eq!(
+            parse_frames("MESSAGE\ncontent-length:10\n\nshort\0").unwrap_err(),
+            "STOMP frame body does not match content-length 10"
+        );
+        // Length in the middle of multibyte character
+        assert!(parse_frames("MESSAGE\ncontent-length:1\n\né\0").is_err());
+    }
+}
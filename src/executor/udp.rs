@@ -0,0 +1,158 @@
+/// Raw UDP datagram helpers
+use std::net::SocketAddr;
+
+use tokio::net::{lookup_host, UdpSocket};
+
+use crate::states::main_page::{generics::Header, request::UdpPayloadFormat};
+
+/// Max size of single datagram
+pub const MAX_DATAGRAM_SIZE: usize = 65535;
+
+/// Resolve target and bind local socket of same address family on random port
+pub async fn bind(uri: &str) -> Result<(UdpSocket, SocketAddr), String> {
+    let authority = uri.split(['/', '?']).next().unwrap_or_default();
+    if authority.is_empty() {
+        return Err("UDP target host is empty".into());
+    }
+    if authority.rsplit_once(':').is_none() {
+        return Err(format!("UDP target must be host:port, got: {authority}"));
+    }
+
+    let target = match lookup_host(authority).await {
+        Ok(mut addrs) => match addrs.next() {
+            Some(addr) => addr,
+            None => return Err(format!("Could not resolve UDP target: {authority}")),
+        },
+        Err(err) => return Err(format!("Could not resolve UDP target {authority}: {err}")),
+    };
+
+    let local = if target.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    };
+
+    match UdpSocket::bind(local).await {
+        Ok(socket) => Ok((socket, target)),
+        Err(err) => Err(format!("Could not bind UDP socket: {err}")),
+    }
+}
+
+/// Datagram bytes from message editor content
+pub fn encode_payload(message: &str, format: &UdpPayloadFormat) -> Result<Vec<u8>, String> {
+    match format {
+        UdpPayloadFormat::TEXT => Ok(message.as_bytes().to_vec()),
+        UdpPayloadFormat::HEX => {
+            // Whitespace and 0x prefixes allowed, so dumps could be pasted as is
+            let digits: String = message
+                .split_whitespace()
+                .map(|chunk| chunk.trim_start_matches("0x"))
+                .collect();
+            if !digits.is_ascii() {
+                return Err("Hex payload must contain only hex digits".into());
+            }
+            if !digits.len().is_multiple_of(2) {
+                return Err("Hex payload has odd number of digits".into());
+            }
+
+            (0..digits.len())
+                .step_by(2)
+                .map(|i| {
+                    u8::from_str_radix(&digits[i..i + 2], 16)
+                        .map_err(|_| format!("Invalid hex byte: {}", &digits[i..i + 2]))
+                })
+                .collect()
+        }
+    }
+}
+
+/// Received datagram as text or as space separated hex dump
+pub fn decode_payload(data: &[u8], format: &UdpPayloadFormat) -> String {
+    match format {
+        UdpPayloadFormat::TEXT => String::from_utf8_lossy(data).to_string(),
+        UdpPayloadFormat::HEX => data
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<Vec<String>>()
+            .join(" "),
+    }
+}
+
+/// Reply details shown in response headers
+pub fn reply_headers(source: &SocketAddr, size: usize) -> Vec<Header> {
+    vec![
+        Header {
+            key: "source".into(),
+            value: source.to_string(),
+        },
+        Header {
+            key: "bytes".into(),
+            value: size.to_string(),
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_round_trip() {
+        let message = "ping é\n";
+        let data = encode_payload(message, &UdpPayloadFormat::TEXT).unwrap();
+        assert_eq!(data, message.as_bytes());
+        assert_eq!(decode_payload(&data, &UdpPayloadFormat::TEXT), message);
+        // Invalid UTF-8 shown lossy, not dropped
+        assert_eq!(
+            decode_payload(&[b'a', 0xff], &UdpPayloadFormat::TEXT),
+            "a\u{fffd}"
+        );
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        let data: Vec<u8> = (0..=255).collect();
+        let dump = decode_payload(&data, &UdpPayloadFormat::HEX);
+        assert!(dump.starts_with("00 01 02"));
+        assert!(dump.ends_with("fe ff"));
+        assert_eq!(encode_payload(&dump, &UdpPayloadFormat::HEX).unwrap(), data);
+
+        // Pasted dumps: prefixes, case, line breaks
+        assert_eq!(
+            encode_payload("0xDE 0xad\nBEEF", &UdpPayloadFormat::HEX).unwrap(),
+            vec![0xde, 0xad, 0xbe, 0xef]
+        );
+        assert!(encode_payload("", &UdpPayloadFormat::HEX)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn hex_errors() {
+        assert_eq!(
+            encode_payload("abc", &UdpPayloadFormat::HEX).unwrap_err(),
+            "Hex payload has odd number of digits"
+        );
+        assert_eq!(
+            encode_payload("zz", &UdpPayloadFormat::HEX).unwrap_err(),
+            "Invalid hex byte: zz"
+        );
+        assert_eq!(
+            encode_payload("éé", &UdpPayloadFormat::HEX).unwrap_err(),
+            "Hex payload must contain only hex digits"
+        );
+    }
+
+    #[tokio::test]
+    async fn bind_target() {
+        let (socket, target) = bind("127.0.0.1:9999/ignored").await.unwrap();
+        assert_eq!(target.to_string(), "127.0.0.1:9999");
+        assert!(socket.local_addr().unwrap().is_ipv4());
+
+        assert_eq!(bind("").await.unwrap_err(), "UDP target host is empty");
+        assert_eq!(
+            bind("localhost").await.unwrap_err(),
+            "UDP target must be host:port, got: localhost"
+        );
+    }
+}
@@ -0,0 +1,520 @@
+/// Environment variables substitution of {{name}} placeholders
+use std::ops::Range;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{SecondsFormat, Utc};
+use rand::Rng;
+
+use crate::states::main_page::{
+    generics::Header,
+    request::{
+        request_data::{OAuth2Auth, RequestAuth, RequestData},
+        RequestSetup,
+    },
+};
+
+/// Built-in variables, generated on every send. Shown in UI
+pub const BUILT_IN: &str = "{{$uuid}} {{$timestamp}} {{$timestampMs}} {{$isoTimestamp}}
+{{$randomInt}} {{$randomInt min max}} {{$randomString}} {{$randomString length}} {{$randomEmail}}
+{{$base64 text}} {{$urlEncode text}}";
+
+/// Characters of random strings
+const ALPHANUMERIC: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+/// Longest `$randomString`, to not allocate unbounded memory on typo
+pub const MAX_RANDOM_STRING: usize = 100_000;
+
+/// Placeholders in text: range of `{{ name }}` with braces and trimmed name
+pub fn placeholders(text: &str) -> Vec<(Range<usize>, String)> {
+    let mut result = vec![];
+    let mut offset = 0;
+
+    while let Some(start) = text[offset..].find("{{") {
+        let start = offset + start;
+        let Some(end) = text[start + 2..].find("}}") else {
+            break;
+        };
+        let end = start + 2 + end;
+
+        let name = text[start + 2..end].trim();
+        // Not a placeholder, like `{{{name}}}` or JSON - retry from next brace
+        if name.is_empty() || name.contains(['{', '}', '\n']) {
+            offset = start + 1;
+            continue;
+        }
+
+        result.push((start..end + 2, name.to_string()));
+        offset = end + 2;
+    }
+
+    result
+}
+
+/// Substitution with variables, collecting names without value
+pub struct Variables<'a> {
+    values: &'a [Header],
+    unresolved: Vec<String>,
+    /// First built-in called with wrong arguments
+    error: Option<String>,
+}
+
+impl<'a> Variables<'a> {
+    pub fn new(values: &'a [Header]) -> Self {
+        Self {
+            values,
+            unresolved: vec![],
+            error: None,
+        }
+    }
+
+    /// Value of variable, first one wins on duplicated names
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|variable| variable.key.trim() == name)
+            .map(|variable| variable.value.as_str())
+    }
+
+    /// Variable has value or it is valid built-in one
+    pub fn is_known(&self, name: &str) -> bool {
+        self.get(name).is_some() || matches!(built_in(name), Some(Ok(_)))
+    }
+
+    /// Text with known placeholders replaced. Unknown ones kept as is.
+    /// Built-in ones substituted last, so they could take variables as arguments:
+    /// `{{$base64 {{user}}:{{password}}}}`. Nested built-ins resolved from
+    /// innermost one: `{{$base64 {{$uuid}}}}`
+    pub fn substitute(&mut self, text: &str) -> String {
+        let mut result = self.substitute_pass(text, false);
+        while result.contains("{{$") {
+            let next = self.substitute_pass(&result, true);
+            if next == result {
+                break;
+            }
+            result = next;
+        }
+        result
+    }
+
+    /// built_in - substitute only $names, otherwise only user variables
+    fn substitute_pass(&mut self, text: &str, built_in_pass: bool) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut last = 0;
+
+        for (range, name) in placeholders(text) {
+            result.push_str(&text[last..range.start]);
+            last = range.end;
+
+            if name.starts_with('$') != built_in_pass {
+                result.push_str(&text[range]);
+                continue;
+            }
+
+            let value = match self.get(&name) {
+                Some(value) => Some(Ok(value.to_string())),
+                None if built_in_pass => built_in(&name),
+                None => None,
+            };
+            match value {
+                Some(Ok(value)) => result.push_str(&value),
+                Some(Err(err)) => {
+                    result.push_str(&text[range.clone()]);
+                    self.error.get_or_insert(format!("{{{{{name}}}}}: {err}"));
+                }
+                None => {
+                    result.push_str(&text[range.clone()]);
+                    if !self.unresolved.contains(&name) {
+                        self.unresolved.push(name);
+                    }
+                }
+            }
+        }
+        result.push_str(&text[last..]);
+
+        result
+    }
+
+    fn substitute_in_place(&mut self, text: &mut String) {
+        if text.contains("{{") {
+            *text = self.substitute(text);
+        }
+    }
+
+    /// Names of variables without value, met during substitution
+    pub fn unresolved(self) -> Vec<String> {
+        self.unresolved
+    }
+}
+
+/// Substitute placeholders in every sent part of request.
+/// Returns names of unresolved variables, or error of built-in with wrong arguments
+pub fn resolve(data: &mut RequestData, values: &[Header]) -> Result<Vec<String>, String> {
+    let mut variables = Variables::new(values);
+
+    variables.substitute_in_place(&mut data.uri);
+    for param in data.query_params.iter_mut() {
+        variables.substitute_in_place(&mut param.key);
+        variables.substitute_in_place(&mut param.value);
+    }
+    for header in data.headers.iter_mut() {
+        variables.substitute_in_place(&mut header.key);
+        variables.substitute_in_place(&mut header.value);
+    }
+
+    let body = &mut data.body;
+    for text in [
+        &mut body.raw.message,
+        &mut body.binary_path,
+        &mut body.graphql.query.message,
+        &mut body.graphql.variables.message,
+        &mut body.graphql.operation_name,
+        &mut body.json_rpc.method,
+        &mut body.json_rpc.params.message,
+    ] {
+        variables.substitute_in_place(text);
+    }
+    for field in body.form_data.iter_mut() {
+        variables.substitute_in_place(&mut field.key);
+        variables.substitute_in_place(&mut field.value);
+    }
+
+    variables.substitute_in_place(&mut data.message.message);
+    for assertion in data.assertions.iter_mut() {
+        variables.substitute_in_place(&mut assertion.target);
+        variables.substitute_in_place(&mut assertion.expected);
+    }
+    resolve_setup(&mut data.setup, &mut variables);
+    resolve_auth(&mut data.auth, &mut variables);
+
+    match variables.error.take() {
+        Some(err) => Err(err),
+        None => Ok(variables.unresolved()),
+    }
+}
+
+/// OAuth2 configuration as used on send, for token cache lookups from UI
+pub fn resolve_oauth2(config: &OAuth2Auth, values: &[Header]) -> OAuth2Auth {
+    let mut config = config.clone();
+    resolve_oauth2_fields(&mut config, &mut Variables::new(values));
+    config
+}
+
+/// Protocol fields sent with message, like MQTT topic or STOMP destination
+fn resolve_setup(setup: &mut RequestSetup, variables: &mut Variables) {
+    if let Some(ws) = setup.ws_mut() {
+        let socket_io = &mut ws.socket_io;
+        let stomp = &mut ws.stomp;
+        for text in [
+            &mut socket_io.namespace,
+            &mut socket_io.auth,
+            &mut socket_io.event,
+            &mut stomp.host,
+            &mut stomp.login,
+            &mut stomp.passcode,
+            &mut stomp.destination,
+            &mut stomp.headers,
+        ] {
+            variables.substitute_in_place(text);
+        }
+    }
+
+    if let Some(mqtt) = setup.mqtt_mut() {
+        for text in [
+            &mut mqtt.client_id,
+            &mut mqtt.username,
+            &mut mqtt.password,
+            &mut mqtt.topic,
+        ] {
+            variables.substitute_in_place(text);
+        }
+    }
+
+    if let Some(grpc) = setup.grpc_mut() {
+        for path in grpc.proto_files.iter_mut() {
+            variables.substitute_in_place(path);
+        }
+        for text in [&mut grpc.import_paths, &mut grpc.service, &mut grpc.method] {
+            variables.substitute_in_place(text);
+        }
+    }
+
+    // UDP host and port are part of uri
+    if let Some(udp) = setup.udp_mut() {
+        variables.substitute_in_place(&mut udp.reply_window);
+    }
+}
+
+fn resolve_auth(auth: &mut RequestAuth, variables: &mut Variables) {
+    resolve_oauth2_fields(&mut auth.oauth2, variables);
+
+    let aws = &mut auth.aws;
+    for text in [
+        &mut auth.username,
+        &mut auth.password,
+        &mut auth.token,
+        &mut auth.api_key_name,
+        &mut auth.api_key_value,
+        &mut aws.access_key,
+        &mut aws.secret_key,
+        &mut aws.session_token,
+        &mut aws.region,
+        &mut aws.service,
+        &mut auth.hmac.secret,
+    ] {
+        variables.substitute_in_place(text);
+    }
+}
+
+fn resolve_oauth2_fields(config: &mut OAuth2Auth, variables: &mut Variables) {
+    for text in [
+        &mut config.token_url,
+        &mut config.auth_url,
+        &mut config.client_id,
+        &mut config.client_secret,
+        &mut config.scope,
+        &mut config.username,
+        &mut config.password,
+    ] {
+        variables.substitute_in_place(text);
+    }
+}
+
+/// Value of built-in variable, like `$uuid` or `$randomInt 1 6`.
+/// None for unknown name, error for wrong arguments
+pub fn built_in(name: &str) -> Option<Result<String, String>> {
+    let (function, args) = match name.split_once(char::is_whitespace) {
+        Some((function, args)) => (function, args.trim()),
+        None => (name, ""),
+    };
+
+    let value = match function {
+        "$uuid" | "$guid" => Ok(uuid::Uuid::new_v4().to_string()),
+        "$timestamp" => Ok(Utc::now().timestamp().to_string()),
+        "$timestampMs" => Ok(Utc::now().timestamp_millis().to_string()),
+        "$isoTimestamp" => Ok(Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)),
+        "$randomInt" => random_int(args),
+        "$randomString" => match args {
+            "" => Ok(random_string(16)),
+            args => match args.parse::<usize>() {
+                Ok(length) if length <= MAX_RANDOM_STRING => Ok(random_string(length)),
+                Ok(_) => Err(format!("length is above {MAX_RANDOM_STRING}")),
+                Err(_) => Err(format!("length must be number, got `{args}`")),
+            },
+        },
+        "$randomEmail" => Ok(format!("{}@example.com", random_string(10).to_lowercase())),
+        "$base64" => Ok(STANDARD.encode(args)),
+        "$urlEncode" => Ok(url_encode(args)),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Uniform number in `min max` range, both included. 0-1000 without arguments
+fn random_int(args: &str) -> Result<String, String> {
+    let (min, max) = if args.is_empty() {
+        (0, 1000)
+    } else {
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        let [min, max] = parts[..] else {
+            return Err(format!("expected `min max`, got `{args}`"));
+        };
+        match (min.parse::<i64>(), max.parse::<i64>()) {
+            (Ok(min), Ok(max)) => (min, max),
+            _ => return Err(format!("expected `min max` numbers, got `{args}`")),
+        }
+    };
+    if min > max {
+        return Err(format!("min {min} is above max {max}"));
+    }
+    Ok(rand::thread_rng().gen_range(min..=max).to_string())
+}
+
+fn random_string(length: usize) -> String {
+    let mut rng = rand::thread_rng();
+    (0..length)
+        .map(|_| ALPHANUMERIC[rng.gen_range(0..ALPHANUMERIC.len())] as char)
+        .collect()
+}
+
+/// Percent encoding of everything except unreserved characters
+fn url_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::states::main_page::collection::CollectionData;
+
+    fn header(key: &str, value: &str) -> Header {
+        Header {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+
+    #[test]
+    fn placeholder_names_trimmed() {
+        assert_eq!(
+            placeholders("a {{ host }}/{{port}} b"),
+            vec![(2..12, "host".to_string()), (13..21, "port".to_string())]
+        );
+        assert!(placeholders("{{ }} {{ open").is_empty());
+    }
+
+    #[test]
+    fn not_placeholders() {
+        assert_eq!(placeholders("{{{x}}}"), vec![(1..6, "x".to_string())]);
+        assert!(placeholders(r#"{"a": {"b": 1}}"#).is_empty());
+        assert!(placeholders("{{a\nb}}").is_empty());
+
+        let values = [header("x", "1")];
+        let mut variables = Variables::new(&values);
+        assert_eq!(variables.substitute("{{{x}}}"), "{1}");
+        assert_eq!(
+            variables.substitute(r#"{"a":{"b":{{x}}}}"#),
+            r#"{"a":{"b":1}}"#
+        );
+        assert!(variables.unresolved().is_empty());
+    }
+
+    #[test]
+    fn substitution() {
+        let values = [
+            header(" host ", "example.com"),
+            header("host", "other.com"),
+            header("empty", ""),
+        ];
+        let mut variables = Variables::new(&values);
+
+        // First one wins on duplicated names
+        assert_eq!(
+            variables.substitute("https://{{ host }}/{{empty}}{{path}}?q={{path}}&{{$nope}}"),
+            "https://example.com/{{path}}?q={{path}}&{{$nope}}"
+        );
+        assert_eq!(variables.unresolved(), vec!["path", "$nope"]);
+    }
+
+    #[test]
+    fn resolve_request() {
+        let mut data = RequestData::default();
+        data.uri = "{{base}}/users".into();
+        data.headers = vec![header("Authorization", "Bearer {{token}}")];
+        data.body.raw.set("{\"id\": {{id}}}".into());
+        data.auth.password = "{{password}}".into();
+
+        let unresolved = resolve(
+            &mut data,
+            &[header("base", "http://localhost"), header("id", "7")],
+        )
+        .unwrap();
+
+        assert_eq!(data.uri, "http://localhost/users");
+        assert_eq!(data.headers[0].value, "Bearer {{token}}");
+        assert_eq!(data.body.raw.message, "{\"id\": 7}");
+        assert_eq!(unresolved, vec!["token", "password"]);
+    }
+
+    #[test]
+    fn collection_over_environment() {
+        let mut collection = CollectionData::default();
+        collection.variables = vec![header("host", "collection.com"), header(" ", "skipped")];
+        let values = collection.merge_variables(vec![
+            header("host", "environment.com"),
+            header("port", "8080"),
+        ]);
+
+        let mut variables = Variables::new(&values);
+        assert_eq!(
+            variables.substitute("{{host}}:{{port}}"),
+            "collection.com:8080"
+        );
+    }
+
+    fn value(name: &str) -> String {
+        built_in(name).unwrap().unwrap()
+    }
+
+    #[test]
+    fn built_in_values() {
+        assert_eq!(value("$uuid").len(), 36);
+        assert!(uuid::Uuid::parse_str(&value("$guid")).is_ok());
+        assert!(value("$timestamp").parse::<i64>().unwrap() > 1_700_000_000);
+        assert!(value("$timestampMs").parse::<i64>().unwrap() > 1_700_000_000_000);
+        assert!(chrono::DateTime::parse_from_rfc3339(&value("$isoTimestamp")).is_ok());
+        assert_eq!(value("$base64 user:pass"), "dXNlcjpwYXNz");
+        assert_eq!(value("$urlEncode a b&c/~"), "a%20b%26c%2F~");
+
+        let email = value("$randomEmail");
+        assert!(email.ends_with("@example.com") && email.len() == 22);
+        assert_eq!(built_in("$unknown"), None);
+    }
+
+    #[test]
+    fn random_int_range() {
+        for _ in 0..100 {
+            let number: i64 = value("$randomInt 1 6").parse().unwrap();
+            assert!((1..=6).contains(&number));
+            let number: i64 = value("$randomInt").parse().unwrap();
+            assert!((0..=1000).contains(&number));
+        }
+        assert_eq!(value("$randomInt -3 -3"), "-3");
+        // Full range has no overflow
+        assert!(built_in(&format!("$randomInt {} {}", i64::MIN, i64::MAX))
+            .unwrap()
+            .is_ok());
+
+        assert!(built_in("$randomInt 6 1").unwrap().is_err());
+        assert!(built_in("$randomInt 1").unwrap().is_err());
+        assert!(built_in("$randomInt a b").unwrap().is_err());
+    }
+
+    #[test]
+    fn random_string_length() {
+        assert_eq!(value("$randomString").len(), 16);
+        let text = value("$randomString 40");
+        assert_eq!(text.len(), 40);
+        assert!(text.bytes().all(|byte| ALPHANUMERIC.contains(&byte)));
+        assert_eq!(value("$randomString 0"), "");
+
+        assert_eq!(
+            built_in("$randomString 4000000000").unwrap().unwrap_err(),
+            "length is above 100000"
+        );
+        assert!(built_in("$randomString ten").unwrap().is_err());
+    }
+
+    #[test]
+    fn nested_built_ins() {
+        let values = [header("user", "admin")];
+        let mut variables = Variables::new(&values);
+
+        let encoded = variables.substitute("{{$base64 {{$urlEncode {{user}}:a b}}}}");
+        assert_eq!(encoded, STANDARD.encode("admin%3Aa%20b"));
+
+        let encoded = variables.substitute("Basic {{$base64 {{$uuid}}}}");
+        let decoded = STANDARD
+            .decode(encoded.strip_prefix("Basic ").unwrap())
+            .unwrap();
+        assert_eq!(decoded.len(), 36);
+        assert!(variables.unresolved().is_empty());
+    }
+
+    #[test]
+    fn built_in_error_stops_resolve() {
+        let mut data = RequestData::default();
+        data.uri = "http://localhost/{{$randomString 4000000000}}/{{$nope}}".into();
+        assert_eq!(
+            resolve(&mut data, &[]).unwrap_err(),
+            "{{$randomString 4000000000}}: length is above 100000"
+        );
+    }
+}
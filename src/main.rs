@@ -6,6 +6,7 @@ use egui::Context;
 
 use crate::{settings::Settings, states::States, ui::UI};
 
+pub mod cli;
 pub mod executor;
 pub mod settings;
 pub mod states;
@@ -56,7 +57,13 @@ impl App for Frapi {
     }
 }
 
+/// `frapi run ...` runs collection headless, see `cli::USAGE`.
+/// Release build on Windows has no console attached, output there only with redirect
 #[tokio::main]
 async fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "run") {
+        std::process::exit(cli::run(&args[1..]).await);
+    }
     Frapi::run();
 }
@@ -8,17 +8,24 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     settings::{
-        main_settings::MainPageSettings, options_settings::OptionsSettings, ui_settings::UISettings,
+        environments_settings::EnvironmentsSettings, main_settings::MainPageSettings,
+        options_settings::OptionsSettings, ui_settings::UISettings,
     },
     states::{
-        main_page::request::{HttpVersion, RequestHttpSetup, RequestWsSetup},
+        main_page::request::{
+            HttpVersion, MqttCommand, MqttVersion, RequestGrpcSetup, RequestHttpSetup,
+            RequestMqttSetup, RequestUdpSetup, RequestWsSetup, SocketIoSetup, StompCommand,
+            StompSetup, UdpPayloadFormat, WsMode,
+        },
         States,
     },
 };
 
+pub mod environments_settings;
 pub mod main_settings;
 pub mod options_settings;
 pub mod ui_settings;
+pub mod vault_settings;
 
 #[cfg(not(debug_assertions))]
 fn default_settings_filepath() -> String {
@@ -30,6 +37,16 @@ fn default_settings_filepath() -> String {
     "cache.json".into()
 }
 
+#[cfg(not(debug_assertions))]
+fn default_vault_filepath() -> String {
+    format!("{}/secrets.vault", default_settings_path())
+}
+
+#[cfg(debug_assertions)]
+fn default_vault_filepath() -> String {
+    "secrets.vault".into()
+}
+
 fn default_settings_path() -> String {
     if std::env::consts::OS == "macos" {
         let home_dir = std::env::var("HOME").unwrap();
@@ -45,6 +62,8 @@ pub struct Settings {
     pub ui: UISettings,
     pub main_page: MainPageSettings,
     pub options: OptionsSettings,
+    #[serde(default)]
+    pub environments: EnvironmentsSettings,
 }
 
 impl Settings {
@@ -160,6 +179,7 @@ impl Settings {
             ui: UISettings::default(),
             main_page: MainPageSettings::default(),
             options: OptionsSettings::default(),
+            environments: EnvironmentsSettings::default(),
         }
     }
 
@@ -215,6 +235,7 @@ impl Settings {
             ui: UISettings::from(&value.style),
             main_page: MainPageSettings::from_original(&value.main_page),
             options: OptionsSettings::from(&value.options),
+            environments: EnvironmentsSettings::from(&value.environments),
         }
     }
 }
@@ -228,6 +249,7 @@ impl From<&States> for Settings {
             ui: UISettings::from(&value.style),
             main_page: MainPageSettings::from(&value.main_page),
             options: OptionsSettings::from(&value.options),
+            environments: EnvironmentsSettings::from(&value.environments),
         }
     }
 }
@@ -244,9 +266,10 @@ mod tests {
         main_settings::entity::{
             collection_settings::CollectionSettings,
             request_settings::{
-                body_settings::RequestBodySettigns, method_settigns::Method,
-                protocol_settings::Protocol, request_setup_settings::RequestSetupSettings, Header,
-                RequestSettings,
+                auth_settings::RequestAuthSettings, body_settings::RequestBodySettigns,
+                method_settigns::Method, protocol_settings::Protocol,
+                request_setup_settings::RequestSetupSettings, scripts_settings::ScriptsSettings,
+                Header, RequestSettings,
             },
             Entity,
         },
@@ -298,6 +321,10 @@ mod tests {
             body: RequestBodySettigns::default(),
             message: "".into(),
             setup: RequestSetupSettings::http(),
+            auth: RequestAuthSettings::default(),
+            captures: vec![],
+            assertions: vec![],
+            scripts: ScriptsSettings::default(),
         };
 
         let request_1 = RequestSettings {
@@ -315,6 +342,10 @@ mod tests {
             body: RequestBodySettigns::default(),
             message: "".into(),
             setup: RequestSetupSettings::http(),
+            auth: RequestAuthSettings::default(),
+            captures: vec![],
+            assertions: vec![],
+            scripts: ScriptsSettings::default(),
         };
 
         let request_2 = RequestSettings {
@@ -332,6 +363,10 @@ mod tests {
             body: RequestBodySettigns::default(),
             message: "".into(),
             setup: RequestSetupSettings::http(),
+            auth: RequestAuthSettings::default(),
+            captures: vec![],
+            assertions: vec![],
+            scripts: ScriptsSettings::default(),
         };
 
         let collection_1 = CollectionSettings {
@@ -339,6 +374,10 @@ mod tests {
             name: "Collection 1".into(),
             description: "Description for Collection 1".into(),
             requests: vec![request_1, request_2],
+            auth: RequestAuthSettings::default(),
+            variables: vec![],
+            headers: vec![],
+            scripts: ScriptsSettings::default(),
         };
 
         let request_1 = RequestSettings {
@@ -356,6 +395,10 @@ mod tests {
             body: RequestBodySettigns::default(),
             message: "".into(),
             setup: RequestSetupSettings::http(),
+            auth: RequestAuthSettings::default(),
+            captures: vec![],
+            assertions: vec![],
+            scripts: ScriptsSettings::default(),
         };
 
         let collection_2 = CollectionSettings {
@@ -363,6 +406,10 @@ mod tests {
             name: "Collection 2".into(),
             description: "Description for Collection 2".into(),
             requests: vec![request_1],
+            auth: RequestAuthSettings::default(),
+            variables: vec![],
+            headers: vec![],
+            scripts: ScriptsSettings::default(),
         };
 
         let request_1 = RequestSettings {
@@ -380,14 +427,18 @@ mod tests {
             body: RequestBodySettigns::default(),
             message: "".into(),
             setup: RequestSetupSettings::http(),
+            auth: RequestAuthSettings::default(),
+            captures: vec![],
+            assertions: vec![],
+            scripts: ScriptsSettings::default(),
         };
 
         let main_page = MainPageSettings {
             entities: vec![
-                Entity::REQUEST(request_0),
-                Entity::COLLECTION(collection_1),
-                Entity::COLLECTION(collection_2),
-                Entity::REQUEST(request_1),
+                Entity::REQUEST(Box::new(request_0)),
+                Entity::COLLECTION(Box::new(collection_1)),
+                Entity::COLLECTION(Box::new(collection_2)),
+                Entity::REQUEST(Box::new(request_1)),
             ],
         };
 
@@ -400,6 +451,7 @@ mod tests {
             ui: ui_settings,
             main_page,
             options,
+            environments: EnvironmentsSettings::default(),
         };
 
         let json = serde_json::to_string_pretty(&application).unwrap();
@@ -466,9 +518,19 @@ impl From<&RequestHttpSetup> for RequestHttpSetupSettings {
 
 /// Settings to make ws reqeust
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
 pub struct RequestWsSetupSettings {
     pub reconnection_timeout: usize,
     pub reconnection_attempts: usize,
+    /// Code sent in Close frame on termination
+    pub close_code: u16,
+    /// Reason sent in Close frame on termination
+    pub close_reason: String,
+    /// Protocol working on top of WS session
+    pub mode: WsModeSetting,
+    pub socket_io: SocketIoSetupSettings,
+    pub stomp: StompSetupSettings,
+    pub graphql: GraphQlWsSetupSettings,
 }
 
 impl Default for RequestWsSetupSettings {
@@ -476,6 +538,12 @@ impl Default for RequestWsSetupSettings {
         Self {
             reconnection_timeout: 5000,
             reconnection_attempts: 3,
+            close_code: 1000,
+            close_reason: "".into(),
+            mode: WsModeSetting::RAW,
+            socket_io: SocketIoSetupSettings::default(),
+            stomp: StompSetupSettings::default(),
+            graphql: GraphQlWsSetupSettings::default(),
         }
     }
 }
@@ -485,6 +553,262 @@ impl From<&RequestWsSetup> for RequestWsSetupSettings {
         Self {
             reconnection_timeout: value.reconnection_timeout.parse::<usize>().unwrap(),
             reconnection_attempts: value.reconnection_attempts.parse::<usize>().unwrap(),
+            close_code: value.close_code(),
+            close_reason: value.close_reason.clone(),
+            mode: match value.mode {
+                WsMode::RAW => WsModeSetting::RAW,
+                WsMode::SOCKETIO => WsModeSetting::SOCKETIO,
+                WsMode::STOMP => WsModeSetting::STOMP,
+                WsMode::GRAPHQL => WsModeSetting::GRAPHQL,
+                WsMode::JSONRPC => WsModeSetting::JSONRPC,
+            },
+            socket_io: SocketIoSetupSettings::from(&value.socket_io),
+            stomp: StompSetupSettings::from(&value.stomp),
+            graphql: GraphQlWsSetupSettings {
+                connection_params: value.graphql.connection_params.clone(),
+            },
+        }
+    }
+}
+
+/// Protocol working on top of WS session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub enum WsModeSetting {
+    /// Messages sent as is
+    #[default]
+    RAW,
+    /// Socket.IO client over Engine.IO v4
+    SOCKETIO,
+    /// STOMP client frames
+    STOMP,
+    /// GraphQL subscriptions over graphql-transport-ws
+    GRAPHQL,
+    /// JSON-RPC 2.0 calls with replies correlation
+    JSONRPC,
+}
+
+/// Settings of Socket.IO mode
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SocketIoSetupSettings {
+    /// Namespace to connect after handshake
+    pub namespace: String,
+    /// Optional JSON auth payload sent with namespace connect
+    pub auth: String,
+    /// Event name for outgoing messages
+    pub event: String,
+}
+
+impl Default for SocketIoSetupSettings {
+    fn default() -> Self {
+        Self {
+            namespace: "/".into(),
+            auth: "".into(),
+            event: "message".into(),
+        }
+    }
+}
+
+impl From<&SocketIoSetup> for SocketIoSetupSettings {
+    fn from(value: &SocketIoSetup) -> Self {
+        Self {
+            namespace: value.namespace.clone(),
+            auth: value.auth.clone(),
+            event: value.event.clone(),
         }
     }
 }
+
+/// Settings of GraphQL subscriptions mode
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(default)]
+pub struct GraphQlWsSetupSettings {
+    /// Optional JSON payload of connection_init message
+    pub connection_params: String,
+}
+
+/// Settings of STOMP mode
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(default)]
+pub struct StompSetupSettings {
+    /// Virtual host for CONNECT frame
+    pub host: String,
+    pub login: String,
+    pub passcode: String,
+    /// Frame sent with message
+    pub command: StompCommandSetting,
+    pub destination: String,
+    /// Extra frame headers, `key:value` per line
+    pub headers: String,
+}
+
+impl From<&StompSetup> for StompSetupSettings {
+    fn from(value: &StompSetup) -> Self {
+        Self {
+            host: value.host.clone(),
+            login: value.login.clone(),
+            passcode: value.passcode.clone(),
+            command: match value.command {
+                StompCommand::SEND => StompCommandSetting::SEND,
+                StompCommand::SUBSCRIBE => StompCommandSetting::SUBSCRIBE,
+                StompCommand::UNSUBSCRIBE => StompCommandSetting::UNSUBSCRIBE,
+            },
+            destination: value.destination.clone(),
+            headers: value.headers.clone(),
+        }
+    }
+}
+
+/// Client frames user could send in STOMP session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub enum StompCommandSetting {
+    #[default]
+    SEND,
+    SUBSCRIBE,
+    UNSUBSCRIBE,
+}
+
+/// Settings to make gRPC reqeust
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(default)]
+pub struct RequestGrpcSetupSettings {
+    /// Paths of .proto files with services
+    pub proto_files: Vec<String>,
+    /// Extra import paths for .proto files, `;` separated
+    pub import_paths: String,
+    /// Get services from server reflection instead of .proto files
+    pub use_reflection: bool,
+    /// Full service name, with package
+    pub service: String,
+    pub method: String,
+}
+
+impl From<&RequestGrpcSetup> for RequestGrpcSetupSettings {
+    fn from(value: &RequestGrpcSetup) -> Self {
+        Self {
+            proto_files: value.proto_files.clone(),
+            import_paths: value.import_paths.clone(),
+            use_reflection: value.use_reflection,
+            service: value.service.clone(),
+            method: value.method.clone(),
+        }
+    }
+}
+
+/// Settings to make MQTT reqeust
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RequestMqttSetupSettings {
+    /// Empty id - generated on connect
+    pub client_id: String,
+    pub username: String,
+    pub password: String,
+    /// Keep alive interval, seconds
+    pub keep_alive: u64,
+    /// Clean session for 3.1.1, clean start for 5
+    pub clean_session: bool,
+    pub version: MqttVersionSetting,
+    /// Packet sent with message
+    pub command: MqttCommandSetting,
+    /// Topic to publish or topic filter to (un)subscribe
+    pub topic: String,
+    pub qos: u8,
+    pub retain: bool,
+}
+
+impl Default for RequestMqttSetupSettings {
+    fn default() -> Self {
+        Self {
+            client_id: "".into(),
+            username: "".into(),
+            password: "".into(),
+            keep_alive: 60,
+            clean_session: true,
+            version: MqttVersionSetting::V311,
+            command: MqttCommandSetting::PUBLISH,
+            topic: "".into(),
+            qos: 0,
+            retain: false,
+        }
+    }
+}
+
+impl From<&RequestMqttSetup> for RequestMqttSetupSettings {
+    fn from(value: &RequestMqttSetup) -> Self {
+        Self {
+            client_id: value.client_id.clone(),
+            username: value.username.clone(),
+            password: value.password.clone(),
+            keep_alive: value.keep_alive(),
+            clean_session: value.clean_session,
+            version: match value.version {
+                MqttVersion::V311 => MqttVersionSetting::V311,
+                MqttVersion::V5 => MqttVersionSetting::V5,
+            },
+            command: match value.command {
+                MqttCommand::PUBLISH => MqttCommandSetting::PUBLISH,
+                MqttCommand::SUBSCRIBE => MqttCommandSetting::SUBSCRIBE,
+                MqttCommand::UNSUBSCRIBE => MqttCommandSetting::UNSUBSCRIBE,
+            },
+            topic: value.topic.clone(),
+            qos: value.qos,
+            retain: value.retain,
+        }
+    }
+}
+
+/// MQTT protocol version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub enum MqttVersionSetting {
+    #[default]
+    V311,
+    V5,
+}
+
+/// Packets user could send in MQTT session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub enum MqttCommandSetting {
+    #[default]
+    PUBLISH,
+    SUBSCRIBE,
+    UNSUBSCRIBE,
+}
+
+/// Settings to make UDP reqeust
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RequestUdpSetupSettings {
+    /// How message editor content turned into datagram and replies shown
+    pub payload_format: UdpPayloadFormatSetting,
+    /// Time to wait replies after datagram sent, ms
+    pub reply_window: u64,
+}
+
+impl Default for RequestUdpSetupSettings {
+    fn default() -> Self {
+        Self {
+            payload_format: UdpPayloadFormatSetting::TEXT,
+            reply_window: 2000,
+        }
+    }
+}
+
+impl From<&RequestUdpSetup> for RequestUdpSetupSettings {
+    fn from(value: &RequestUdpSetup) -> Self {
+        Self {
+            payload_format: match value.payload_format {
+                UdpPayloadFormat::TEXT => UdpPayloadFormatSetting::TEXT,
+                UdpPayloadFormat::HEX => UdpPayloadFormatSetting::HEX,
+            },
+            reply_window: value.reply_window(),
+        }
+    }
+}
+
+/// UDP datagram payload format
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub enum UdpPayloadFormatSetting {
+    #[default]
+    TEXT,
+    HEX,
+}
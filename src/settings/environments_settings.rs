@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+use crate::states::environments::{Environment as StateEnvironment, Environments};
+
+// Settings::Environments - named sets of variables for {{name}} placeholders
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct EnvironmentsSettings {
+    pub items: Vec<EnvironmentSettings>,
+    /// Id of active environment
+    pub active: Option<String>,
+}
+
+impl From<&Environments> for EnvironmentsSettings {
+    fn from(value: &Environments) -> Self {
+        Self {
+            items: value.items.iter().map(EnvironmentSettings::from).collect(),
+            active: value.active.clone(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct EnvironmentSettings {
+    pub id: String,
+    pub name: String,
+    pub variables: Vec<VariableSettings>,
+    /// Names of secret variables, values kept in vault
+    pub secrets: Vec<String>,
+    /// Path of .env or JSON file variables imported from
+    pub source: String,
+    /// Reload variables when source file changes
+    pub watch: bool,
+}
+
+impl From<&StateEnvironment> for EnvironmentSettings {
+    fn from(value: &StateEnvironment) -> Self {
+        Self {
+            id: value.id.clone(),
+            name: value.name.clone(),
+            variables: value
+                .variables
+                .iter()
+                .map(|variable| VariableSettings {
+                    key: variable.key.clone(),
+                    value: variable.value.clone(),
+                })
+                .collect(),
+            secrets: value
+                .secrets
+                .iter()
+                .map(|secret| secret.key.clone())
+                .collect(),
+            source: value.source.clone(),
+            watch: value.watch,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub struct VariableSettings {
+    pub key: String,
+    pub value: String,
+}
@@ -12,17 +12,19 @@ pub mod request_settings;
 
 #[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
 pub enum Entity {
-    COLLECTION(CollectionSettings),
-    REQUEST(RequestSettings),
+    COLLECTION(Box<CollectionSettings>),
+    REQUEST(Box<RequestSettings>),
 }
 
 impl From<&StateEntity> for Entity {
     fn from(value: &StateEntity) -> Self {
         match value {
             StateEntity::COLLECTION(collection) => {
-                Self::COLLECTION(CollectionSettings::from(collection))
+                Self::COLLECTION(Box::new(CollectionSettings::from(collection)))
+            }
+            StateEntity::REQUEST(request) => {
+                Self::REQUEST(Box::new(RequestSettings::from(request)))
             }
-            StateEntity::REQUEST(request) => Self::REQUEST(RequestSettings::from(request)),
         }
     }
 }
@@ -31,9 +33,11 @@ impl Entity {
     pub fn from_original(value: &StateEntity) -> Self {
         match value {
             StateEntity::COLLECTION(collection) => {
-                Self::COLLECTION(CollectionSettings::from_original(collection))
+                Self::COLLECTION(Box::new(CollectionSettings::from_original(collection)))
+            }
+            StateEntity::REQUEST(request) => {
+                Self::REQUEST(Box::new(RequestSettings::from_original(request)))
             }
-            StateEntity::REQUEST(request) => Self::REQUEST(RequestSettings::from_original(request)),
         }
     }
 }
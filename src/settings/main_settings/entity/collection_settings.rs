@@ -1,8 +1,14 @@
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    settings::main_settings::entity::request_settings::RequestSettings,
-    states::main_page::collection::Collection as StateCollection,
+    settings::{
+        environments_settings::VariableSettings,
+        main_settings::entity::request_settings::{
+            auth_settings::RequestAuthSettings, scripts_settings::ScriptsSettings, Header,
+            RequestSettings,
+        },
+    },
+    states::main_page::collection::{Collection as StateCollection, CollectionData},
 };
 
 #[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
@@ -11,6 +17,18 @@ pub struct CollectionSettings {
     pub name: String,
     pub description: String,
     pub requests: Vec<RequestSettings>,
+    /// Auth for requests, wich inherit it
+    #[serde(default)]
+    pub auth: RequestAuthSettings,
+    /// Variables for requests, override ones of active environment
+    #[serde(default)]
+    pub variables: Vec<VariableSettings>,
+    /// Headers merged into every request, request headers win
+    #[serde(default)]
+    pub headers: Vec<Header>,
+    /// Scripts run around send of every request, before request ones
+    #[serde(default)]
+    pub scripts: ScriptsSettings,
 }
 
 impl From<&StateCollection> for CollectionSettings {
@@ -25,6 +43,10 @@ impl From<&StateCollection> for CollectionSettings {
             name: value.draft.name.clone(),
             description: value.draft.description.clone(),
             requests,
+            auth: RequestAuthSettings::from(&value.draft.auth),
+            variables: variables_settings(&value.draft),
+            headers: value.draft.headers.iter().map(Header::from).collect(),
+            scripts: ScriptsSettings::from(&value.draft.scripts),
         }
     }
 }
@@ -41,6 +63,21 @@ impl CollectionSettings {
             name: value.original.name.clone(),
             description: value.original.description.clone(),
             requests,
+            auth: RequestAuthSettings::from(&value.original.auth),
+            variables: variables_settings(&value.original),
+            headers: value.original.headers.iter().map(Header::from).collect(),
+            scripts: ScriptsSettings::from(&value.original.scripts),
         }
     }
 }
+
+fn variables_settings(value: &CollectionData) -> Vec<VariableSettings> {
+    value
+        .variables
+        .iter()
+        .map(|variable| VariableSettings {
+            key: variable.key.clone(),
+            value: variable.value.clone(),
+        })
+        .collect()
+}
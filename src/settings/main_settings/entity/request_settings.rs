@@ -3,16 +3,22 @@ use serde_json::Value;
 
 use crate::{
     settings::main_settings::entity::request_settings::{
-        body_settings::RequestBodySettigns, method_settigns::Method, protocol_settings::Protocol,
-        request_setup_settings::RequestSetupSettings,
+        assertion_settings::AssertionSettings, auth_settings::RequestAuthSettings,
+        body_settings::RequestBodySettigns, capture_settings::CaptureSettings,
+        method_settigns::Method, protocol_settings::Protocol,
+        request_setup_settings::RequestSetupSettings, scripts_settings::ScriptsSettings,
     },
     states::main_page::{generics::Header as StateHeader, request::Request as StateRequest},
 };
 
+pub mod assertion_settings;
+pub mod auth_settings;
 pub mod body_settings;
+pub mod capture_settings;
 pub mod method_settigns;
 pub mod protocol_settings;
 pub mod request_setup_settings;
+pub mod scripts_settings;
 
 #[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
 pub struct RequestSettings {
@@ -25,6 +31,17 @@ pub struct RequestSettings {
     pub body: RequestBodySettigns,
     pub message: String,
     pub setup: RequestSetupSettings,
+    #[serde(default)]
+    pub auth: RequestAuthSettings,
+    /// Response values stored into variables
+    #[serde(default)]
+    pub captures: Vec<CaptureSettings>,
+    /// Checks of response
+    #[serde(default)]
+    pub assertions: Vec<AssertionSettings>,
+    /// Pre-request and post-response scripts
+    #[serde(default)]
+    pub scripts: ScriptsSettings,
 }
 
 impl From<&StateRequest> for RequestSettings {
@@ -45,6 +62,20 @@ impl From<&StateRequest> for RequestSettings {
             body: RequestBodySettigns::from(&value.draft.body),
             message: value.draft.message.message.clone(),
             setup: RequestSetupSettings::from(&value.draft.setup),
+            auth: RequestAuthSettings::from(&value.draft.auth),
+            captures: value
+                .draft
+                .captures
+                .iter()
+                .map(CaptureSettings::from)
+                .collect(),
+            assertions: value
+                .draft
+                .assertions
+                .iter()
+                .map(AssertionSettings::from)
+                .collect(),
+            scripts: ScriptsSettings::from(&value.draft.scripts),
         }
     }
 }
@@ -67,6 +98,20 @@ impl RequestSettings {
             body: RequestBodySettigns::from(&value.original.body),
             message: value.original.message.message.clone(),
             setup: RequestSetupSettings::from(&value.original.setup),
+            auth: RequestAuthSettings::from(&value.original.auth),
+            captures: value
+                .original
+                .captures
+                .iter()
+                .map(CaptureSettings::from)
+                .collect(),
+            assertions: value
+                .original
+                .assertions
+                .iter()
+                .map(AssertionSettings::from)
+                .collect(),
+            scripts: ScriptsSettings::from(&value.original.scripts),
         }
     }
 }
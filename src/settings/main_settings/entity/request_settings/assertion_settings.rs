@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+use crate::states::main_page::request::request_data::{Assertion, AssertionKind};
+
+/// Check of response made after each send
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct AssertionSettings {
+    pub kind: AssertionKindSettings,
+    /// Header name or JSONPath
+    pub target: String,
+    /// Status, range, regex, value, type or milliseconds
+    pub expected: String,
+}
+
+impl From<&Assertion> for AssertionSettings {
+    fn from(value: &Assertion) -> Self {
+        Self {
+            kind: match value.kind {
+                AssertionKind::STATUS => AssertionKindSettings::STATUS,
+                AssertionKind::STATUSRANGE => AssertionKindSettings::STATUSRANGE,
+                AssertionKind::HEADEREXISTS => AssertionKindSettings::HEADEREXISTS,
+                AssertionKind::HEADERMATCHES => AssertionKindSettings::HEADERMATCHES,
+                AssertionKind::JSONEQUALS => AssertionKindSettings::JSONEQUALS,
+                AssertionKind::JSONCONTAINS => AssertionKindSettings::JSONCONTAINS,
+                AssertionKind::JSONTYPE => AssertionKindSettings::JSONTYPE,
+                AssertionKind::BODYMATCHES => AssertionKindSettings::BODYMATCHES,
+                AssertionKind::TIMEBELOW => AssertionKindSettings::TIMEBELOW,
+            },
+            target: value.target.clone(),
+            expected: value.expected.clone(),
+        }
+    }
+}
+
+/// What part of response checked and how
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub enum AssertionKindSettings {
+    #[default]
+    STATUS,
+    STATUSRANGE,
+    HEADEREXISTS,
+    HEADERMATCHES,
+    JSONEQUALS,
+    JSONCONTAINS,
+    JSONTYPE,
+    BODYMATCHES,
+    TIMEBELOW,
+}
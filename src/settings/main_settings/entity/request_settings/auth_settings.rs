@@ -0,0 +1,244 @@
+use serde::{Deserialize, Serialize};
+
+use crate::states::main_page::request::request_data::{
+    ApiKeyLocation, AuthType, AwsSigV4Auth, HmacAlgorithm, HmacAuth, HmacEncoding, HmacTimestamp,
+    OAuth2Auth, OAuth2Grant, RequestAuth,
+};
+
+/// Request authorization
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct RequestAuthSettings {
+    pub auth_type: AuthTypeSettings,
+    /// Basic and Digest user
+    pub username: String,
+    /// Basic and Digest password
+    pub password: String,
+    /// Bearer token
+    pub token: String,
+    /// API key header or query param name
+    pub api_key_name: String,
+    pub api_key_value: String,
+    pub api_key_location: ApiKeyLocationSettings,
+    pub oauth2: OAuth2Settings,
+    pub aws: AwsSigV4Settings,
+    pub hmac: HmacSettings,
+}
+
+impl From<&RequestAuth> for RequestAuthSettings {
+    fn from(value: &RequestAuth) -> Self {
+        Self {
+            auth_type: match value.auth_type {
+                AuthType::INHERIT => AuthTypeSettings::INHERIT,
+                AuthType::NONE => AuthTypeSettings::NONE,
+                AuthType::BASIC => AuthTypeSettings::BASIC,
+                AuthType::DIGEST => AuthTypeSettings::DIGEST,
+                AuthType::BEARER => AuthTypeSettings::BEARER,
+                AuthType::APIKEY => AuthTypeSettings::APIKEY,
+                AuthType::OAUTH2 => AuthTypeSettings::OAUTH2,
+                AuthType::AWSSIGV4 => AuthTypeSettings::AWSSIGV4,
+                AuthType::HMAC => AuthTypeSettings::HMAC,
+            },
+            username: value.username.clone(),
+            password: value.password.clone(),
+            token: value.token.clone(),
+            api_key_name: value.api_key_name.clone(),
+            api_key_value: value.api_key_value.clone(),
+            api_key_location: match value.api_key_location {
+                ApiKeyLocation::HEADER => ApiKeyLocationSettings::HEADER,
+                ApiKeyLocation::QUERY => ApiKeyLocationSettings::QUERY,
+            },
+            oauth2: OAuth2Settings::from(&value.oauth2),
+            aws: AwsSigV4Settings::from(&value.aws),
+            hmac: HmacSettings::from(&value.hmac),
+        }
+    }
+}
+
+/// Authorization scheme
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub enum AuthTypeSettings {
+    /// Auth of parent collection
+    #[default]
+    INHERIT,
+    NONE,
+    BASIC,
+    DIGEST,
+    BEARER,
+    APIKEY,
+    OAUTH2,
+    AWSSIGV4,
+    HMAC,
+}
+
+/// Where API key is sent
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub enum ApiKeyLocationSettings {
+    #[default]
+    HEADER,
+    QUERY,
+}
+
+/// OAuth 2.0 token acquiring
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct OAuth2Settings {
+    pub grant: OAuth2GrantSettings,
+    pub token_url: String,
+    /// Authorization code grant only
+    pub auth_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    /// Space separated scopes
+    pub scope: String,
+    /// Password grant user
+    pub username: String,
+    /// Password grant password
+    pub password: String,
+    /// Port of local redirect listener. Empty - random
+    pub redirect_port: String,
+    /// Client id and secret sent in form, instead of Basic header
+    pub credentials_in_body: bool,
+}
+
+impl From<&OAuth2Auth> for OAuth2Settings {
+    fn from(value: &OAuth2Auth) -> Self {
+        Self {
+            grant: match value.grant {
+                OAuth2Grant::CLIENTCREDENTIALS => OAuth2GrantSettings::CLIENTCREDENTIALS,
+                OAuth2Grant::PASSWORD => OAuth2GrantSettings::PASSWORD,
+                OAuth2Grant::AUTHCODE => OAuth2GrantSettings::AUTHCODE,
+            },
+            token_url: value.token_url.clone(),
+            auth_url: value.auth_url.clone(),
+            client_id: value.client_id.clone(),
+            client_secret: value.client_secret.clone(),
+            scope: value.scope.clone(),
+            username: value.username.clone(),
+            password: value.password.clone(),
+            redirect_port: value.redirect_port.clone(),
+            credentials_in_body: value.credentials_in_body,
+        }
+    }
+}
+
+/// OAuth 2.0 grant type
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub enum OAuth2GrantSettings {
+    #[default]
+    CLIENTCREDENTIALS,
+    PASSWORD,
+    AUTHCODE,
+}
+
+/// AWS Signature Version 4 signing
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct AwsSigV4Settings {
+    pub access_key: String,
+    pub secret_key: String,
+    /// Temporary credentials only
+    pub session_token: String,
+    pub region: String,
+    pub service: String,
+}
+
+impl From<&AwsSigV4Auth> for AwsSigV4Settings {
+    fn from(value: &AwsSigV4Auth) -> Self {
+        Self {
+            access_key: value.access_key.clone(),
+            secret_key: value.secret_key.clone(),
+            session_token: value.session_token.clone(),
+            region: value.region.clone(),
+            service: value.service.clone(),
+        }
+    }
+}
+
+/// HMAC signature over string built from template
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct HmacSettings {
+    pub algorithm: HmacAlgorithmSettings,
+    pub encoding: HmacEncodingSettings,
+    pub secret: String,
+    /// Secret is base64 of key bytes
+    pub secret_base64: bool,
+    /// String to sign with placeholders
+    pub template: String,
+    pub signature_header: String,
+    /// Value of signature header with {signature} placeholder
+    pub signature_value: String,
+    /// Empty - timestamp not sent
+    pub timestamp_header: String,
+    pub timestamp_format: HmacTimestampSettings,
+}
+
+impl Default for HmacSettings {
+    fn default() -> Self {
+        Self {
+            algorithm: HmacAlgorithmSettings::SHA256,
+            encoding: HmacEncodingSettings::HEX,
+            secret: "".into(),
+            secret_base64: false,
+            template: "{method}\n{path}\n{timestamp}\n{body_sha256}".into(),
+            signature_header: "X-Signature".into(),
+            signature_value: "{signature}".into(),
+            timestamp_header: "X-Timestamp".into(),
+            timestamp_format: HmacTimestampSettings::UNIXSECONDS,
+        }
+    }
+}
+
+impl From<&HmacAuth> for HmacSettings {
+    fn from(value: &HmacAuth) -> Self {
+        Self {
+            algorithm: match value.algorithm {
+                HmacAlgorithm::SHA1 => HmacAlgorithmSettings::SHA1,
+                HmacAlgorithm::SHA256 => HmacAlgorithmSettings::SHA256,
+                HmacAlgorithm::SHA512 => HmacAlgorithmSettings::SHA512,
+            },
+            encoding: match value.encoding {
+                HmacEncoding::HEX => HmacEncodingSettings::HEX,
+                HmacEncoding::BASE64 => HmacEncodingSettings::BASE64,
+            },
+            secret: value.secret.clone(),
+            secret_base64: value.secret_base64,
+            template: value.template.clone(),
+            signature_header: value.signature_header.clone(),
+            signature_value: value.signature_value.clone(),
+            timestamp_header: value.timestamp_header.clone(),
+            timestamp_format: match value.timestamp_format {
+                HmacTimestamp::UNIXSECONDS => HmacTimestampSettings::UNIXSECONDS,
+                HmacTimestamp::UNIXMILLIS => HmacTimestampSettings::UNIXMILLIS,
+                HmacTimestamp::ISO8601 => HmacTimestampSettings::ISO8601,
+            },
+        }
+    }
+}
+
+/// Hash function of HMAC
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub enum HmacAlgorithmSettings {
+    SHA1,
+    #[default]
+    SHA256,
+    SHA512,
+}
+
+/// Text form of signature bytes
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub enum HmacEncodingSettings {
+    #[default]
+    HEX,
+    BASE64,
+}
+
+/// Format of sent timestamp
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub enum HmacTimestampSettings {
+    #[default]
+    UNIXSECONDS,
+    UNIXMILLIS,
+    ISO8601,
+}
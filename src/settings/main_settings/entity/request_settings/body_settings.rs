@@ -1,13 +1,22 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::states::main_page::request::request_data::{BodyFromData, FormFieldType, RequestBody};
+use crate::states::main_page::request::request_data::{
+    BodyFromData, BodyMode, FormFieldType, GraphQlBody, JsonRpcBody, RequestBody,
+};
 
 #[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
 pub struct RequestBodySettigns {
+    /// Missing in files saved before, guessed by filled parts
+    #[serde(default)]
+    pub mode: Option<BodyModeSettings>,
     pub raw: String,
     pub form_data: Vec<BodyFromDataSettings>,
     pub binary_path: String,
+    #[serde(default)]
+    pub graphql: GraphQlBodySettings,
+    #[serde(default)]
+    pub json_rpc: JsonRpcBodySettings,
 }
 
 impl From<&RequestBody> for RequestBodySettigns {
@@ -18,9 +27,18 @@ impl From<&RequestBody> for RequestBodySettigns {
             .map(|val| BodyFromDataSettings::from(val))
             .collect();
         Self {
+            mode: Some(match value.mode {
+                BodyMode::RAW => BodyModeSettings::RAW,
+                BodyMode::FORMDATA => BodyModeSettings::FORMDATA,
+                BodyMode::BINARY => BodyModeSettings::BINARY,
+                BodyMode::GRAPHQL => BodyModeSettings::GRAPHQL,
+                BodyMode::JSONRPC => BodyModeSettings::JSONRPC,
+            }),
             raw: value.raw.message.clone(),
             form_data: form_data,
             binary_path: value.binary_path.clone(),
+            graphql: GraphQlBodySettings::from(&value.graphql),
+            json_rpc: JsonRpcBodySettings::from(&value.json_rpc),
         }
     }
 }
@@ -28,13 +46,27 @@ impl From<&RequestBody> for RequestBodySettigns {
 impl Default for RequestBodySettigns {
     fn default() -> Self {
         Self {
+            mode: Some(BodyModeSettings::default()),
             raw: "".into(),
             form_data: vec![],
             binary_path: "".into(),
+            graphql: GraphQlBodySettings::default(),
+            json_rpc: JsonRpcBodySettings::default(),
         }
     }
 }
 
+/// Part of request body to send
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub enum BodyModeSettings {
+    #[default]
+    RAW,
+    FORMDATA,
+    BINARY,
+    GRAPHQL,
+    JSONRPC,
+}
+
 /// Request Form Body data Fied Type
 #[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
 pub enum FormFieldTypeSettings {
@@ -63,3 +95,43 @@ impl From<&BodyFromData> for BodyFromDataSettings {
         }
     }
 }
+
+/// GraphQL operation
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct GraphQlBodySettings {
+    pub query: String,
+    pub variables: String,
+    pub operation_name: String,
+}
+
+impl From<&GraphQlBody> for GraphQlBodySettings {
+    fn from(value: &GraphQlBody) -> Self {
+        Self {
+            query: value.query.message.clone(),
+            variables: value.variables.message.clone(),
+            operation_name: value.operation_name.clone(),
+        }
+    }
+}
+
+/// JSON-RPC call
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct JsonRpcBodySettings {
+    pub method: String,
+    pub params: String,
+    pub notification: bool,
+    pub batch: bool,
+}
+
+impl From<&JsonRpcBody> for JsonRpcBodySettings {
+    fn from(value: &JsonRpcBody) -> Self {
+        Self {
+            method: value.method.clone(),
+            params: value.params.message.clone(),
+            notification: value.notification,
+            batch: value.batch,
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+use crate::states::main_page::request::request_data::{Capture, CaptureScope, CaptureSource};
+
+/// Value extracted from response into variable
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct CaptureSettings {
+    pub source: CaptureSourceSettings,
+    /// JSONPath, header or cookie name, regex
+    pub expression: String,
+    pub variable: String,
+    pub scope: CaptureScopeSettings,
+}
+
+impl From<&Capture> for CaptureSettings {
+    fn from(value: &Capture) -> Self {
+        Self {
+            source: match value.source {
+                CaptureSource::JSONPATH => CaptureSourceSettings::JSONPATH,
+                CaptureSource::HEADER => CaptureSourceSettings::HEADER,
+                CaptureSource::COOKIE => CaptureSourceSettings::COOKIE,
+                CaptureSource::REGEX => CaptureSourceSettings::REGEX,
+            },
+            expression: value.expression.clone(),
+            variable: value.variable.clone(),
+            scope: match value.scope {
+                CaptureScope::ENVIRONMENT => CaptureScopeSettings::ENVIRONMENT,
+                CaptureScope::COLLECTION => CaptureScopeSettings::COLLECTION,
+            },
+        }
+    }
+}
+
+/// Part of response value taken from
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub enum CaptureSourceSettings {
+    #[default]
+    JSONPATH,
+    HEADER,
+    COOKIE,
+    REGEX,
+}
+
+/// Where captured variable stored
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub enum CaptureScopeSettings {
+    #[default]
+    ENVIRONMENT,
+    COLLECTION,
+}
@@ -9,6 +9,11 @@ pub enum Protocol {
     HTTPS,
     WS,
     WSS,
+    GRPC,
+    GRPCS,
+    MQTT,
+    MQTTS,
+    UDP,
 }
 
 impl Display for Protocol {
@@ -18,6 +23,11 @@ impl Display for Protocol {
             Protocol::HTTPS => write!(f, "HTTPS"),
             Protocol::WS => write!(f, "WS"),
             Protocol::WSS => write!(f, "WSS"),
+            Protocol::GRPC => write!(f, "GRPC"),
+            Protocol::GRPCS => write!(f, "GRPCS"),
+            Protocol::MQTT => write!(f, "MQTT"),
+            Protocol::MQTTS => write!(f, "MQTTS"),
+            Protocol::UDP => write!(f, "UDP"),
         }
     }
 }
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    settings::{RequestHttpSetupSettings, RequestWsSetupSettings},
+    settings::{
+        RequestGrpcSetupSettings, RequestHttpSetupSettings, RequestMqttSetupSettings,
+        RequestUdpSetupSettings, RequestWsSetupSettings,
+    },
     states::main_page::request::RequestSetup,
 };
 
@@ -13,6 +16,9 @@ pub mod ws_setup_settigns;
 pub enum RequestSetupSettings {
     HTTP(RequestHttpSetupSettings),
     WS(RequestWsSetupSettings),
+    GRPC(RequestGrpcSetupSettings),
+    MQTT(RequestMqttSetupSettings),
+    UDP(RequestUdpSetupSettings),
 }
 
 impl Default for RequestSetupSettings {
@@ -30,6 +36,15 @@ impl From<&RequestSetup> for RequestSetupSettings {
             RequestSetup::WS(request_ws_setup) => {
                 Self::WS(RequestWsSetupSettings::from(request_ws_setup))
             }
+            RequestSetup::GRPC(request_grpc_setup) => {
+                Self::GRPC(RequestGrpcSetupSettings::from(request_grpc_setup))
+            }
+            RequestSetup::MQTT(request_mqtt_setup) => {
+                Self::MQTT(RequestMqttSetupSettings::from(request_mqtt_setup))
+            }
+            RequestSetup::UDP(request_udp_setup) => {
+                Self::UDP(RequestUdpSetupSettings::from(request_udp_setup))
+            }
         }
     }
 }
@@ -41,4 +56,13 @@ impl RequestSetupSettings {
     pub fn ws() -> Self {
         Self::WS(RequestWsSetupSettings::default())
     }
+    pub fn grpc() -> Self {
+        Self::GRPC(RequestGrpcSetupSettings::default())
+    }
+    pub fn mqtt() -> Self {
+        Self::MQTT(RequestMqttSetupSettings::default())
+    }
+    pub fn udp() -> Self {
+        Self::UDP(RequestUdpSetupSettings::default())
+    }
 }
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+use crate::states::main_page::request::request_data::Scripts;
+
+/// Rhai scripts run around send, of request or collection
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ScriptsSettings {
+    pub pre_request: String,
+    pub post_response: String,
+}
+
+impl From<&Scripts> for ScriptsSettings {
+    fn from(value: &Scripts) -> Self {
+        Self {
+            pre_request: value.pre_request.clone(),
+            post_response: value.post_response.clone(),
+        }
+    }
+}
@@ -0,0 +1,299 @@
+/// Encrypted storage of secret variables.
+/// Kept apart from settings file, so secrets never get into exports
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    io::Write,
+};
+
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::settings::default_vault_filepath;
+
+/// PBKDF2-HMAC-SHA256 iterations for master password, as OWASP recommends
+#[cfg(not(test))]
+const PBKDF2_ROUNDS: u32 = 600_000;
+/// Few iterations, so tests do not spend seconds on every key
+#[cfg(test)]
+const PBKDF2_ROUNDS: u32 = 1_000;
+
+/// Key of AES-256-GCM
+pub type VaultKey = [u8; 32];
+
+/// How vault key obtained
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub enum VaultModeSettings {
+    /// Derived from master password
+    #[default]
+    PASSWORD,
+    /// Random key kept by OS keyring, or user only file on Linux
+    KEYRING,
+}
+
+/// Vault file: secrets map as JSON, encrypted by AES-256-GCM
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub struct VaultSettings {
+    pub mode: VaultModeSettings,
+    /// Base64 salt of password key derivation
+    pub salt: String,
+    /// Base64 nonce of last encryption
+    pub nonce: String,
+    /// Base64 ciphertext, empty for new vault
+    pub data: String,
+}
+
+impl VaultSettings {
+    /// Empty vault with fresh salt
+    pub fn new(mode: VaultModeSettings) -> Self {
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        Self {
+            mode,
+            salt: STANDARD.encode(salt),
+            nonce: "".into(),
+            data: "".into(),
+        }
+    }
+
+    /// Vault from default path, None if not created yet
+    pub fn load() -> Result<Option<Self>, String> {
+        let path = default_vault_filepath();
+        match fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text)
+                .map(Some)
+                .map_err(|err| format!("Could not parse vault file. Path: {path}. Trace: {err}")),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(format!(
+                "Could not load vault file. Path: {path}. Error: {err}"
+            )),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = default_vault_filepath();
+        let text = serde_json::to_string_pretty(self)
+            .map_err(|err| format!("Could not convert vault to string. Trace: {err}"))?;
+
+        OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(text.as_bytes()))
+            .map_err(|err| format!("Could not save vault file. Path: {path}. Error: {err}"))
+    }
+
+    /// Key for master password
+    pub fn password_key(&self, password: &str) -> Result<VaultKey, String> {
+        let salt = STANDARD
+            .decode(&self.salt)
+            .map_err(|err| format!("Broken vault salt. Error: {err}"))?;
+        let mut key = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), &salt, PBKDF2_ROUNDS, &mut key);
+        Ok(key)
+    }
+
+    /// Replace content with encrypted secrets, under new nonce
+    pub fn encrypt(
+        &mut self,
+        key: &VaultKey,
+        secrets: &HashMap<String, String>,
+    ) -> Result<(), String> {
+        let plain = serde_json::to_vec(secrets)
+            .map_err(|err| format!("Could not convert secrets. Trace: {err}"))?;
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let data = cipher
+            .encrypt(&nonce, plain.as_slice())
+            .map_err(|_| "Could not encrypt secrets".to_string())?;
+
+        self.nonce = STANDARD.encode(nonce);
+        self.data = STANDARD.encode(data);
+        Ok(())
+    }
+
+    /// Secrets of vault. Fails on wrong key
+    pub fn decrypt(&self, key: &VaultKey) -> Result<HashMap<String, String>, String> {
+        if self.data.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let nonce = STANDARD
+            .decode(&self.nonce)
+            .map_err(|err| format!("Broken vault nonce. Error: {err}"))?;
+        let data = STANDARD
+            .decode(&self.data)
+            .map_err(|err| format!("Broken vault data. Error: {err}"))?;
+        if nonce.len() != 12 {
+            return Err("Broken vault nonce".into());
+        }
+
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+        let plain = cipher
+            .decrypt(Nonce::from_slice(&nonce), data.as_slice())
+            .map_err(|_| "Wrong password or key, vault could not be decrypted".to_string())?;
+
+        serde_json::from_slice(&plain).map_err(|err| format!("Broken vault content. Trace: {err}"))
+    }
+}
+
+/// Random key for OS kept vault
+pub fn random_key() -> VaultKey {
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    key
+}
+
+fn decode_key(text: &str) -> Result<VaultKey, String> {
+    STANDARD
+        .decode(text.trim())
+        .ok()
+        .and_then(|bytes| VaultKey::try_from(bytes.as_slice()).ok())
+        .ok_or("Broken vault key".to_string())
+}
+
+/// Vault key from OS keyring
+#[cfg(any(windows, target_os = "macos"))]
+pub fn os_key() -> Result<Option<VaultKey>, String> {
+    let entry = keyring::Entry::new("frapi", "vault-key").map_err(|err| err.to_string())?;
+    match entry.get_password() {
+        Ok(text) => decode_key(&text).map(Some),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(err) => Err(format!("Could not read OS keyring. Error: {err}")),
+    }
+}
+
+/// Store vault key in OS keyring
+#[cfg(any(windows, target_os = "macos"))]
+pub fn store_os_key(key: &VaultKey) -> Result<(), String> {
+    keyring::Entry::new("frapi", "vault-key")
+        .and_then(|entry| entry.set_password(&STANDARD.encode(key)))
+        .map_err(|err| format!("Could not write OS keyring. Error: {err}"))
+}
+
+/// Key file readable only by user. Linux keyring does not survive reboot,
+/// so file in config dir used instead
+#[cfg(not(any(windows, target_os = "macos")))]
+fn key_filepath() -> Result<std::path::PathBuf, String> {
+    let config = match std::env::var("XDG_CONFIG_HOME") {
+        Ok(path) if !path.is_empty() => std::path::PathBuf::from(path),
+        _ => std::path::PathBuf::from(
+            std::env::var("HOME").map_err(|_| "HOME is not set".to_string())?,
+        )
+        .join(".config"),
+    };
+    Ok(config.join("frapi").join("vault.key"))
+}
+
+/// Vault key from user key file
+#[cfg(not(any(windows, target_os = "macos")))]
+pub fn os_key() -> Result<Option<VaultKey>, String> {
+    let path = key_filepath()?;
+    match fs::read_to_string(&path) {
+        Ok(text) => decode_key(&text).map(Some),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(format!(
+            "Could not read vault key file. Path: {}. Error: {err}",
+            path.display()
+        )),
+    }
+}
+
+/// Store vault key in user key file
+#[cfg(not(any(windows, target_os = "macos")))]
+pub fn store_os_key(key: &VaultKey) -> Result<(), String> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let path = key_filepath()?;
+    let error = |err: std::io::Error| {
+        format!(
+            "Could not write vault key file. Path: {}. Error: {err}",
+            path.display()
+        )
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(error)?;
+    }
+    OpenOptions::new()
+        .write(true)
+        .truncate(true)
+        .create(true)
+        .mode(0o600)
+        .open(&path)
+        .and_then(|mut file| file.write_all(STANDARD.encode(key).as_bytes()))
+        .map_err(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secrets() -> HashMap<String, String> {
+        HashMap::from([
+            ("env-1/token".to_string(), "s3cr3t".to_string()),
+            ("env-2/password".to_string(), "p\"a:ss".to_string()),
+        ])
+    }
+
+    #[test]
+    fn encrypt_decrypt() {
+        let mut vault = VaultSettings::new(VaultModeSettings::PASSWORD);
+        let key = vault.password_key("master").unwrap();
+        vault.encrypt(&key, &secrets()).unwrap();
+        assert!(!vault.data.contains("s3cr3t"));
+        assert_eq!(vault.decrypt(&key).unwrap(), secrets());
+
+        // Same password and salt give same key, nonce changes on every encryption
+        let nonce = vault.nonce.clone();
+        let key = vault.password_key("master").unwrap();
+        vault.encrypt(&key, &secrets()).unwrap();
+        assert_ne!(vault.nonce, nonce);
+        assert_eq!(vault.decrypt(&key).unwrap(), secrets());
+
+        let key = random_key();
+        vault.encrypt(&key, &HashMap::new()).unwrap();
+        assert_eq!(vault.decrypt(&key).unwrap(), HashMap::new());
+    }
+
+    #[test]
+    fn wrong_key() {
+        let mut vault = VaultSettings::new(VaultModeSettings::PASSWORD);
+        let key = vault.password_key("master").unwrap();
+        vault.encrypt(&key, &secrets()).unwrap();
+
+        let wrong = vault.password_key("Master").unwrap();
+        assert_eq!(
+            vault.decrypt(&wrong).unwrap_err(),
+            "Wrong password or key, vault could not be decrypted"
+        );
+        // Salt is part of key, same password of other vault does not fit
+        let other = VaultSettings::new(VaultModeSettings::PASSWORD);
+        assert_eq!(
+            vault
+                .decrypt(&other.password_key("master").unwrap())
+                .unwrap_err(),
+            "Wrong password or key, vault could not be decrypted"
+        );
+    }
+
+    #[test]
+    fn empty_and_broken() {
+        let vault = VaultSettings::new(VaultModeSettings::KEYRING);
+        assert_eq!(vault.decrypt(&random_key()).unwrap(), HashMap::new());
+
+        let mut vault = VaultSettings::new(VaultModeSettings::PASSWORD);
+        let key = random_key();
+        vault.encrypt(&key, &secrets()).unwrap();
+        vault.nonce = STANDARD.encode([0u8; 8]);
+        assert_eq!(vault.decrypt(&key).unwrap_err(), "Broken vault nonce");
+
+        assert_eq!(decode_key(&STANDARD.encode(key)).unwrap(), key);
+        assert_eq!(decode_key("c2hvcnQ=").unwrap_err(), "Broken vault key");
+    }
+}
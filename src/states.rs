@@ -13,11 +13,21 @@ use crate::{
         ui_settings::{UISettings, UITheme},
         Settings,
     },
-    states::main_page::{entity::Entity, MainPage},
+    states::{
+        environments::{CaptureRecord, Environments},
+        main_page::{
+            entity::Entity, generics::Header, request::request_data::CaptureScope, MainPage,
+        },
+        runner::Runner,
+        vault::Vault,
+    },
     ui::{colors::ThemeColors, fonts::Fonts},
 };
 
+pub mod environments;
 pub mod main_page;
+pub mod runner;
+pub mod vault;
 
 #[derive(Clone, Debug)]
 pub struct States {
@@ -25,18 +35,41 @@ pub struct States {
     pub main_page: MainPage,
     pub style: Style,
     pub options: Options,
+    pub environments: Environments,
+    /// Storage of secret variables values
+    pub vault: Vault,
+    /// Run of collection requests
+    pub runner: Runner,
     pub events: Arc<Mutex<Events>>,
 }
 
 impl From<&Settings> for States {
     fn from(value: &Settings) -> Self {
-        Self {
+        let mut events = Events::new();
+        let vault = match Vault::load() {
+            Ok(val) => val,
+            Err(err) => {
+                events.event_error(&err);
+                Vault::default()
+            }
+        };
+
+        let mut states = Self {
             current_page: Page::MAIN,
             main_page: MainPage::from(value),
             style: Style::from(&value.ui),
             options: Options::from(&value.options),
-            events: Arc::new(Mutex::new(Events::new())),
+            environments: Environments::from(&value.environments),
+            vault,
+            runner: Runner::default(),
+            events: Arc::new(Mutex::new(events)),
+        };
+
+        // Key kept by OS needs no user input
+        if states.vault.uses_os_key() {
+            states.unlock_vault("");
         }
+        states
     }
 }
 
@@ -57,8 +90,62 @@ impl States {
         self.events.lock().unwrap().clear_events();
     }
 
+    /// Variables of active environment with its secrets, when vault unlocked
+    pub fn environment_variables(&self) -> Vec<Header> {
+        let mut variables = self.environments.variables();
+        if self.vault.is_unlocked() {
+            variables.extend(self.environments.secrets());
+        }
+        variables
+    }
+
+    /// Variables for selected entity: of its collection, then of active environment
+    pub fn variables(&self) -> Vec<Header> {
+        let environment = self.environment_variables();
+        match self.main_page.selected_collection() {
+            Some(collection) => collection.draft.merge_variables(environment),
+            None => environment,
+        }
+    }
+
+    /// Create vault when missing, or unlock existing one.
+    /// password - master password, empty to use OS kept key on creation
+    pub fn unlock_vault(&mut self, password: &str) {
+        let result = if self.vault.is_created() {
+            self.vault.unlock(password)
+        } else if password.is_empty() {
+            self.vault.create(None)
+        } else {
+            self.vault.create(Some(password))
+        };
+
+        match result.and_then(|_| self.vault.secrets()) {
+            Ok(secrets) => {
+                self.environments.fill_secrets(&secrets);
+                self.event_info(&"Vault unlocked".into());
+            }
+            Err(err) => self.event_error(&format!("Could not unlock vault. Error: {err}")),
+        }
+    }
+
+    /// Lock vault and forget secrets values
+    pub fn lock_vault(&mut self) {
+        self.vault.lock();
+        self.environments.fill_secrets(&Default::default());
+        self.event_info(&"Vault locked".into());
+    }
+
+    /// Encrypt secrets into vault, when it unlocked
+    fn save_vault(&mut self) {
+        if let Err(err) = self.vault.save(&self.environments.secret_values()) {
+            self.event_error(&format!("Could not save vault. Error: {err}"));
+        }
+    }
+
     /// Process Entities after save - removing chage status
     pub fn on_save_complete(&mut self) {
+        self.save_vault();
+        self.environments.is_changed = false;
         for i in 0..self.main_page.entities.len() {
             match &mut self.main_page.entities[i] {
                 Entity::COLLECTION(collection) => collection.on_save(),
@@ -82,6 +169,12 @@ impl States {
         self.style = new_states.style;
         self.options = new_states.options;
         self.main_page = new_states.main_page;
+        self.environments = new_states.environments;
+        self.runner = Runner::default();
+        match self.vault.secrets() {
+            Ok(secrets) => self.environments.fill_secrets(&secrets),
+            Err(err) => self.event_error(&err),
+        }
     }
 
     /// Saving all data and mark changed entities to unchanged
@@ -95,8 +188,33 @@ impl States {
         };
     }
 
+    /// Saving environments along with original states of entities
+    pub fn save_environments(&mut self) {
+        match Settings::from_original(&*self).save(None) {
+            Ok(_) => {
+                self.event_info(&"Settings [environments] save complete successful".into());
+                self.save_vault();
+                self.environments.is_changed = false;
+            }
+            Err(err) => self.event_error(&err),
+        };
+    }
+
     /// Update all planned changes for States Data, for next Frame
     pub fn update(&mut self) {
+        self.store_captured();
+        if self.runner.is_running() {
+            let environment = self.environment_variables();
+            self.runner
+                .update(&mut self.main_page, environment, &self.events);
+        }
+        for result in self.environments.reload_watched() {
+            match result {
+                Ok(msg) => self.event_info(&msg),
+                Err(err) => self.event_error(&err),
+            }
+        }
+
         let move_done = self.main_page.update_request_move();
 
         let dnd_done = self.main_page.update_dnd();
@@ -108,6 +226,65 @@ impl States {
         }
     }
 
+    /// Store values captured from responses into variables of active environment
+    /// or collection of request
+    fn store_captured(&mut self) {
+        // (collection index, request name, value)
+        let mut captured = vec![];
+        for (i, entity) in self.main_page.entities.iter().enumerate() {
+            match entity {
+                Entity::COLLECTION(collection) => {
+                    for request in collection.requests.iter() {
+                        for value in request.executor.take_captured() {
+                            captured.push((Some(i), request.draft.name.clone(), value));
+                        }
+                    }
+                }
+                Entity::REQUEST(request) => {
+                    for value in request.executor.take_captured() {
+                        captured.push((None, request.draft.name.clone(), value));
+                    }
+                }
+            }
+        }
+
+        for (collection_idx, request, value) in captured {
+            let target = match value.scope {
+                CaptureScope::ENVIRONMENT => self
+                    .environments
+                    .set_variable(&value.variable, &value.value),
+                CaptureScope::COLLECTION => collection_idx
+                    .and_then(|i| self.main_page.get_collection_mut(i))
+                    .map(|collection| {
+                        environments::set_variable(
+                            &mut collection.draft.variables,
+                            &value.variable,
+                            &value.value,
+                        );
+                        collection.is_changed = true;
+                        collection.draft.name.clone()
+                    }),
+            };
+
+            if target.is_none() {
+                let reason = match value.scope {
+                    CaptureScope::ENVIRONMENT => "no active environment",
+                    CaptureScope::COLLECTION => "request is not in collection",
+                };
+                self.event_warning(&format!(
+                    "Captured {{{{{}}}}} not stored: {reason}",
+                    value.variable
+                ));
+            }
+
+            self.environments.log_captured(CaptureRecord {
+                request,
+                target,
+                captured: value,
+            });
+        }
+    }
+
     /// Saving data with original states. Mostly need for savings on moved entity
     pub fn save_original(&mut self, save_to_path: Option<PathBuf>) {
         match Settings::from_original(&*self).save(save_to_path) {
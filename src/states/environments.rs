@@ -0,0 +1,320 @@
+/// Environments with variables, substituted on send as {{name}}
+use std::{
+    collections::HashMap,
+    fs,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{
+    executor::captures::Captured,
+    settings::environments_settings::{EnvironmentSettings, EnvironmentsSettings},
+    states::main_page::generics::Header,
+};
+
+pub mod import;
+
+/// Amount of captured values kept for inspector
+const CAPTURED_HISTORY: usize = 100;
+
+/// How often watched files checked for changes
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Default)]
+pub struct Environments {
+    pub items: Vec<Environment>,
+    /// Id of active environment
+    pub active: Option<String>,
+    /// Environments changed since last save
+    pub is_changed: bool,
+    /// Values captured from responses, newest last.
+    /// Only for inspector, dont store in settings
+    pub captured: Vec<CaptureRecord>,
+    /// Last check of watched files
+    watched_at: Option<Instant>,
+}
+
+impl From<&EnvironmentsSettings> for Environments {
+    fn from(value: &EnvironmentsSettings) -> Self {
+        Self {
+            items: value.items.iter().map(Environment::from).collect(),
+            active: value.active.clone(),
+            is_changed: false,
+            captured: vec![],
+            watched_at: None,
+        }
+    }
+}
+
+impl Environments {
+    pub fn active(&self) -> Option<&Environment> {
+        let active = self.active.as_ref()?;
+        self.items
+            .iter()
+            .find(|environment| &environment.id == active)
+    }
+
+    /// Name of active environment for UI
+    pub fn active_name(&self) -> String {
+        match self.active() {
+            Some(environment) => environment.name.clone(),
+            None => "No Environment".into(),
+        }
+    }
+
+    /// Variables of active environment, without empty names
+    pub fn variables(&self) -> Vec<Header> {
+        match self.active() {
+            Some(environment) => environment
+                .variables
+                .iter()
+                .filter(|variable| !variable.key.trim().is_empty())
+                .cloned()
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Secret variables of active environment, without empty names
+    pub fn secrets(&self) -> Vec<Header> {
+        match self.active() {
+            Some(environment) => environment
+                .secrets
+                .iter()
+                .filter(|secret| !secret.key.trim().is_empty())
+                .cloned()
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Values of all secrets for vault, by "environment id/name"
+    pub fn secret_values(&self) -> HashMap<String, String> {
+        let mut values = HashMap::new();
+        for environment in self.items.iter() {
+            for secret in environment.secrets.iter() {
+                if !secret.key.trim().is_empty() {
+                    values.insert(
+                        format!("{}/{}", environment.id, secret.key.trim()),
+                        secret.value.clone(),
+                    );
+                }
+            }
+        }
+        values
+    }
+
+    /// Set values of secrets from vault. Secret missing in vault gets empty value
+    pub fn fill_secrets(&mut self, values: &HashMap<String, String>) {
+        for environment in self.items.iter_mut() {
+            for secret in environment.secrets.iter_mut() {
+                let key = format!("{}/{}", environment.id, secret.key.trim());
+                secret.value = values.get(&key).cloned().unwrap_or_default();
+            }
+        }
+    }
+
+    /// Set variable of active environment, adding it if missing.
+    /// Secret with same name updated instead of plain variable.
+    /// Returns name of environment, None when no active one
+    pub fn set_variable(&mut self, name: &str, value: &str) -> Option<String> {
+        let active = self.active.clone()?;
+        let environment = self
+            .items
+            .iter_mut()
+            .find(|environment| environment.id == active)?;
+
+        environment.set(name, value);
+        self.is_changed = true;
+        Some(environment.name.clone())
+    }
+
+    /// Remember captured value for inspector
+    pub fn log_captured(&mut self, record: CaptureRecord) {
+        self.captured.push(record);
+        if self.captured.len() > CAPTURED_HISTORY {
+            self.captured.remove(0);
+        }
+    }
+
+    /// Import variables from .env or JSON file into environment, remembering file as its source
+    pub fn import_file(&mut self, index: usize, path: String) -> Result<usize, String> {
+        let environment = &mut self.items[index];
+        environment.source = path;
+        environment.modified = None;
+        let count = environment.reload()?;
+        self.is_changed = true;
+        Ok(count)
+    }
+
+    /// Reload environments from watched files, changed since last check.
+    /// Returns messages of reloads, errors stop watching
+    pub fn reload_watched(&mut self) -> Vec<Result<String, String>> {
+        if self
+            .watched_at
+            .is_some_and(|watched_at| watched_at.elapsed() < WATCH_INTERVAL)
+        {
+            return vec![];
+        }
+        self.watched_at = Some(Instant::now());
+
+        let mut result = vec![];
+        for environment in self.items.iter_mut() {
+            if !environment.watch || environment.source.is_empty() {
+                continue;
+            }
+
+            let modified = fs::metadata(&environment.source).and_then(|meta| meta.modified());
+            match modified {
+                Ok(modified) if environment.modified == Some(modified) => {}
+                Ok(_) => match environment.reload() {
+                    Ok(count) => result.push(Ok(format!(
+                        "Environment {} reloaded {count} variables from {}",
+                        environment.name, environment.source
+                    ))),
+                    Err(err) => {
+                        environment.watch = false;
+                        result.push(Err(err));
+                    }
+                },
+                Err(err) => {
+                    environment.watch = false;
+                    result.push(Err(format!(
+                        "Stopped watching {} for environment {}. Error: {err}",
+                        environment.source, environment.name
+                    )));
+                }
+            }
+        }
+        result
+    }
+
+    pub fn select(&mut self, id: Option<String>) {
+        if self.active != id {
+            self.active = id;
+            self.is_changed = true;
+        }
+    }
+
+    /// Add empty environment and return its index
+    pub fn add(&mut self) -> usize {
+        self.items.push(Environment {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: format!("Environment {}", self.items.len() + 1),
+            variables: vec![],
+            secrets: vec![],
+            source: "".into(),
+            watch: false,
+            modified: None,
+        });
+        self.is_changed = true;
+        self.items.len() - 1
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        let environment = self.items.remove(index);
+        if self.active.as_ref() == Some(&environment.id) {
+            self.active = None;
+        }
+        self.is_changed = true;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Environment {
+    /// unique ID of environment UUIDv4
+    pub id: String,
+    pub name: String,
+    pub variables: Vec<Header>,
+    /// Secret variables, values kept in vault and empty while it locked
+    pub secrets: Vec<Header>,
+    /// Path of .env or JSON file variables imported from, empty if none
+    pub source: String,
+    /// Reload variables when source file changes
+    pub watch: bool,
+    /// Modification time of source file on last reload
+    pub modified: Option<SystemTime>,
+}
+
+impl Environment {
+    /// Set variable, secret with same name updated instead of plain one
+    pub fn set(&mut self, name: &str, value: &str) {
+        match self
+            .secrets
+            .iter_mut()
+            .find(|secret| secret.key.trim() == name)
+        {
+            Some(secret) => secret.value = value.to_string(),
+            None => set_variable(&mut self.variables, name, value),
+        }
+    }
+
+    /// Set variables from source file. Variables missing in file are kept.
+    /// Returns amount of variables in file
+    pub fn reload(&mut self) -> Result<usize, String> {
+        let error = |err: std::io::Error| format!("Could not read {}. Error: {err}", self.source);
+        let text = fs::read_to_string(&self.source).map_err(error)?;
+        let modified = fs::metadata(&self.source)
+            .and_then(|meta| meta.modified())
+            .map_err(error)?;
+        let variables = import::parse(&text)
+            .map_err(|err| format!("Could not parse {}. {err}", self.source))?;
+
+        for variable in variables.iter() {
+            self.set(&variable.key, &variable.value);
+        }
+        self.modified = Some(modified);
+        Ok(variables.len())
+    }
+}
+
+impl From<&EnvironmentSettings> for Environment {
+    fn from(value: &EnvironmentSettings) -> Self {
+        Self {
+            id: value.id.clone(),
+            name: value.name.clone(),
+            variables: value
+                .variables
+                .iter()
+                .map(|variable| Header {
+                    key: variable.key.clone(),
+                    value: variable.value.clone(),
+                })
+                .collect(),
+            secrets: value
+                .secrets
+                .iter()
+                .map(|name| Header {
+                    key: name.clone(),
+                    value: "".into(),
+                })
+                .collect(),
+            source: value.source.clone(),
+            watch: value.watch,
+            modified: None,
+        }
+    }
+}
+
+/// Value captured from response with place it stored to
+#[derive(Debug, Clone)]
+pub struct CaptureRecord {
+    /// Name of request received response
+    pub request: String,
+    /// Environment or collection name, None when value was not stored
+    pub target: Option<String>,
+    pub captured: Captured,
+}
+
+/// Update variable value, adding it if missing
+pub fn set_variable(variables: &mut Vec<Header>, name: &str, value: &str) {
+    match variables
+        .iter_mut()
+        .find(|variable| variable.key.trim() == name)
+    {
+        Some(variable) => variable.value = value.to_string(),
+        None => variables.push(Header {
+            key: name.to_string(),
+            value: value.to_string(),
+        }),
+    }
+}
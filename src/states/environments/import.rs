@@ -0,0 +1,239 @@
+/// Variables from .env files and flat JSON objects
+use serde_json::Value;
+
+use crate::states::main_page::generics::Header;
+
+/// Variables of file content: JSON object when starts with `{`, .env otherwise
+pub fn parse(text: &str) -> Result<Vec<Header>, String> {
+    if text.trim_start().starts_with('{') {
+        parse_json(text)
+    } else {
+        parse_dotenv(text)
+    }
+}
+
+/// Flat JSON object. Nested values kept as JSON text
+pub fn parse_json(text: &str) -> Result<Vec<Header>, String> {
+    let json: Value =
+        serde_json::from_str(text).map_err(|err| format!("Invalid JSON. Error: {err}"))?;
+    let object = json
+        .as_object()
+        .ok_or("JSON file must contain object".to_string())?;
+
+    Ok(object
+        .iter()
+        .map(|(key, value)| Header {
+            key: key.clone(),
+            value: match value {
+                Value::String(text) => text.clone(),
+                Value::Null => "".into(),
+                other => other.to_string(),
+            },
+        })
+        .collect())
+}
+
+/// .env file: `KEY=VALUE` lines with optional `export`, `#` comments,
+/// 'literal' and "escaped" values, which could span several lines
+pub fn parse_dotenv(text: &str) -> Result<Vec<Header>, String> {
+    let mut result = vec![];
+    let mut lines = text.lines().enumerate();
+
+    while let Some((i, line)) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("Line {}: expected KEY=VALUE", i + 1));
+        };
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(format!("Line {}: invalid name `{key}`", i + 1));
+        }
+
+        let value = value.trim_start();
+        let value = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                // Closing quote could be on one of next lines
+                let mut quoted = value[1..].to_string();
+                loop {
+                    if let Some(end) = closing_quote(&quoted, quote) {
+                        quoted.truncate(end);
+                        break;
+                    }
+                    match lines.next() {
+                        Some((_, next)) => {
+                            quoted.push('\n');
+                            quoted.push_str(next);
+                        }
+                        None => return Err(format!("Line {}: unclosed quote", i + 1)),
+                    }
+                }
+                if quote == '"' {
+                    unescape(&quoted)
+                } else {
+                    quoted
+                }
+            }
+            // Comment starts after whitespace, `a#b` is value
+            Some('#') => "".into(),
+            _ => match value.find(" #").or(value.find("\t#")) {
+                Some(comment) => value[..comment].trim_end().to_string(),
+                None => value.trim_end().to_string(),
+            },
+        };
+
+        result.push(Header {
+            key: key.to_string(),
+            value,
+        });
+    }
+
+    Ok(result)
+}
+
+/// Position of unescaped closing quote
+fn closing_quote(text: &str, quote: char) -> Option<usize> {
+    let mut is_escaped = false;
+    for (i, char) in text.char_indices() {
+        match char {
+            '\\' if quote == '"' && !is_escaped => is_escaped = true,
+            char if char == quote && !is_escaped => return Some(i),
+            _ => is_escaped = false,
+        }
+    }
+    None
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            result.push(char);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('t') => result.push('\t'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(headers: Vec<Header>) -> Vec<(String, String)> {
+        headers
+            .into_iter()
+            .map(|header| (header.key, header.value))
+            .collect()
+    }
+
+    fn pair(key: &str, value: &str) -> (String, String) {
+        (key.into(), value.into())
+    }
+
+    #[test]
+    fn dotenv() {
+        let text = r#"
+# comment line
+
+export TOKEN=abc
+PLAIN = value with spaces  
+INLINE=value # comment
+HASH=a#b
+EMPTY=
+EMPTY_COMMENT= # nothing
+URL=https://example.com/?a=1&b=2
+SINGLE='it is $literal # not comment \n'
+DOUBLE="line\nnext \"quoted\""
+MULTI="first
+second"
+"#;
+        assert_eq!(
+            pairs(parse_dotenv(text).unwrap()),
+            vec![
+                pair("TOKEN", "abc"),
+                pair("PLAIN", "value with spaces"),
+                pair("INLINE", "value"),
+                pair("HASH", "a#b"),
+                pair("EMPTY", ""),
+                pair("EMPTY_COMMENT", ""),
+                pair("URL", "https://example.com/?a=1&b=2"),
+                pair("SINGLE", "it is $literal # not comment \\n"),
+                pair("DOUBLE", "line\nnext \"quoted\""),
+                pair("MULTI", "first\nsecond"),
+            ]
+        );
+    }
+
+    #[test]
+    fn dotenv_errors() {
+        assert_eq!(
+            parse_dotenv("A=1\nNO_VALUE").unwrap_err(),
+            "Line 2: expected KEY=VALUE"
+        );
+        assert_eq!(
+            parse_dotenv("MY KEY=1").unwrap_err(),
+            "Line 1: invalid name `MY KEY`"
+        );
+        assert_eq!(
+            parse_dotenv("A=\"open\nB=2").unwrap_err(),
+            "Line 1: unclosed quote"
+        );
+    }
+
+    #[test]
+    fn json() {
+        let text = r#"{
+            "host": "example.com",
+            "port": 8080,
+            "debug": true,
+            "nothing": null,
+            "nested": {"a": [1, "b"]},
+            "list": [1, 2]
+        }"#;
+        let mut values = pairs(parse_json(text).unwrap());
+        values.sort();
+        assert_eq!(
+            values,
+            vec![
+                pair("debug", "true"),
+                pair("host", "example.com"),
+                pair("list", "[1,2]"),
+                pair("nested", r#"{"a":[1,"b"]}"#),
+                pair("nothing", ""),
+                pair("port", "8080"),
+            ]
+        );
+    }
+
+    #[test]
+    fn json_errors() {
+        assert_eq!(
+            parse_json("[1, 2]").unwrap_err(),
+            "JSON file must contain object"
+        );
+        assert!(parse_json("{\"a\": ")
+            .unwrap_err()
+            .starts_with("Invalid JSON"));
+    }
+
+    #[test]
+    fn format_detected() {
+        assert_eq!(
+            pairs(parse("  {\"A\": \"1\"}").unwrap()),
+            vec![pair("A", "1")]
+        );
+        assert_eq!(pairs(parse("A=1").unwrap()), vec![pair("A", "1")]);
+    }
+}
@@ -16,6 +16,7 @@ pub mod collection;
 pub mod entity;
 pub mod filtered_entity;
 pub mod generics;
+pub mod jwt;
 pub mod request;
 pub mod response;
 pub mod selected_entity;
@@ -1,6 +1,12 @@
 use crate::{
     settings::main_settings::entity::collection_settings::CollectionSettings,
-    states::main_page::request::Request,
+    states::main_page::{
+        generics::Header,
+        request::{
+            request_data::{AuthType, RequestAuth, Scripts},
+            Request,
+        },
+    },
 };
 
 /// Collection Entity representation
@@ -18,6 +24,10 @@ pub struct Collection {
     pub requests: Vec<Request>,
     /// state if collection visualy folded or not
     pub is_folded: bool,
+    /// new header data - for UI, to add new neader
+    pub new_header: Header,
+    /// new variable data - for UI, to add new variable
+    pub new_variable: Header,
 }
 
 /// From Settigns -> State
@@ -37,6 +47,8 @@ impl From<&CollectionSettings> for Collection {
             draft,
             requests,
             is_folded: true,
+            new_header: Header::default(),
+            new_variable: Header::default(),
         }
     }
 }
@@ -50,6 +62,8 @@ impl Collection {
             draft: CollectionData::default(),
             requests: vec![],
             is_folded: true,
+            new_header: Header::default(),
+            new_variable: Header::default(),
         }
     }
     /// Drop change mark and transfer Draft to Original Data.
@@ -91,14 +105,50 @@ impl Collection {
 pub struct CollectionData {
     pub name: String,
     pub description: String,
+    /// Auth for requests, wich inherit it
+    pub auth: RequestAuth,
+    /// Variables for requests, override ones of active environment
+    pub variables: Vec<Header>,
+    /// Headers merged into every request, request headers win
+    pub headers: Vec<Header>,
+    /// Scripts run around send of every request, before request ones
+    pub scripts: Scripts,
 }
 
 /// From Settings -> State
 impl From<&CollectionSettings> for CollectionData {
     fn from(value: &CollectionSettings) -> Self {
+        let mut auth = RequestAuth::from(&value.auth);
+        // Collection is top level, nothing to inherit
+        if auth.auth_type == AuthType::INHERIT {
+            auth.auth_type = AuthType::NONE;
+        }
+
         Self {
             name: value.name.clone(),
             description: value.description.clone(),
+            auth,
+            variables: value
+                .variables
+                .iter()
+                .map(|variable| Header {
+                    key: variable.key.clone(),
+                    value: variable.value.clone(),
+                })
+                .collect(),
+            headers: value
+                .headers
+                .iter()
+                .map(|header| Header {
+                    key: header.key.clone(),
+                    // Remove redundant quotes from only string-like values
+                    value: match header.value.as_str() {
+                        Some(val) => val.to_string(),
+                        None => header.value.to_string(),
+                    },
+                })
+                .collect(),
+            scripts: Scripts::from(&value.scripts),
         }
     }
 }
@@ -108,6 +158,13 @@ impl CollectionData {
         Self {
             name: "New Collection".into(),
             description: "".into(),
+            auth: RequestAuth {
+                auth_type: AuthType::NONE,
+                ..RequestAuth::default()
+            },
+            variables: vec![],
+            headers: vec![],
+            scripts: Scripts::default(),
         }
     }
     /// Copy from other Self.
@@ -115,5 +172,38 @@ impl CollectionData {
     pub fn copy_from_other(&mut self, other_collection: &Self) {
         self.name = other_collection.name.clone();
         self.description = other_collection.description.clone();
+        self.auth = other_collection.auth.clone();
+        self.variables = other_collection.variables.clone();
+        self.headers = other_collection.headers.clone();
+        self.scripts = other_collection.scripts.clone();
+    }
+
+    /// Collection variables first, so they override environment ones
+    pub fn merge_variables(&self, environment: Vec<Header>) -> Vec<Header> {
+        let mut variables: Vec<Header> = self
+            .variables
+            .iter()
+            .filter(|variable| !variable.key.trim().is_empty())
+            .cloned()
+            .collect();
+        variables.extend(environment);
+        variables
+    }
+
+    /// Collection headers, not set in request, followed by request headers
+    pub fn merge_headers(&self, headers: &[Header]) -> Vec<Header> {
+        let mut merged: Vec<Header> = self
+            .headers
+            .iter()
+            .filter(|header| {
+                !header.key.trim().is_empty()
+                    && !headers
+                        .iter()
+                        .any(|own| own.key.trim().eq_ignore_ascii_case(header.key.trim()))
+            })
+            .cloned()
+            .collect();
+        merged.extend(headers.iter().cloned());
+        merged
     }
 }
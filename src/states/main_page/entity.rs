@@ -16,10 +16,10 @@ impl From<&SettingsEntity> for Entity {
     fn from(value: &SettingsEntity) -> Self {
         match value {
             SettingsEntity::COLLECTION(collection_settings) => {
-                Self::COLLECTION(Collection::from(collection_settings))
+                Self::COLLECTION(Collection::from(collection_settings.as_ref()))
             }
             SettingsEntity::REQUEST(request_settings) => {
-                Self::REQUEST(Request::from(request_settings))
+                Self::REQUEST(Request::from(request_settings.as_ref()))
             }
         }
     }
@@ -1,21 +1,36 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    fmt::Display,
+    sync::{Arc, Mutex},
+};
 
 use futures::executor::block_on;
+use prost_reflect::{DescriptorPool, DynamicMessage};
 use serde_json::Value;
 
 use crate::{
-    executor::{Executor, State},
+    executor::{
+        graphql::{self, GraphQlSchema},
+        grpc, variables, Executor, State,
+    },
     settings::{
         main_settings::entity::request_settings::{
-            request_setup_settings::RequestSetupSettings, RequestSettings,
+            protocol_settings::Protocol, request_setup_settings::RequestSetupSettings,
+            RequestSettings,
         },
-        HttpVersionSetting, RequestHttpSetupSettings, RequestWsSetupSettings,
+        HttpVersionSetting, MqttCommandSetting, MqttVersionSetting, RequestGrpcSetupSettings,
+        RequestHttpSetupSettings, RequestMqttSetupSettings, RequestUdpSetupSettings,
+        RequestWsSetupSettings, SocketIoSetupSettings, StompCommandSetting, StompSetupSettings,
+        UdpPayloadFormatSetting, WsModeSetting,
     },
     states::{
         main_page::{
+            collection::CollectionData,
             generics::{CountedText, Header},
-            request::request_data::{BodyFromData, FormFieldType, RequestData},
-            response::Response,
+            jwt::Jwt,
+            request::request_data::{
+                BodyFromData, BodyMode, FormFieldType, GraphQlBody, JsonRpcBody, RequestData,
+            },
+            response::{Response, ResponsesFilter},
         },
         Events,
     },
@@ -46,7 +61,16 @@ pub struct Request {
     pub visible_details: RequestDetails,
     /// headers currently visible on screen
     pub visible_headers: RequestHeaders,
-    pub visible_body: RequestBodyDetails,
+    /// GraphQL schema from introspection, for autocomplete.
+    /// Fetched on demand, dont store in settings
+    pub graphql_schema: Arc<Mutex<Option<GraphQlSchema>>>,
+    /// gRPC services descriptors, for services and methods selection.
+    /// Loaded on demand, dont store in settings
+    pub grpc_pool: Arc<Mutex<Option<DescriptorPool>>>,
+    /// responses currently visible on screen
+    pub visible_responses: ResponsesFilter,
+    /// Tokens found in headers, with entered verification keys
+    pub header_jwts: Vec<Jwt>,
 }
 
 /// From Settings -> State
@@ -60,7 +84,11 @@ impl From<&RequestSettings> for Request {
 
         let visible_details = if draft.protocot_is_http() {
             RequestDetails::QueryParams
-        } else if draft.protocot_is_ws() {
+        } else if draft.protocot_is_ws()
+            || draft.protocot_is_grpc()
+            || draft.protocot_is_mqtt()
+            || draft.protocot_is_udp()
+        {
             RequestDetails::Message
         } else {
             RequestDetails::Header
@@ -78,7 +106,10 @@ impl From<&RequestSettings> for Request {
             },
             visible_details,
             visible_headers: RequestHeaders::Custom,
-            visible_body: RequestBodyDetails::Raw,
+            graphql_schema: Arc::new(Mutex::new(None)),
+            grpc_pool: Arc::new(Mutex::new(None)),
+            visible_responses: ResponsesFilter::All,
+            header_jwts: vec![],
             new_body_form_field: BodyFromData {
                 key: "".into(),
                 value: "".into(),
@@ -102,7 +133,10 @@ impl Request {
             executor,
             visible_details: RequestDetails::Header,
             visible_headers: RequestHeaders::Custom,
-            visible_body: RequestBodyDetails::Raw,
+            graphql_schema: Arc::new(Mutex::new(None)),
+            grpc_pool: Arc::new(Mutex::new(None)),
+            visible_responses: ResponsesFilter::All,
+            header_jwts: vec![],
             new_body_form_field: BodyFromData {
                 key: "".into(),
                 value: "".into(),
@@ -111,25 +145,185 @@ impl Request {
         }
     }
     /// Fire Executor to make requests
-    pub fn go(&mut self, events: Arc<Mutex<Events>>, delay_send_message: bool) {
-        // Clearing body parts wich does not selected currently
+    /// parent - collection of request, for inherited auth and default headers
+    /// variables - variables of collection and active environment
+    pub fn go(
+        &mut self,
+        events: Arc<Mutex<Events>>,
+        delay_send_message: bool,
+        parent: Option<&CollectionData>,
+        variables: &[Header],
+    ) {
+        // Clearing body parts wich does not selected for send.
+        // WS keeps all parts, its mode picks one to send
         let mut request_data = self.draft.clone();
-        match self.visible_body {
-            RequestBodyDetails::Raw => {
+        request_data.auth = request_data
+            .auth
+            .resolve(parent.map(|collection| &collection.auth));
+        if let Some(collection) = parent {
+            request_data.headers = collection.merge_headers(&request_data.headers);
+        }
+        match request_data.body.mode {
+            _ if request_data.protocot_is_ws() => {}
+            BodyMode::RAW => {
+                request_data.body.binary_path = "".into();
+                request_data.body.form_data = vec![];
+                request_data.body.graphql = GraphQlBody::default();
+                request_data.body.json_rpc = JsonRpcBody::default();
+            }
+            BodyMode::FORMDATA => {
                 request_data.body.binary_path = "".into();
+                request_data.body.raw = CountedText::default();
+                request_data.body.graphql = GraphQlBody::default();
+                request_data.body.json_rpc = JsonRpcBody::default();
+            }
+            BodyMode::BINARY => {
                 request_data.body.form_data = vec![];
+                request_data.body.raw = CountedText::default();
+                request_data.body.graphql = GraphQlBody::default();
+                request_data.body.json_rpc = JsonRpcBody::default();
             }
-            RequestBodyDetails::FormData => {
+            BodyMode::GRAPHQL => {
                 request_data.body.binary_path = "".into();
+                request_data.body.form_data = vec![];
                 request_data.body.raw = CountedText::default();
+                request_data.body.json_rpc = JsonRpcBody::default();
             }
-            RequestBodyDetails::Binary => {
+            BodyMode::JSONRPC => {
+                request_data.body.binary_path = "".into();
                 request_data.body.form_data = vec![];
                 request_data.body.raw = CountedText::default();
+                request_data.body.graphql = GraphQlBody::default();
+            }
+        }
+        let mut scripts = vec![];
+        if let Some(collection) = parent {
+            scripts.push(&collection.scripts);
+        }
+        scripts.push(&self.draft.scripts);
+
+        self.executor.execute(
+            &request_data,
+            delay_send_message,
+            variables,
+            &scripts,
+            events,
+        );
+    }
+
+    /// Fetch GraphQL schema by introspection in background.
+    /// WS uri requested over HTTP with same security.
+    /// Headers, auth and variables applied as on send
+    pub fn fetch_graphql_schema(
+        &self,
+        events: Arc<Mutex<Events>>,
+        parent: Option<&CollectionData>,
+        variables: &[Header],
+    ) {
+        let protocol = match self.draft.protocol {
+            Protocol::HTTP | Protocol::WS | Protocol::GRPC | Protocol::MQTT | Protocol::UDP => {
+                "http"
+            }
+            Protocol::HTTPS | Protocol::WSS | Protocol::GRPCS | Protocol::MQTTS => "https",
+        };
+        let mut request_data = self.draft.clone();
+        request_data.auth = request_data
+            .auth
+            .resolve(parent.map(|collection| &collection.auth));
+        if let Some(collection) = parent {
+            request_data.headers = collection.merge_headers(&request_data.headers);
+        }
+        match variables::resolve(&mut request_data, variables) {
+            Ok(unresolved) if !unresolved.is_empty() => {
+                events.lock().unwrap().event_warning(&format!(
+                    "GraphQL: unresolved variables: {}. Sent as is",
+                    unresolved.join(", ")
+                ));
+            }
+            Ok(_) => {}
+            Err(err) => {
+                events.lock().unwrap().event_error(&err);
+                return;
+            }
+        }
+        let uri = format!("{protocol}://{}", request_data.uri);
+        let headers = request_data.headers;
+        let auth = request_data.auth;
+        let schema = Arc::clone(&self.graphql_schema);
+
+        events
+            .lock()
+            .unwrap()
+            .event_info(&"GraphQL: fetching schema...".into());
+
+        tokio::spawn(async move {
+            match graphql::fetch_schema(uri, headers, auth, Arc::clone(&events)).await {
+                Ok(val) => {
+                    events.lock().unwrap().event_info(&format!(
+                        "GraphQL: schema fetched. Types: {}",
+                        val.types.len()
+                    ));
+                    *schema.lock().unwrap() = Some(val);
+                }
+                Err(err) => {
+                    events.lock().unwrap().event_error(&format!("Error: {err}"));
+                }
+            }
+        });
+    }
+
+    /// Load gRPC services from .proto files or server reflection in background
+    pub fn load_grpc_services(&self, events: Arc<Mutex<Events>>) {
+        let setup = match self.draft.setup.grpc() {
+            Some(val) => val.clone(),
+            None => return,
+        };
+        let protocol = self.draft.protocol.clone();
+        let uri = self.draft.uri.clone();
+        let pool = Arc::clone(&self.grpc_pool);
+
+        events
+            .lock()
+            .unwrap()
+            .event_info(&"gRPC: loading services...".into());
+
+        tokio::spawn(async move {
+            match grpc::load_pool(&protocol, &uri, &setup).await {
+                Ok(val) => {
+                    events.lock().unwrap().event_info(&format!(
+                        "gRPC: services loaded. Services: {}",
+                        val.services().len()
+                    ));
+                    *pool.lock().unwrap() = Some(val);
+                }
+                Err(err) => {
+                    events.lock().unwrap().event_error(&format!("Error: {err}"));
+                }
+            }
+        });
+    }
+
+    /// Replace message with JSON of selected gRPC method input, all fields with defaults
+    pub fn grpc_message_template(&mut self, events: Arc<Mutex<Events>>) {
+        let setup = match self.draft.setup.grpc() {
+            Some(val) => val,
+            None => return,
+        };
+        let template = match self.grpc_pool.lock().unwrap().as_ref() {
+            Some(pool) => grpc::find_method(pool, &setup.service, &setup.method)
+                .map(|method| grpc::message_to_json(&DynamicMessage::new(method.input()))),
+            None => Err("gRPC: load services in Setup first".into()),
+        };
+
+        match template {
+            Ok(template) => {
+                self.draft.message.set(template);
+                self.is_changed = true;
+            }
+            Err(err) => {
+                events.lock().unwrap().event_error(&format!("Error: {err}"));
             }
         }
-        self.executor
-            .execute(&request_data, delay_send_message, events);
     }
 
     /// Stop Executor, also drop execution in progress
@@ -236,18 +430,14 @@ impl Request {
 #[derive(Debug, Clone, PartialEq)]
 pub enum RequestDetails {
     Header,
+    Auth,
     Body,
     QueryParams,
     Message,
     Setup,
-}
-
-/// Request details currently shown on UI
-#[derive(Debug, Clone, PartialEq)]
-pub enum RequestBodyDetails {
-    Raw,
-    FormData,
-    Binary,
+    Captures,
+    Tests,
+    Scripts,
 }
 
 /// Request details currently shown on UI
@@ -305,40 +495,97 @@ impl HttpVersion {
 pub enum RequestSetup {
     HTTP(RequestHttpSetup),
     WS(RequestWsSetup),
+    GRPC(RequestGrpcSetup),
+    MQTT(RequestMqttSetup),
+    UDP(RequestUdpSetup),
 }
 
 impl RequestSetup {
     pub fn http(&self) -> Option<&RequestHttpSetup> {
         match self {
             RequestSetup::HTTP(request_http_setup) => Some(request_http_setup),
-            RequestSetup::WS(_) => None,
+            _ => None,
         }
     }
 
     pub fn ws(&self) -> Option<&RequestWsSetup> {
         match self {
-            RequestSetup::HTTP(_) => None,
             RequestSetup::WS(request_ws_setup) => Some(request_ws_setup),
+            _ => None,
         }
     }
 
     pub fn http_mut(&mut self) -> Option<&mut RequestHttpSetup> {
         match self {
             RequestSetup::HTTP(request_http_setup) => Some(request_http_setup),
-            RequestSetup::WS(_) => None,
+            _ => None,
         }
     }
 
     pub fn ws_mut(&mut self) -> Option<&mut RequestWsSetup> {
         match self {
-            RequestSetup::HTTP(_) => None,
             RequestSetup::WS(request_ws_setup) => Some(request_ws_setup),
+            _ => None,
+        }
+    }
+
+    pub fn grpc(&self) -> Option<&RequestGrpcSetup> {
+        match self {
+            RequestSetup::GRPC(request_grpc_setup) => Some(request_grpc_setup),
+            _ => None,
+        }
+    }
+
+    pub fn grpc_mut(&mut self) -> Option<&mut RequestGrpcSetup> {
+        match self {
+            RequestSetup::GRPC(request_grpc_setup) => Some(request_grpc_setup),
+            _ => None,
+        }
+    }
+
+    pub fn mqtt(&self) -> Option<&RequestMqttSetup> {
+        match self {
+            RequestSetup::MQTT(request_mqtt_setup) => Some(request_mqtt_setup),
+            _ => None,
+        }
+    }
+
+    pub fn mqtt_mut(&mut self) -> Option<&mut RequestMqttSetup> {
+        match self {
+            RequestSetup::MQTT(request_mqtt_setup) => Some(request_mqtt_setup),
+            _ => None,
+        }
+    }
+
+    pub fn udp(&self) -> Option<&RequestUdpSetup> {
+        match self {
+            RequestSetup::UDP(request_udp_setup) => Some(request_udp_setup),
+            _ => None,
+        }
+    }
+
+    pub fn udp_mut(&mut self) -> Option<&mut RequestUdpSetup> {
+        match self {
+            RequestSetup::UDP(request_udp_setup) => Some(request_udp_setup),
+            _ => None,
         }
     }
 
     pub fn default_ws() -> Self {
         Self::WS(RequestWsSetup::default())
     }
+
+    pub fn default_grpc() -> Self {
+        Self::GRPC(RequestGrpcSetup::default())
+    }
+
+    pub fn default_mqtt() -> Self {
+        Self::MQTT(RequestMqttSetup::default())
+    }
+
+    pub fn default_udp() -> Self {
+        Self::UDP(RequestUdpSetup::default())
+    }
 }
 
 impl From<&RequestSetupSettings> for RequestSetup {
@@ -350,6 +597,15 @@ impl From<&RequestSetupSettings> for RequestSetup {
             RequestSetupSettings::WS(request_ws_setup_settings) => {
                 Self::WS(RequestWsSetup::from(request_ws_setup_settings))
             }
+            RequestSetupSettings::GRPC(request_grpc_setup_settings) => {
+                Self::GRPC(RequestGrpcSetup::from(request_grpc_setup_settings))
+            }
+            RequestSetupSettings::MQTT(request_mqtt_setup_settings) => {
+                Self::MQTT(RequestMqttSetup::from(request_mqtt_setup_settings))
+            }
+            RequestSetupSettings::UDP(request_udp_setup_settings) => {
+                Self::UDP(RequestUdpSetup::from(request_udp_setup_settings))
+            }
         }
     }
 }
@@ -365,6 +621,15 @@ impl Default for RequestSetup {
 pub struct RequestWsSetup {
     pub reconnection_timeout: String,
     pub reconnection_attempts: String,
+    /// Code sent in Close frame on termination
+    pub close_code: String,
+    /// Reason sent in Close frame on termination
+    pub close_reason: String,
+    /// Protocol working on top of WS session
+    pub mode: WsMode,
+    pub socket_io: SocketIoSetup,
+    pub stomp: StompSetup,
+    pub graphql: GraphQlWsSetup,
 }
 
 impl From<&RequestWsSetupSettings> for RequestWsSetup {
@@ -372,6 +637,20 @@ impl From<&RequestWsSetupSettings> for RequestWsSetup {
         Self {
             reconnection_timeout: value.reconnection_timeout.to_string(),
             reconnection_attempts: value.reconnection_attempts.to_string(),
+            close_code: value.close_code.to_string(),
+            close_reason: value.close_reason.clone(),
+            mode: match value.mode {
+                WsModeSetting::RAW => WsMode::RAW,
+                WsModeSetting::SOCKETIO => WsMode::SOCKETIO,
+                WsModeSetting::STOMP => WsMode::STOMP,
+                WsModeSetting::GRAPHQL => WsMode::GRAPHQL,
+                WsModeSetting::JSONRPC => WsMode::JSONRPC,
+            },
+            socket_io: SocketIoSetup::from(&value.socket_io),
+            stomp: StompSetup::from(&value.stomp),
+            graphql: GraphQlWsSetup {
+                connection_params: value.graphql.connection_params.clone(),
+            },
         }
     }
 }
@@ -384,6 +663,41 @@ impl RequestWsSetup {
     pub fn reconnection_attempts(&self) -> usize {
         self.reconnection_attempts.parse::<usize>().unwrap_or(9)
     }
+
+    /// Code to send, Normal Closure when typed one could not be sent
+    pub fn close_code(&self) -> u16 {
+        self.close_code
+            .parse::<u16>()
+            .ok()
+            .filter(|code| is_sendable_close_code(*code))
+            .unwrap_or(1000)
+    }
+
+    /// Reason to send, cut to fit Close frame
+    pub fn close_reason(&self) -> String {
+        truncate_bytes(&self.close_reason, MAX_CLOSE_REASON_BYTES).to_string()
+    }
+}
+
+/// Close frame payload limited to 125 bytes, 2 of them taken by code
+pub const MAX_CLOSE_REASON_BYTES: usize = 123;
+
+/// Code allowed in Close frame: defined by RFC 6455, except reserved
+/// 1004, 1005, 1006 and 1015, or application one
+pub fn is_sendable_close_code(code: u16) -> bool {
+    matches!(code, 1000..=1003 | 1007..=1014 | 3000..=4999)
+}
+
+/// Longest prefix of text not longer than limit in bytes, on char boundary
+pub fn truncate_bytes(text: &str, limit: usize) -> &str {
+    if text.len() <= limit {
+        return text;
+    }
+    let mut end = limit;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
 }
 
 impl Default for RequestWsSetup {
@@ -391,6 +705,310 @@ impl Default for RequestWsSetup {
         Self {
             reconnection_timeout: "5000".into(),
             reconnection_attempts: "3".into(),
+            close_code: "1000".into(),
+            close_reason: "".into(),
+            mode: WsMode::RAW,
+            socket_io: SocketIoSetup::default(),
+            stomp: StompSetup::default(),
+            graphql: GraphQlWsSetup::default(),
+        }
+    }
+}
+
+/// Protocol working on top of WS session
+#[derive(Debug, Clone, PartialEq)]
+pub enum WsMode {
+    RAW,
+    SOCKETIO,
+    STOMP,
+    GRAPHQL,
+    JSONRPC,
+}
+
+impl Display for WsMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WsMode::RAW => write!(f, "RAW"),
+            WsMode::SOCKETIO => write!(f, "SOCKET.IO"),
+            WsMode::STOMP => write!(f, "STOMP"),
+            WsMode::GRAPHQL => write!(f, "GRAPHQL"),
+            WsMode::JSONRPC => write!(f, "JSON-RPC"),
+        }
+    }
+}
+
+/// Settings of Socket.IO mode
+#[derive(Debug, Clone)]
+pub struct SocketIoSetup {
+    /// Namespace to connect after handshake
+    pub namespace: String,
+    /// Optional JSON auth payload sent with namespace connect
+    pub auth: String,
+    /// Event name for outgoing messages
+    pub event: String,
+}
+
+impl From<&SocketIoSetupSettings> for SocketIoSetup {
+    fn from(value: &SocketIoSetupSettings) -> Self {
+        Self {
+            namespace: value.namespace.clone(),
+            auth: value.auth.clone(),
+            event: value.event.clone(),
+        }
+    }
+}
+
+impl Default for SocketIoSetup {
+    fn default() -> Self {
+        Self {
+            namespace: "/".into(),
+            auth: "".into(),
+            event: "message".into(),
+        }
+    }
+}
+
+/// Settings of GraphQL subscriptions mode
+#[derive(Debug, Clone, Default)]
+pub struct GraphQlWsSetup {
+    /// Optional JSON payload of connection_init message
+    pub connection_params: String,
+}
+
+/// Settings of STOMP mode
+#[derive(Debug, Clone)]
+pub struct StompSetup {
+    /// Virtual host for CONNECT frame
+    pub host: String,
+    pub login: String,
+    pub passcode: String,
+    /// Frame sent with message
+    pub command: StompCommand,
+    pub destination: String,
+    /// Extra frame headers, `key:value` per line
+    pub headers: String,
+}
+
+impl From<&StompSetupSettings> for StompSetup {
+    fn from(value: &StompSetupSettings) -> Self {
+        Self {
+            host: value.host.clone(),
+            login: value.login.clone(),
+            passcode: value.passcode.clone(),
+            command: match value.command {
+                StompCommandSetting::SEND => StompCommand::SEND,
+                StompCommandSetting::SUBSCRIBE => StompCommand::SUBSCRIBE,
+                StompCommandSetting::UNSUBSCRIBE => StompCommand::UNSUBSCRIBE,
+            },
+            destination: value.destination.clone(),
+            headers: value.headers.clone(),
+        }
+    }
+}
+
+impl Default for StompSetup {
+    fn default() -> Self {
+        Self {
+            host: "".into(),
+            login: "".into(),
+            passcode: "".into(),
+            command: StompCommand::SEND,
+            destination: "".into(),
+            headers: "".into(),
+        }
+    }
+}
+
+/// Client frames user could send in STOMP session
+#[derive(Debug, Clone, PartialEq)]
+pub enum StompCommand {
+    SEND,
+    SUBSCRIBE,
+    UNSUBSCRIBE,
+}
+
+impl Display for StompCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StompCommand::SEND => write!(f, "SEND"),
+            StompCommand::SUBSCRIBE => write!(f, "SUBSCRIBE"),
+            StompCommand::UNSUBSCRIBE => write!(f, "UNSUBSCRIBE"),
+        }
+    }
+}
+
+/// Settings to make gRPC reqeust
+#[derive(Debug, Clone, Default)]
+pub struct RequestGrpcSetup {
+    /// Paths of .proto files with services
+    pub proto_files: Vec<String>,
+    /// Extra import paths for .proto files, `;` separated
+    pub import_paths: String,
+    /// Get services from server reflection instead of .proto files
+    pub use_reflection: bool,
+    /// Full service name, with package
+    pub service: String,
+    pub method: String,
+}
+
+impl From<&RequestGrpcSetupSettings> for RequestGrpcSetup {
+    fn from(value: &RequestGrpcSetupSettings) -> Self {
+        Self {
+            proto_files: value.proto_files.clone(),
+            import_paths: value.import_paths.clone(),
+            use_reflection: value.use_reflection,
+            service: value.service.clone(),
+            method: value.method.clone(),
+        }
+    }
+}
+
+impl RequestGrpcSetup {
+    /// Import paths without empty parts
+    pub fn import_paths(&self) -> Vec<String> {
+        self.import_paths
+            .split(';')
+            .map(|val| val.trim().to_string())
+            .filter(|val| !val.is_empty())
+            .collect()
+    }
+}
+
+/// Settings to make MQTT reqeust
+#[derive(Debug, Clone)]
+pub struct RequestMqttSetup {
+    /// Empty id - generated on connect
+    pub client_id: String,
+    pub username: String,
+    pub password: String,
+    /// Keep alive interval, seconds
+    pub keep_alive: String,
+    /// Clean session for 3.1.1, clean start for 5
+    pub clean_session: bool,
+    pub version: MqttVersion,
+    /// Packet sent with message
+    pub command: MqttCommand,
+    /// Topic to publish or topic filter to (un)subscribe
+    pub topic: String,
+    pub qos: u8,
+    pub retain: bool,
+}
+
+impl From<&RequestMqttSetupSettings> for RequestMqttSetup {
+    fn from(value: &RequestMqttSetupSettings) -> Self {
+        Self {
+            client_id: value.client_id.clone(),
+            username: value.username.clone(),
+            password: value.password.clone(),
+            keep_alive: value.keep_alive.to_string(),
+            clean_session: value.clean_session,
+            version: match value.version {
+                MqttVersionSetting::V311 => MqttVersion::V311,
+                MqttVersionSetting::V5 => MqttVersion::V5,
+            },
+            command: match value.command {
+                MqttCommandSetting::PUBLISH => MqttCommand::PUBLISH,
+                MqttCommandSetting::SUBSCRIBE => MqttCommand::SUBSCRIBE,
+                MqttCommandSetting::UNSUBSCRIBE => MqttCommand::UNSUBSCRIBE,
+            },
+            topic: value.topic.clone(),
+            qos: value.qos.min(2),
+            retain: value.retain,
+        }
+    }
+}
+
+impl Default for RequestMqttSetup {
+    fn default() -> Self {
+        Self::from(&RequestMqttSetupSettings::default())
+    }
+}
+
+impl RequestMqttSetup {
+    pub fn keep_alive(&self) -> u64 {
+        self.keep_alive.parse::<u64>().unwrap_or(60)
+    }
+}
+
+/// MQTT protocol version
+#[derive(Debug, Clone, PartialEq)]
+pub enum MqttVersion {
+    V311,
+    V5,
+}
+
+impl Display for MqttVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MqttVersion::V311 => write!(f, "3.1.1"),
+            MqttVersion::V5 => write!(f, "5"),
+        }
+    }
+}
+
+/// Packets user could send in MQTT session
+#[derive(Debug, Clone, PartialEq)]
+pub enum MqttCommand {
+    PUBLISH,
+    SUBSCRIBE,
+    UNSUBSCRIBE,
+}
+
+impl Display for MqttCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MqttCommand::PUBLISH => write!(f, "PUBLISH"),
+            MqttCommand::SUBSCRIBE => write!(f, "SUBSCRIBE"),
+            MqttCommand::UNSUBSCRIBE => write!(f, "UNSUBSCRIBE"),
+        }
+    }
+}
+
+/// Settings to make UDP reqeust
+#[derive(Debug, Clone)]
+pub struct RequestUdpSetup {
+    /// How message editor content turned into datagram and replies shown
+    pub payload_format: UdpPayloadFormat,
+    /// Time to wait replies after datagram sent, ms
+    pub reply_window: String,
+}
+
+impl From<&RequestUdpSetupSettings> for RequestUdpSetup {
+    fn from(value: &RequestUdpSetupSettings) -> Self {
+        Self {
+            payload_format: match value.payload_format {
+                UdpPayloadFormatSetting::TEXT => UdpPayloadFormat::TEXT,
+                UdpPayloadFormatSetting::HEX => UdpPayloadFormat::HEX,
+            },
+            reply_window: value.reply_window.to_string(),
+        }
+    }
+}
+
+impl Default for RequestUdpSetup {
+    fn default() -> Self {
+        Self::from(&RequestUdpSetupSettings::default())
+    }
+}
+
+impl RequestUdpSetup {
+    pub fn reply_window(&self) -> u64 {
+        self.reply_window.parse::<u64>().unwrap_or(2000)
+    }
+}
+
+/// UDP datagram payload format
+#[derive(Debug, Clone, PartialEq)]
+pub enum UdpPayloadFormat {
+    TEXT,
+    HEX,
+}
+
+impl Display for UdpPayloadFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UdpPayloadFormat::TEXT => write!(f, "Text"),
+            UdpPayloadFormat::HEX => write!(f, "Hex"),
         }
     }
 }
@@ -430,3 +1048,29 @@ impl From<&RequestHttpSetupSettings> for RequestHttpSetup {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sendable_close_codes() {
+        for code in [1000, 1001, 1003, 1007, 1014, 3000, 4999] {
+            assert!(is_sendable_close_code(code), "{code}");
+        }
+        for code in [0, 999, 1004, 1005, 1006, 1015, 2000, 2999, 5000] {
+            assert!(!is_sendable_close_code(code), "{code}");
+        }
+    }
+
+    #[test]
+    fn truncate_on_char_boundary() {
+        assert_eq!(truncate_bytes("abc", 5), "abc");
+        assert_eq!(truncate_bytes("abcdef", 3), "abc");
+        // `é` takes 2 bytes, so it is not cut in half
+        assert_eq!(truncate_bytes("aéb", 2), "a");
+        assert_eq!(truncate_bytes("aéb", 3), "aé");
+        let reason = "ы".repeat(100);
+        assert!(truncate_bytes(&reason, MAX_CLOSE_REASON_BYTES).len() <= MAX_CLOSE_REASON_BYTES);
+    }
+}
@@ -1,10 +1,24 @@
 use std::fmt::Display;
 
+use base64::{engine::general_purpose::STANDARD, Engine};
+
 use crate::{
+    executor::oauth2,
     settings::main_settings::entity::request_settings::{
-        body_settings::{FormFieldTypeSettings, RequestBodySettigns},
+        assertion_settings::{AssertionKindSettings, AssertionSettings},
+        auth_settings::{
+            ApiKeyLocationSettings, AuthTypeSettings, AwsSigV4Settings, HmacAlgorithmSettings,
+            HmacEncodingSettings, HmacSettings, HmacTimestampSettings, OAuth2GrantSettings,
+            OAuth2Settings, RequestAuthSettings,
+        },
+        body_settings::{
+            BodyModeSettings, FormFieldTypeSettings, GraphQlBodySettings, JsonRpcBodySettings,
+            RequestBodySettigns,
+        },
+        capture_settings::{CaptureScopeSettings, CaptureSettings, CaptureSourceSettings},
         method_settigns::Method,
         protocol_settings::Protocol,
+        scripts_settings::ScriptsSettings,
         RequestSettings,
     },
     states::main_page::{
@@ -28,6 +42,12 @@ pub struct RequestData {
     pub query_params: Vec<Header>,
     /// Settings for request
     pub setup: RequestSetup,
+    pub auth: RequestAuth,
+    /// Response values stored into variables
+    pub captures: Vec<Capture>,
+    /// Checks of response
+    pub assertions: Vec<Assertion>,
+    pub scripts: Scripts,
 }
 
 /// From Settigns -> State
@@ -66,6 +86,10 @@ impl From<&RequestSettings> for RequestData {
             query_params: vec![],
             message,
             setup: RequestSetup::from(&value.setup),
+            auth: RequestAuth::from(&value.auth),
+            captures: value.captures.iter().map(Capture::from).collect(),
+            assertions: value.assertions.iter().map(Assertion::from).collect(),
+            scripts: Scripts::from(&value.scripts),
         };
         data.parse_query_params();
         data
@@ -84,6 +108,10 @@ impl RequestData {
             query_params: vec![],
             message: CountedText::default(),
             setup: RequestSetup::default(),
+            auth: RequestAuth::default(),
+            captures: vec![],
+            assertions: vec![],
+            scripts: Scripts::default(),
         }
     }
     /// Copy from other Self.
@@ -96,6 +124,10 @@ impl RequestData {
         self.body = other_request.body.clone();
         self.message = other_request.message.clone();
         self.setup = other_request.setup.clone();
+        self.auth = other_request.auth.clone();
+        self.captures = other_request.captures.clone();
+        self.assertions = other_request.assertions.clone();
+        self.scripts = other_request.scripts.clone();
 
         self.headers = other_request
             .headers
@@ -115,22 +147,38 @@ impl RequestData {
 
     // Parsing protocol of URL and trim it
     fn parse_url_protocol(&mut self) {
+        // Lowercased only to detect scheme, so path and {{variables}} keep case
         let url = self.uri.to_lowercase();
         let mut split_url = if url.starts_with("http:") {
             self.protocol = Protocol::HTTP;
-            url.split_at(5).1.to_string()
+            self.uri.split_at(5).1.to_string()
         } else if url.starts_with("https:") {
             self.protocol = Protocol::HTTPS;
-            url.split_at(6).1.to_string()
+            self.uri.split_at(6).1.to_string()
         } else if url.starts_with("ws:") {
             self.protocol = Protocol::WS;
-            url.split_at(3).1.to_string()
+            self.uri.split_at(3).1.to_string()
         } else if url.starts_with("wss:") {
             self.protocol = Protocol::WSS;
-            url.split_at(4).1.to_string()
+            self.uri.split_at(4).1.to_string()
+        } else if url.starts_with("grpc:") {
+            self.protocol = Protocol::GRPC;
+            self.uri.split_at(5).1.to_string()
+        } else if url.starts_with("grpcs:") {
+            self.protocol = Protocol::GRPCS;
+            self.uri.split_at(6).1.to_string()
+        } else if url.starts_with("mqtt:") {
+            self.protocol = Protocol::MQTT;
+            self.uri.split_at(5).1.to_string()
+        } else if url.starts_with("mqtts:") {
+            self.protocol = Protocol::MQTTS;
+            self.uri.split_at(6).1.to_string()
+        } else if url.starts_with("udp:") {
+            self.protocol = Protocol::UDP;
+            self.uri.split_at(4).1.to_string()
         } else {
             self.protocol = Protocol::HTTPS;
-            url
+            self.uri.clone()
         };
 
         while split_url.starts_with("\\") || split_url.starts_with("/") {
@@ -209,6 +257,607 @@ impl RequestData {
         }
         false
     }
+
+    pub fn protocot_is_grpc(&self) -> bool {
+        if [Protocol::GRPC, Protocol::GRPCS].contains(&self.protocol) {
+            return true;
+        }
+        false
+    }
+
+    pub fn protocot_is_mqtt(&self) -> bool {
+        if [Protocol::MQTT, Protocol::MQTTS].contains(&self.protocol) {
+            return true;
+        }
+        false
+    }
+
+    pub fn protocot_is_udp(&self) -> bool {
+        self.protocol == Protocol::UDP
+    }
+}
+
+/// Request authorization, applied on send
+#[derive(Debug, Clone, Default)]
+pub struct RequestAuth {
+    pub auth_type: AuthType,
+    /// Basic and Digest user
+    pub username: String,
+    /// Basic and Digest password
+    pub password: String,
+    /// Bearer token
+    pub token: String,
+    /// API key header or query param name
+    pub api_key_name: String,
+    pub api_key_value: String,
+    pub api_key_location: ApiKeyLocation,
+    pub oauth2: OAuth2Auth,
+    pub aws: AwsSigV4Auth,
+    pub hmac: HmacAuth,
+}
+
+impl From<&RequestAuthSettings> for RequestAuth {
+    fn from(value: &RequestAuthSettings) -> Self {
+        Self {
+            auth_type: match value.auth_type {
+                AuthTypeSettings::INHERIT => AuthType::INHERIT,
+                AuthTypeSettings::NONE => AuthType::NONE,
+                AuthTypeSettings::BASIC => AuthType::BASIC,
+                AuthTypeSettings::DIGEST => AuthType::DIGEST,
+                AuthTypeSettings::BEARER => AuthType::BEARER,
+                AuthTypeSettings::APIKEY => AuthType::APIKEY,
+                AuthTypeSettings::OAUTH2 => AuthType::OAUTH2,
+                AuthTypeSettings::AWSSIGV4 => AuthType::AWSSIGV4,
+                AuthTypeSettings::HMAC => AuthType::HMAC,
+            },
+            username: value.username.clone(),
+            password: value.password.clone(),
+            token: value.token.clone(),
+            api_key_name: value.api_key_name.clone(),
+            api_key_value: value.api_key_value.clone(),
+            api_key_location: match value.api_key_location {
+                ApiKeyLocationSettings::HEADER => ApiKeyLocation::HEADER,
+                ApiKeyLocationSettings::QUERY => ApiKeyLocation::QUERY,
+            },
+            oauth2: OAuth2Auth::from(&value.oauth2),
+            aws: AwsSigV4Auth::from(&value.aws),
+            hmac: HmacAuth::from(&value.hmac),
+        }
+    }
+}
+
+impl RequestAuth {
+    /// Headers computed from selected scheme.
+    /// OAuth 2.0 token shown from cache, actual one acquired on send
+    pub fn headers(&self) -> Vec<Header> {
+        match self.auth_type {
+            AuthType::INHERIT | AuthType::NONE => vec![],
+            AuthType::BASIC => vec![Header {
+                key: "Authorization".into(),
+                value: format!(
+                    "Basic {}",
+                    STANDARD.encode(format!("{}:{}", self.username, self.password))
+                ),
+            }],
+            AuthType::DIGEST => vec![Header {
+                key: "Authorization".into(),
+                value: "Digest <answered on server challenge...>".into(),
+            }],
+            AuthType::BEARER => vec![Header {
+                key: "Authorization".into(),
+                value: format!("Bearer {}", self.token.trim()),
+            }],
+            AuthType::APIKEY => {
+                if self.api_key_location == ApiKeyLocation::HEADER && !self.api_key_name.is_empty()
+                {
+                    vec![Header {
+                        key: self.api_key_name.clone(),
+                        value: self.api_key_value.clone(),
+                    }]
+                } else {
+                    vec![]
+                }
+            }
+            AuthType::OAUTH2 => vec![Header {
+                key: "Authorization".into(),
+                value: match oauth2::cached_token(&self.oauth2) {
+                    Some(token) => format!("Bearer {}", token.access_token),
+                    None => "Bearer <acquired on send...>".into(),
+                },
+            }],
+            AuthType::AWSSIGV4 => vec![Header {
+                key: "Authorization".into(),
+                value: "AWS4-HMAC-SHA256 <signed on send...>".into(),
+            }],
+            AuthType::HMAC => {
+                let mut headers = vec![];
+                if !self.hmac.timestamp_header.is_empty() {
+                    headers.push(Header {
+                        key: self.hmac.timestamp_header.clone(),
+                        value: "<time of send...>".into(),
+                    });
+                }
+                if !self.hmac.signature_header.is_empty() {
+                    headers.push(Header {
+                        key: self.hmac.signature_header.clone(),
+                        value: "<signed on send...>".into(),
+                    });
+                }
+                headers
+            }
+        }
+    }
+
+    /// Auth actually applied: parent one when inherited.
+    /// No parent or parent inheriting itself - no auth
+    pub fn resolve(&self, parent: Option<&RequestAuth>) -> RequestAuth {
+        if self.auth_type != AuthType::INHERIT {
+            return self.clone();
+        }
+
+        match parent {
+            Some(parent) if parent.auth_type != AuthType::INHERIT => parent.clone(),
+            _ => RequestAuth {
+                auth_type: AuthType::NONE,
+                ..self.clone()
+            },
+        }
+    }
+
+    /// Query params computed from selected scheme
+    pub fn query_params(&self) -> Vec<Header> {
+        if self.auth_type == AuthType::APIKEY
+            && self.api_key_location == ApiKeyLocation::QUERY
+            && !self.api_key_name.is_empty()
+        {
+            return vec![Header {
+                key: self.api_key_name.clone(),
+                value: self.api_key_value.clone(),
+            }];
+        }
+        vec![]
+    }
+}
+
+/// Authorization scheme
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum AuthType {
+    /// Auth of parent collection
+    #[default]
+    INHERIT,
+    NONE,
+    BASIC,
+    DIGEST,
+    BEARER,
+    APIKEY,
+    OAUTH2,
+    AWSSIGV4,
+    HMAC,
+}
+
+impl Display for AuthType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuthType::INHERIT => f.write_str("Inherit from parent"),
+            AuthType::NONE => f.write_str("No Auth"),
+            AuthType::BASIC => f.write_str("Basic"),
+            AuthType::DIGEST => f.write_str("Digest"),
+            AuthType::BEARER => f.write_str("Bearer Token"),
+            AuthType::APIKEY => f.write_str("API Key"),
+            AuthType::OAUTH2 => f.write_str("OAuth 2.0"),
+            AuthType::AWSSIGV4 => f.write_str("AWS Signature"),
+            AuthType::HMAC => f.write_str("HMAC Signature"),
+        }
+    }
+}
+
+/// OAuth 2.0 token acquiring
+#[derive(Debug, Clone, Default)]
+pub struct OAuth2Auth {
+    pub grant: OAuth2Grant,
+    pub token_url: String,
+    /// Authorization code grant only
+    pub auth_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    /// Space separated scopes
+    pub scope: String,
+    /// Password grant user
+    pub username: String,
+    /// Password grant password
+    pub password: String,
+    /// Port of local redirect listener. Empty - random
+    pub redirect_port: String,
+    /// Client id and secret sent in form, instead of Basic header
+    pub credentials_in_body: bool,
+}
+
+impl From<&OAuth2Settings> for OAuth2Auth {
+    fn from(value: &OAuth2Settings) -> Self {
+        Self {
+            grant: match value.grant {
+                OAuth2GrantSettings::CLIENTCREDENTIALS => OAuth2Grant::CLIENTCREDENTIALS,
+                OAuth2GrantSettings::PASSWORD => OAuth2Grant::PASSWORD,
+                OAuth2GrantSettings::AUTHCODE => OAuth2Grant::AUTHCODE,
+            },
+            token_url: value.token_url.clone(),
+            auth_url: value.auth_url.clone(),
+            client_id: value.client_id.clone(),
+            client_secret: value.client_secret.clone(),
+            scope: value.scope.clone(),
+            username: value.username.clone(),
+            password: value.password.clone(),
+            redirect_port: value.redirect_port.clone(),
+            credentials_in_body: value.credentials_in_body,
+        }
+    }
+}
+
+impl OAuth2Auth {
+    pub fn redirect_port(&self) -> u16 {
+        self.redirect_port.parse::<u16>().unwrap_or(0)
+    }
+}
+
+/// OAuth 2.0 grant type
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum OAuth2Grant {
+    #[default]
+    CLIENTCREDENTIALS,
+    PASSWORD,
+    AUTHCODE,
+}
+
+impl Display for OAuth2Grant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OAuth2Grant::CLIENTCREDENTIALS => f.write_str("Client Credentials"),
+            OAuth2Grant::PASSWORD => f.write_str("Password"),
+            OAuth2Grant::AUTHCODE => f.write_str("Authorization Code (PKCE)"),
+        }
+    }
+}
+
+/// AWS Signature Version 4 credentials and scope
+#[derive(Debug, Clone, Default)]
+pub struct AwsSigV4Auth {
+    pub access_key: String,
+    pub secret_key: String,
+    /// Temporary credentials only
+    pub session_token: String,
+    pub region: String,
+    /// Service name in credential scope, like execute-api or s3
+    pub service: String,
+}
+
+impl From<&AwsSigV4Settings> for AwsSigV4Auth {
+    fn from(value: &AwsSigV4Settings) -> Self {
+        Self {
+            access_key: value.access_key.clone(),
+            secret_key: value.secret_key.clone(),
+            session_token: value.session_token.clone(),
+            region: value.region.clone(),
+            service: value.service.clone(),
+        }
+    }
+}
+
+/// HMAC signature over string built from template
+#[derive(Debug, Clone)]
+pub struct HmacAuth {
+    pub algorithm: HmacAlgorithm,
+    pub encoding: HmacEncoding,
+    pub secret: String,
+    /// Secret is base64 of key bytes
+    pub secret_base64: bool,
+    /// String to sign with placeholders, like {method} or {body_sha256}
+    pub template: String,
+    pub signature_header: String,
+    /// Value of signature header, {signature} replaced with computed one
+    pub signature_value: String,
+    /// Empty - timestamp not sent
+    pub timestamp_header: String,
+    pub timestamp_format: HmacTimestamp,
+}
+
+impl Default for HmacAuth {
+    fn default() -> Self {
+        Self::from(&HmacSettings::default())
+    }
+}
+
+impl From<&HmacSettings> for HmacAuth {
+    fn from(value: &HmacSettings) -> Self {
+        Self {
+            algorithm: match value.algorithm {
+                HmacAlgorithmSettings::SHA1 => HmacAlgorithm::SHA1,
+                HmacAlgorithmSettings::SHA256 => HmacAlgorithm::SHA256,
+                HmacAlgorithmSettings::SHA512 => HmacAlgorithm::SHA512,
+            },
+            encoding: match value.encoding {
+                HmacEncodingSettings::HEX => HmacEncoding::HEX,
+                HmacEncodingSettings::BASE64 => HmacEncoding::BASE64,
+            },
+            secret: value.secret.clone(),
+            secret_base64: value.secret_base64,
+            template: value.template.clone(),
+            signature_header: value.signature_header.clone(),
+            signature_value: value.signature_value.clone(),
+            timestamp_header: value.timestamp_header.clone(),
+            timestamp_format: match value.timestamp_format {
+                HmacTimestampSettings::UNIXSECONDS => HmacTimestamp::UNIXSECONDS,
+                HmacTimestampSettings::UNIXMILLIS => HmacTimestamp::UNIXMILLIS,
+                HmacTimestampSettings::ISO8601 => HmacTimestamp::ISO8601,
+            },
+        }
+    }
+}
+
+/// Hash function of HMAC
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum HmacAlgorithm {
+    SHA1,
+    #[default]
+    SHA256,
+    SHA512,
+}
+
+impl Display for HmacAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HmacAlgorithm::SHA1 => f.write_str("HMAC-SHA1"),
+            HmacAlgorithm::SHA256 => f.write_str("HMAC-SHA256"),
+            HmacAlgorithm::SHA512 => f.write_str("HMAC-SHA512"),
+        }
+    }
+}
+
+/// Text form of signature bytes
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum HmacEncoding {
+    #[default]
+    HEX,
+    BASE64,
+}
+
+impl Display for HmacEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HmacEncoding::HEX => f.write_str("Hex"),
+            HmacEncoding::BASE64 => f.write_str("Base64"),
+        }
+    }
+}
+
+/// Format of {timestamp} and timestamp header
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum HmacTimestamp {
+    #[default]
+    UNIXSECONDS,
+    UNIXMILLIS,
+    ISO8601,
+}
+
+impl Display for HmacTimestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HmacTimestamp::UNIXSECONDS => f.write_str("Unix seconds"),
+            HmacTimestamp::UNIXMILLIS => f.write_str("Unix milliseconds"),
+            HmacTimestamp::ISO8601 => f.write_str("ISO 8601"),
+        }
+    }
+}
+
+/// Where API key is sent
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum ApiKeyLocation {
+    #[default]
+    HEADER,
+    QUERY,
+}
+
+impl Display for ApiKeyLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiKeyLocation::HEADER => f.write_str("Header"),
+            ApiKeyLocation::QUERY => f.write_str("Query Params"),
+        }
+    }
+}
+
+/// Value extracted from response into variable, for request chaining
+#[derive(Debug, Clone, Default)]
+pub struct Capture {
+    pub source: CaptureSource,
+    /// JSONPath, header or cookie name, regex
+    pub expression: String,
+    /// Name of variable to store value
+    pub variable: String,
+    pub scope: CaptureScope,
+}
+
+impl From<&CaptureSettings> for Capture {
+    fn from(value: &CaptureSettings) -> Self {
+        Self {
+            source: match value.source {
+                CaptureSourceSettings::JSONPATH => CaptureSource::JSONPATH,
+                CaptureSourceSettings::HEADER => CaptureSource::HEADER,
+                CaptureSourceSettings::COOKIE => CaptureSource::COOKIE,
+                CaptureSourceSettings::REGEX => CaptureSource::REGEX,
+            },
+            expression: value.expression.clone(),
+            variable: value.variable.clone(),
+            scope: match value.scope {
+                CaptureScopeSettings::ENVIRONMENT => CaptureScope::ENVIRONMENT,
+                CaptureScopeSettings::COLLECTION => CaptureScope::COLLECTION,
+            },
+        }
+    }
+}
+
+/// Part of response value taken from
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum CaptureSource {
+    #[default]
+    JSONPATH,
+    HEADER,
+    COOKIE,
+    REGEX,
+}
+
+impl Display for CaptureSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CaptureSource::JSONPATH => f.write_str("JSONPath"),
+            CaptureSource::HEADER => f.write_str("Header"),
+            CaptureSource::COOKIE => f.write_str("Cookie"),
+            CaptureSource::REGEX => f.write_str("Regex"),
+        }
+    }
+}
+
+/// Where captured variable stored
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum CaptureScope {
+    /// Active environment
+    #[default]
+    ENVIRONMENT,
+    /// Collection of request
+    COLLECTION,
+}
+
+impl Display for CaptureScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CaptureScope::ENVIRONMENT => f.write_str("Environment"),
+            CaptureScope::COLLECTION => f.write_str("Collection"),
+        }
+    }
+}
+
+/// Check of response made after each send
+#[derive(Debug, Clone, Default)]
+pub struct Assertion {
+    pub kind: AssertionKind,
+    /// Header name or JSONPath
+    pub target: String,
+    /// Status, range, regex, value, type or milliseconds
+    pub expected: String,
+}
+
+impl From<&AssertionSettings> for Assertion {
+    fn from(value: &AssertionSettings) -> Self {
+        Self {
+            kind: match value.kind {
+                AssertionKindSettings::STATUS => AssertionKind::STATUS,
+                AssertionKindSettings::STATUSRANGE => AssertionKind::STATUSRANGE,
+                AssertionKindSettings::HEADEREXISTS => AssertionKind::HEADEREXISTS,
+                AssertionKindSettings::HEADERMATCHES => AssertionKind::HEADERMATCHES,
+                AssertionKindSettings::JSONEQUALS => AssertionKind::JSONEQUALS,
+                AssertionKindSettings::JSONCONTAINS => AssertionKind::JSONCONTAINS,
+                AssertionKindSettings::JSONTYPE => AssertionKind::JSONTYPE,
+                AssertionKindSettings::BODYMATCHES => AssertionKind::BODYMATCHES,
+                AssertionKindSettings::TIMEBELOW => AssertionKind::TIMEBELOW,
+            },
+            target: value.target.clone(),
+            expected: value.expected.clone(),
+        }
+    }
+}
+
+/// What part of response checked and how
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum AssertionKind {
+    /// Status equals code
+    #[default]
+    STATUS,
+    /// Status within `200-299`
+    STATUSRANGE,
+    HEADEREXISTS,
+    /// Header value matches regex
+    HEADERMATCHES,
+    /// JSONPath value equals JSON or text
+    JSONEQUALS,
+    /// JSONPath text contains substring or array contains item
+    JSONCONTAINS,
+    /// JSONPath value of type: string, number, boolean, object, array, null
+    JSONTYPE,
+    /// Body matches regex
+    BODYMATCHES,
+    /// Response time below milliseconds
+    TIMEBELOW,
+}
+
+impl AssertionKind {
+    pub const ALL: [AssertionKind; 9] = [
+        AssertionKind::STATUS,
+        AssertionKind::STATUSRANGE,
+        AssertionKind::HEADEREXISTS,
+        AssertionKind::HEADERMATCHES,
+        AssertionKind::JSONEQUALS,
+        AssertionKind::JSONCONTAINS,
+        AssertionKind::JSONTYPE,
+        AssertionKind::BODYMATCHES,
+        AssertionKind::TIMEBELOW,
+    ];
+
+    /// Target hint, None when kind has no target
+    pub fn target_hint(&self) -> Option<&'static str> {
+        match self {
+            AssertionKind::HEADEREXISTS | AssertionKind::HEADERMATCHES => Some("Header name"),
+            AssertionKind::JSONEQUALS | AssertionKind::JSONCONTAINS | AssertionKind::JSONTYPE => {
+                Some("$.path")
+            }
+            _ => None,
+        }
+    }
+
+    /// Expected value hint, None when kind has no expected value
+    pub fn expected_hint(&self) -> Option<&'static str> {
+        match self {
+            AssertionKind::STATUS => Some("200"),
+            AssertionKind::STATUSRANGE => Some("200-299"),
+            AssertionKind::HEADEREXISTS => None,
+            AssertionKind::HEADERMATCHES | AssertionKind::BODYMATCHES => Some("Regex"),
+            AssertionKind::JSONEQUALS | AssertionKind::JSONCONTAINS => Some("Value"),
+            AssertionKind::JSONTYPE => Some("string|number|boolean|object|array|null"),
+            AssertionKind::TIMEBELOW => Some("ms"),
+        }
+    }
+}
+
+impl Display for AssertionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssertionKind::STATUS => f.write_str("Status equals"),
+            AssertionKind::STATUSRANGE => f.write_str("Status in range"),
+            AssertionKind::HEADEREXISTS => f.write_str("Header exists"),
+            AssertionKind::HEADERMATCHES => f.write_str("Header matches"),
+            AssertionKind::JSONEQUALS => f.write_str("JSONPath equals"),
+            AssertionKind::JSONCONTAINS => f.write_str("JSONPath contains"),
+            AssertionKind::JSONTYPE => f.write_str("JSONPath type"),
+            AssertionKind::BODYMATCHES => f.write_str("Body matches"),
+            AssertionKind::TIMEBELOW => f.write_str("Time below"),
+        }
+    }
+}
+
+/// Rhai scripts run around send, of request or collection
+#[derive(Debug, Clone, Default)]
+pub struct Scripts {
+    /// Could change request and set variables before send
+    pub pre_request: String,
+    /// Could check response, set variables and add test results
+    pub post_response: String,
+}
+
+impl From<&ScriptsSettings> for Scripts {
+    fn from(value: &ScriptsSettings) -> Self {
+        Self {
+            pre_request: value.pre_request.clone(),
+            post_response: value.post_response.clone(),
+        }
+    }
 }
 
 /// Request Form Body data Fied Type
@@ -238,17 +887,134 @@ pub struct BodyFromData {
 /// Body representation for request
 #[derive(Debug, Clone)]
 pub struct RequestBody {
+    /// Part of body sent, others kept for switching back
+    pub mode: BodyMode,
     pub raw: CountedText,
     pub form_data: Vec<BodyFromData>,
     pub binary_path: String,
+    pub graphql: GraphQlBody,
+    pub json_rpc: JsonRpcBody,
 }
 
 impl Default for RequestBody {
     fn default() -> Self {
         Self {
+            mode: BodyMode::default(),
             raw: CountedText::default(),
             form_data: vec![],
             binary_path: "".into(),
+            graphql: GraphQlBody::default(),
+            json_rpc: JsonRpcBody::default(),
+        }
+    }
+}
+
+/// Part of request body to send
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum BodyMode {
+    #[default]
+    RAW,
+    FORMDATA,
+    BINARY,
+    GRAPHQL,
+    JSONRPC,
+}
+
+impl BodyMode {
+    /// Mode for settings saved before mode was stored: first filled part
+    fn guess(value: &RequestBodySettigns) -> Self {
+        if !value.graphql.query.trim().is_empty() {
+            Self::GRAPHQL
+        } else if !value.json_rpc.method.trim().is_empty()
+            || (value.json_rpc.batch && !value.json_rpc.params.trim().is_empty())
+        {
+            Self::JSONRPC
+        } else if !value.raw.is_empty() {
+            Self::RAW
+        } else if !value.form_data.is_empty() {
+            Self::FORMDATA
+        } else if !value.binary_path.is_empty() {
+            Self::BINARY
+        } else {
+            Self::RAW
+        }
+    }
+}
+
+/// GraphQL operation, sent as JSON envelope
+#[derive(Debug, Clone)]
+pub struct GraphQlBody {
+    pub query: CountedText,
+    /// JSON object with variables
+    pub variables: CountedText,
+    pub operation_name: String,
+}
+
+impl Default for GraphQlBody {
+    fn default() -> Self {
+        Self {
+            query: CountedText::default(),
+            variables: CountedText::default(),
+            operation_name: "".into(),
+        }
+    }
+}
+
+/// JSON-RPC call, envelope and id generated on send
+#[derive(Debug, Clone)]
+pub struct JsonRpcBody {
+    pub method: String,
+    /// Params of call, or list of calls for batch
+    pub params: CountedText,
+    /// Send without id, no reply expected
+    pub notification: bool,
+    pub batch: bool,
+}
+
+impl Default for JsonRpcBody {
+    fn default() -> Self {
+        Self {
+            method: "".into(),
+            params: CountedText::default(),
+            notification: false,
+            batch: false,
+        }
+    }
+}
+
+impl JsonRpcBody {
+    /// Nothing to send
+    pub fn is_empty(&self) -> bool {
+        if self.batch {
+            return self.params.message.trim().is_empty();
+        }
+        self.method.trim().is_empty()
+    }
+}
+
+impl From<&JsonRpcBodySettings> for JsonRpcBody {
+    fn from(value: &JsonRpcBodySettings) -> Self {
+        let mut params = CountedText::default();
+        params.set(value.params.clone());
+        Self {
+            method: value.method.clone(),
+            params,
+            notification: value.notification,
+            batch: value.batch,
+        }
+    }
+}
+
+impl From<&GraphQlBodySettings> for GraphQlBody {
+    fn from(value: &GraphQlBodySettings) -> Self {
+        let mut query = CountedText::default();
+        query.set(value.query.clone());
+        let mut variables = CountedText::default();
+        variables.set(value.variables.clone());
+        Self {
+            query,
+            variables,
+            operation_name: value.operation_name.clone(),
         }
     }
 }
@@ -286,9 +1052,19 @@ impl From<&RequestBodySettigns> for RequestBody {
         let mut raw = CountedText::default();
         raw.set(value.raw.clone());
         Self {
+            mode: match value.mode {
+                Some(BodyModeSettings::RAW) => BodyMode::RAW,
+                Some(BodyModeSettings::FORMDATA) => BodyMode::FORMDATA,
+                Some(BodyModeSettings::BINARY) => BodyMode::BINARY,
+                Some(BodyModeSettings::GRAPHQL) => BodyMode::GRAPHQL,
+                Some(BodyModeSettings::JSONRPC) => BodyMode::JSONRPC,
+                None => BodyMode::guess(value),
+            },
             raw,
             form_data,
             binary_path: value.binary_path.clone(),
+            graphql: GraphQlBody::from(&value.graphql),
+            json_rpc: JsonRpcBody::from(&value.json_rpc),
         }
     }
 }
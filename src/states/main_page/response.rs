@@ -1,9 +1,12 @@
+use std::time::Duration;
+
 use chrono::{DateTime, Local};
 use serde_json::Value;
-use tokio_tungstenite::tungstenite::Utf8Bytes;
+use tokio_tungstenite::tungstenite::{protocol::CloseFrame, Utf8Bytes};
 
 use crate::states::main_page::{generics::Header, response::response_data::ResponseData};
 use reqwest::{Error, Response as HttpResponse};
+use tonic::Status;
 
 pub mod response_data;
 
@@ -20,10 +23,32 @@ pub struct Response {
     pub code: usize,
     /// state of UI element of folded
     pub is_folded: bool,
-    // TODO: add how long request took. Troubles how to save request send time.
+    /// WS Close frame code, when peer closed connection
+    pub close_code: Option<u16>,
+    /// WS Close frame reason, when peer closed connection
+    pub close_reason: String,
+    /// Name of event for messages of protocols on top of WS (Socket.IO event, etc.)
+    pub event: Option<String>,
+    /// Message pushed by server, not reply on request (JSON-RPC notification, etc.)
+    pub is_notification: bool,
+    /// How long request took, from send to response received
+    pub duration: Option<Duration>,
+    /// Results of request assertions, empty when none declared
+    pub assertions: Vec<AssertionResult>,
 }
 
 impl Response {
+    /// Count of passed and all assertions
+    pub fn assertions_passed(&self) -> (usize, usize) {
+        (
+            self.assertions
+                .iter()
+                .filter(|result| result.is_passed)
+                .count(),
+            self.assertions.len(),
+        )
+    }
+
     /// Used as answear from WSS ot WS
     pub fn from_utf8_bytes(data: Utf8Bytes) -> Self {
         Self {
@@ -32,16 +57,95 @@ impl Response {
             selected_view: ResponseView::RAW,
             code: 0,
             is_folded: true,
+            close_code: None,
+            close_reason: "".into(),
+            event: None,
+            is_notification: false,
+            duration: None,
+            assertions: vec![],
+        }
+    }
+
+    /// Used as answear from protocols with named messages, like Socket.IO events
+    pub fn from_event(event: String, data: String) -> Self {
+        Self {
+            time: Local::now(),
+            data: ResponseData::new(data, vec![], "".into()),
+            selected_view: ResponseView::RAW,
+            code: 0,
+            is_folded: true,
+            close_code: None,
+            close_reason: "".into(),
+            event: Some(event),
+            is_notification: false,
+            duration: None,
+            assertions: vec![],
         }
     }
 
-    pub fn closed_connection() -> Self {
+    /// Used as answear from protocols with framed messages, like STOMP frames
+    pub fn from_frame(event: String, headers: Vec<Header>, data: String) -> Self {
         Self {
             time: Local::now(),
-            data: ResponseData::new("Connection closed".into(), vec![], "".into()),
+            data: ResponseData::new(data, headers, "".into()),
             selected_view: ResponseView::RAW,
             code: 0,
             is_folded: true,
+            close_code: None,
+            close_reason: "".into(),
+            event: Some(event),
+            is_notification: false,
+            duration: None,
+            assertions: vec![],
+        }
+    }
+
+    /// Used as final gRPC call status, with trailers as headers
+    pub fn from_grpc_status(status: &Status, trailers: Vec<Header>) -> Self {
+        Self::from_frame(
+            format!("STATUS {} {:?}", status.code() as i32, status.code()),
+            trailers,
+            status.message().into(),
+        )
+    }
+
+    /// Used for messages pushed by server without request, like JSON-RPC notifications
+    pub fn from_notification(event: String, data: String) -> Self {
+        let mut response = Self::from_event(event, data);
+        response.is_notification = true;
+        response
+    }
+
+    /// Used when WS connection closed.
+    /// If Close frame received - its code and reason stored in response
+    pub fn closed_connection(close_frame: Option<CloseFrame>) -> Self {
+        let (raw, close_code, close_reason) = match close_frame {
+            Some(frame) => {
+                let code = u16::from(frame.code);
+                let reason = frame.reason.to_string();
+                (
+                    format!(
+                        "Connection closed. Code: {code} ({}). Reason: {reason}",
+                        close_code_description(code)
+                    ),
+                    Some(code),
+                    reason,
+                )
+            }
+            None => ("Connection closed".into(), None, "".into()),
+        };
+        Self {
+            time: Local::now(),
+            data: ResponseData::new(raw, vec![], "".into()),
+            selected_view: ResponseView::RAW,
+            code: 0,
+            is_folded: true,
+            close_code,
+            close_reason,
+            event: None,
+            is_notification: false,
+            duration: None,
+            assertions: vec![],
         }
     }
 
@@ -68,6 +172,12 @@ impl Response {
                         selected_view: ResponseView::RAW,
                         code,
                         is_folded: true,
+                        close_code: None,
+                        close_reason: "".into(),
+                        event: None,
+                        is_notification: false,
+                        duration: None,
+                        assertions: vec![],
                     }),
                     Err(err) => Err((
                         Self {
@@ -80,6 +190,12 @@ impl Response {
                             selected_view: ResponseView::RAW,
                             code,
                             is_folded: true,
+                            close_code: None,
+                            close_reason: "".into(),
+                            event: None,
+                            is_notification: false,
+                            duration: None,
+                            assertions: vec![],
                         },
                         format!("Error during text receiving. Error: {}", err),
                     )),
@@ -94,6 +210,12 @@ impl Response {
                         selected_view: ResponseView::RAW,
                         code: err.status().unwrap().as_u16() as usize,
                         is_folded: true,
+                        close_code: None,
+                        close_reason: "".into(),
+                        event: None,
+                        is_notification: false,
+                        duration: None,
+                        assertions: vec![],
                     }),
                     None => Err((
                         Self {
@@ -106,6 +228,12 @@ impl Response {
                             selected_view: ResponseView::RAW,
                             code: err.status().unwrap().as_u16() as usize,
                             is_folded: true,
+                            close_code: None,
+                            close_reason: "".into(),
+                            event: None,
+                            is_notification: false,
+                            duration: None,
+                            assertions: vec![],
                         },
                         "Error during text receiving for error reason".into(),
                     )),
@@ -121,6 +249,12 @@ impl Response {
                         selected_view: ResponseView::RAW,
                         code: err.status().unwrap().as_u16() as usize,
                         is_folded: true,
+                        close_code: None,
+                        close_reason: "".into(),
+                        event: None,
+                        is_notification: false,
+                        duration: None,
+                        assertions: vec![],
                     },
                     "Error during text receiving".into(),
                 )),
@@ -143,6 +277,12 @@ impl Response {
                     selected_view: ResponseView::RAW,
                     code,
                     is_folded: true,
+                    close_code: None,
+                    close_reason: "".into(),
+                    event: None,
+                    is_notification: false,
+                    duration: None,
+                    assertions: vec![],
                 }),
                 None => Err((
                     Self {
@@ -156,6 +296,12 @@ impl Response {
                         selected_view: ResponseView::RAW,
                         code,
                         is_folded: true,
+                        close_code: None,
+                        close_reason: "".into(),
+                        event: None,
+                        is_notification: false,
+                        duration: None,
+                        assertions: vec![],
                     },
                     "During Request Error occured. Could not read error status code.".into(),
                 )),
@@ -171,6 +317,12 @@ impl Response {
                     selected_view: ResponseView::RAW,
                     code,
                     is_folded: true,
+                    close_code: None,
+                    close_reason: "".into(),
+                    event: None,
+                    is_notification: false,
+                    duration: None,
+                    assertions: vec![],
                 },
                 "During Request Error occured. Could not read error reason.".into(),
             )),
@@ -178,6 +330,40 @@ impl Response {
     }
 }
 
+/// Outcome of single assertion
+#[derive(Debug, Clone)]
+pub struct AssertionResult {
+    /// Human readable assertion, like `Status equals 200`
+    pub name: String,
+    pub is_passed: bool,
+    /// Actual value or reason of failure
+    pub message: String,
+}
+
+/// Human readable description of WS Close frame code (RFC 6455)
+pub fn close_code_description(code: u16) -> &'static str {
+    match code {
+        1000 => "Normal Closure",
+        1001 => "Going Away",
+        1002 => "Protocol Error",
+        1003 => "Unsupported Data",
+        1005 => "No Status Received",
+        1006 => "Abnormal Closure",
+        1007 => "Invalid Payload",
+        1008 => "Policy Violation",
+        1009 => "Message Too Big",
+        1010 => "Mandatory Extension",
+        1011 => "Internal Error",
+        1012 => "Service Restart",
+        1013 => "Try Again Later",
+        1014 => "Bad Gateway",
+        1015 => "TLS Handshake",
+        3000..=3999 => "Registered",
+        4000..=4999 => "Private",
+        _ => "Unknown",
+    }
+}
+
 /// Json representations for UI
 #[derive(Debug, Clone, PartialEq)]
 pub enum JsonViewType {
@@ -214,10 +400,31 @@ impl JsonView {
     }
 }
 
+/// Responses shown in list, notifications could be shown separately from replies
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResponsesFilter {
+    All,
+    Replies,
+    Notifications,
+}
+
+impl ResponsesFilter {
+    /// Check if response should be shown with filter
+    pub fn is_visible(&self, response: &Response) -> bool {
+        match self {
+            ResponsesFilter::All => true,
+            ResponsesFilter::Replies => !response.is_notification,
+            ResponsesFilter::Notifications => response.is_notification,
+        }
+    }
+}
+
 /// Response representations types
 #[derive(Debug, Clone, PartialEq)]
 pub enum ResponseView {
     JSON,
     RAW,
     HEADERS,
+    JWT,
+    TESTS,
 }
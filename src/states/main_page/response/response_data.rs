@@ -1,4 +1,8 @@
-use crate::states::main_page::{generics::Header, response::JsonView};
+use crate::states::main_page::{
+    generics::Header,
+    jwt::{self, Jwt},
+    response::JsonView,
+};
 
 /// Response data state
 #[derive(Debug, Clone)]
@@ -8,16 +12,28 @@ pub struct ResponseData {
     pub headers: Vec<Header>,
     pub redictection_url: String,
     pub redirection: Vec<Redicrections>,
+    /// Tokens found in headers and body
+    pub jwts: Vec<Jwt>,
 }
 
 impl ResponseData {
     pub fn new(raw: String, headers: Vec<Header>, redictection_url: String) -> Self {
+        let mut tokens = Jwt::find_in_headers(&headers);
+        for token in Jwt::find(&raw) {
+            if !tokens.contains(&token) {
+                tokens.push(token);
+            }
+        }
+        let mut jwts = vec![];
+        jwt::sync(&mut jwts, tokens);
+
         Self {
             json: JsonView::new(&raw),
             raw,
             headers,
             redictection_url,
             redirection: vec![],
+            jwts,
         }
     }
 
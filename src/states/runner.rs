@@ -0,0 +1,349 @@
+/// Run of collection requests one by one, with report of results
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::states::{
+    main_page::{
+        collection::Collection, entity::Entity, generics::Header, response::AssertionResult,
+        MainPage,
+    },
+    Events,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct Runner {
+    pub is_visible: bool,
+    /// Collection to run
+    pub collection_id: Option<String>,
+    pub collection_name: String,
+    /// Requests of collection in order, only selected ones run
+    pub steps: Vec<RunnerStep>,
+    /// Delay between requests in ms, as typed
+    pub delay: String,
+    /// Stop run on first failed request
+    pub stop_on_failure: bool,
+    /// Results of last run
+    pub report: Vec<RunReport>,
+    state: RunnerState,
+}
+
+/// Request of collection to run
+#[derive(Debug, Clone)]
+pub struct RunnerStep {
+    pub request_id: String,
+    pub name: String,
+    pub is_selected: bool,
+    /// Only HTTP requests have response to wait for
+    pub is_runnable: bool,
+}
+
+/// Result of one request of run
+#[derive(Debug, Clone)]
+pub struct RunReport {
+    pub request_id: String,
+    pub name: String,
+    /// HTTP code, 0 when not received
+    pub code: usize,
+    pub duration: Option<Duration>,
+    pub assertions: Vec<AssertionResult>,
+    pub is_passed: bool,
+    /// Reason when request was not sent
+    pub error: Option<String>,
+    /// Index of response in request responses
+    pub response_idx: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default)]
+enum RunnerState {
+    #[default]
+    Idle,
+    /// Next step sent after time passed
+    Waiting { step: usize, until: Instant },
+    /// Step sent, waiting executor to get free
+    Running { step: usize, responses: usize },
+}
+
+impl Runner {
+    /// Open runner for collection, all runnable requests selected
+    pub fn open(&mut self, collection: &Collection) {
+        if self.collection_id.as_ref() != Some(&collection.id) && !self.is_running() {
+            self.collection_id = Some(collection.id.clone());
+            self.report.clear();
+        }
+        self.collection_name = collection.draft.name.clone();
+
+        // Keep selection of requests already known
+        let previous = std::mem::take(&mut self.steps);
+        self.steps = collection
+            .requests
+            .iter()
+            .map(|request| {
+                let is_runnable = request.draft.protocot_is_http();
+                let is_selected = previous
+                    .iter()
+                    .find(|step| step.request_id == request.id)
+                    .map_or(is_runnable, |step| step.is_selected && is_runnable);
+                RunnerStep {
+                    request_id: request.id.clone(),
+                    name: request.draft.name.clone(),
+                    is_selected,
+                    is_runnable,
+                }
+            })
+            .collect();
+        self.is_visible = true;
+    }
+
+    pub fn is_running(&self) -> bool {
+        !matches!(self.state, RunnerState::Idle)
+    }
+
+    /// Index of step currently sent or waiting
+    pub fn current_step(&self) -> Option<usize> {
+        match self.state {
+            RunnerState::Idle => None,
+            RunnerState::Waiting { step, .. } | RunnerState::Running { step, .. } => Some(step),
+        }
+    }
+
+    pub fn start(&mut self, events: &Arc<Mutex<Events>>) {
+        self.report.clear();
+        match self.next_step(0) {
+            Some(step) => {
+                events
+                    .lock()
+                    .unwrap()
+                    .event_info(&format!("Runner: running {}", self.collection_name));
+                self.state = RunnerState::Waiting {
+                    step,
+                    until: Instant::now(),
+                };
+            }
+            None => events
+                .lock()
+                .unwrap()
+                .event_warning(&"Runner: no requests selected".into()),
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.state = RunnerState::Idle;
+    }
+
+    /// Count of passed and all reported requests
+    pub fn passed(&self) -> (usize, usize) {
+        (
+            self.report.iter().filter(|report| report.is_passed).count(),
+            self.report.len(),
+        )
+    }
+
+    /// Advance run: send next request when its time come, collect result of sent one.
+    /// environment - variables of active environment, collection ones added on send
+    pub fn update(
+        &mut self,
+        main_page: &mut MainPage,
+        environment: Vec<Header>,
+        events: &Arc<Mutex<Events>>,
+    ) {
+        let Some(collection) = self
+            .collection_id
+            .as_ref()
+            .and_then(|id| collection_mut(main_page, id))
+        else {
+            self.stop();
+            events
+                .lock()
+                .unwrap()
+                .event_error(&"Runner: collection not found".into());
+            return;
+        };
+
+        match self.state.clone() {
+            RunnerState::Idle => {}
+            RunnerState::Waiting { step, until } => {
+                if Instant::now() < until {
+                    return;
+                }
+
+                let request_id = self.steps[step].request_id.clone();
+                let parent = collection.draft.clone();
+                let variables = parent.merge_variables(environment);
+                let Some(request) = collection
+                    .requests
+                    .iter_mut()
+                    .find(|request| request.id == request_id)
+                else {
+                    self.fail(step, "Request not found".into(), events);
+                    return;
+                };
+                if !request.executor_is_free() {
+                    self.fail(step, "Request is already executing".into(), events);
+                    return;
+                }
+
+                let responses = request.responses.lock().unwrap().len();
+                request.go(Arc::clone(events), false, Some(&parent), &variables);
+
+                // Executor stays free when request was not sent, like on script error
+                if request.executor_is_free()
+                    && request.responses.lock().unwrap().len() == responses
+                {
+                    self.fail(step, "Request not sent, see events".into(), events);
+                    return;
+                }
+                self.state = RunnerState::Running { step, responses };
+            }
+            RunnerState::Running { step, responses } => {
+                let Some(request) = collection
+                    .requests
+                    .iter()
+                    .find(|request| request.id == self.steps[step].request_id)
+                else {
+                    self.fail(step, "Request not found".into(), events);
+                    return;
+                };
+                if !request.executor_is_free() {
+                    return;
+                }
+
+                let received = request.responses.lock().unwrap();
+                // Last one is final, Digest challenge comes before it
+                let report = match received
+                    .len()
+                    .checked_sub(1)
+                    .filter(|last| *last >= responses)
+                {
+                    Some(last) => {
+                        let response = &received[last];
+                        let (passed, total) = response.assertions_passed();
+                        RunReport {
+                            request_id: request.id.clone(),
+                            name: request.draft.name.clone(),
+                            code: response.code,
+                            duration: response.duration,
+                            assertions: response.assertions.clone(),
+                            // Without assertions only status matters
+                            is_passed: if total > 0 {
+                                passed == total
+                            } else {
+                                (100..400).contains(&response.code)
+                            },
+                            error: None,
+                            response_idx: Some(last),
+                        }
+                    }
+                    None => RunReport {
+                        request_id: request.id.clone(),
+                        name: request.draft.name.clone(),
+                        code: 0,
+                        duration: None,
+                        assertions: vec![],
+                        is_passed: false,
+                        error: Some("No response received".into()),
+                        response_idx: None,
+                    },
+                };
+                drop(received);
+
+                self.finish_step(step, report, events);
+            }
+        }
+    }
+
+    /// Select request of report and unfold its response
+    pub fn show_response(&self, report_idx: usize, main_page: &mut MainPage) {
+        let (Some(report), Some(collection_id)) =
+            (self.report.get(report_idx), self.collection_id.as_ref())
+        else {
+            return;
+        };
+        let Some(collection_idx) = main_page.entities.iter().position(|entity| {
+            matches!(entity, Entity::COLLECTION(collection) if &collection.id == collection_id)
+        }) else {
+            return;
+        };
+        let Some(collection) = main_page.get_collection_mut(collection_idx) else {
+            return;
+        };
+        let Some(request_idx) = collection
+            .requests
+            .iter()
+            .position(|request| request.id == report.request_id)
+        else {
+            return;
+        };
+
+        collection.is_folded = false;
+        if let Some(i) = report.response_idx {
+            let request = &collection.requests[request_idx];
+            if let Some(response) = request.responses.lock().unwrap().get_mut(i) {
+                response.is_folded = false;
+            }
+        }
+        main_page
+            .selected_entity
+            .select_request(Some(collection_idx), request_idx);
+    }
+
+    fn fail(&mut self, step: usize, error: String, events: &Arc<Mutex<Events>>) {
+        let report = RunReport {
+            request_id: self.steps[step].request_id.clone(),
+            name: self.steps[step].name.clone(),
+            code: 0,
+            duration: None,
+            assertions: vec![],
+            is_passed: false,
+            error: Some(error),
+            response_idx: None,
+        };
+        self.finish_step(step, report, events);
+    }
+
+    /// Record result, plan next step or finish run
+    fn finish_step(&mut self, step: usize, report: RunReport, events: &Arc<Mutex<Events>>) {
+        let is_passed = report.is_passed;
+        self.report.push(report);
+
+        let next = self.next_step(step + 1);
+        match next {
+            Some(next) if is_passed || !self.stop_on_failure => {
+                let delay = self.delay.trim().parse::<u64>().unwrap_or(0);
+                self.state = RunnerState::Waiting {
+                    step: next,
+                    until: Instant::now() + Duration::from_millis(delay),
+                };
+            }
+            _ => {
+                self.state = RunnerState::Idle;
+                let (passed, total) = self.passed();
+                let message = format!(
+                    "Runner: {} finished, passed {passed} of {total}",
+                    self.collection_name
+                );
+                if passed == total {
+                    events.lock().unwrap().event_info(&message);
+                } else {
+                    events.lock().unwrap().event_warning(&message);
+                }
+            }
+        }
+    }
+
+    /// Index of next selected step, starting from
+    fn next_step(&self, from: usize) -> Option<usize> {
+        (from..self.steps.len()).find(|i| self.steps[*i].is_selected && self.steps[*i].is_runnable)
+    }
+}
+
+fn collection_mut<'a>(main_page: &'a mut MainPage, id: &str) -> Option<&'a mut Collection> {
+    main_page
+        .entities
+        .iter_mut()
+        .find_map(|entity| match entity {
+            Entity::COLLECTION(collection) if collection.id == id => Some(collection),
+            _ => None,
+        })
+}
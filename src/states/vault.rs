@@ -0,0 +1,205 @@
+/// Vault of secret variables: locked or unlocked by master password or OS kept key
+use std::{collections::HashMap, fmt::Debug};
+
+use crate::settings::vault_settings::{self, VaultKey, VaultModeSettings, VaultSettings};
+
+#[derive(Clone, Default)]
+pub struct Vault {
+    /// Vault file, None when not created yet
+    file: Option<VaultSettings>,
+    /// Key of unlocked vault
+    key: Option<VaultKey>,
+}
+
+/// Key never printed
+impl Debug for Vault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Vault")
+            .field("is_created", &self.is_created())
+            .field("is_unlocked", &self.is_unlocked())
+            .finish()
+    }
+}
+
+impl Vault {
+    /// Vault from default path, locked
+    pub fn load() -> Result<Self, String> {
+        Ok(Self {
+            file: VaultSettings::load()?,
+            key: None,
+        })
+    }
+
+    pub fn is_created(&self) -> bool {
+        self.file.is_some()
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.key.is_some()
+    }
+
+    /// Vault unlocked by OS kept key, without password
+    pub fn uses_os_key(&self) -> bool {
+        self.file
+            .as_ref()
+            .is_some_and(|file| file.mode == VaultModeSettings::KEYRING)
+    }
+
+    /// Create empty vault and keep it unlocked.
+    /// password - master password, None to protect by OS kept key
+    pub fn create(&mut self, password: Option<&str>) -> Result<(), String> {
+        let (mut file, key) = match password {
+            Some(password) => {
+                if password.is_empty() {
+                    return Err("Master password is empty".into());
+                }
+                let file = VaultSettings::new(VaultModeSettings::PASSWORD);
+                let key = file.password_key(password)?;
+                (file, key)
+            }
+            None => {
+                let key = match vault_settings::os_key()? {
+                    Some(key) => key,
+                    None => {
+                        let key = vault_settings::random_key();
+                        vault_settings::store_os_key(&key)?;
+                        key
+                    }
+                };
+                (VaultSettings::new(VaultModeSettings::KEYRING), key)
+            }
+        };
+
+        file.encrypt(&key, &HashMap::new())?;
+        file.save()?;
+        self.file = Some(file);
+        self.key = Some(key);
+        Ok(())
+    }
+
+    /// Unlock vault by master password, or by OS kept key for such vaults
+    pub fn unlock(&mut self, password: &str) -> Result<(), String> {
+        let file = self
+            .file
+            .as_ref()
+            .ok_or("Vault is not created".to_string())?;
+        let key = match file.mode {
+            VaultModeSettings::PASSWORD => file.password_key(password)?,
+            VaultModeSettings::KEYRING => {
+                vault_settings::os_key()?.ok_or("Vault key not found in OS keyring".to_string())?
+            }
+        };
+
+        // Wrong key fails on authentication tag
+        file.decrypt(&key)?;
+        self.key = Some(key);
+        Ok(())
+    }
+
+    pub fn lock(&mut self) {
+        self.key = None;
+    }
+
+    /// Secrets by "environment id/name". Empty while locked
+    pub fn secrets(&self) -> Result<HashMap<String, String>, String> {
+        match (&self.file, &self.key) {
+            (Some(file), Some(key)) => file.decrypt(key),
+            _ => Ok(HashMap::new()),
+        }
+    }
+
+    /// Encrypt secrets into vault file. Skipped while locked
+    pub fn save(&mut self, secrets: &HashMap<String, String>) -> Result<(), String> {
+        let (Some(file), Some(key)) = (&mut self.file, &self.key) else {
+            return Ok(());
+        };
+        file.encrypt(key, secrets)?;
+        file.save()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        settings::environments_settings::EnvironmentsSettings,
+        states::{environments::Environments, main_page::generics::Header},
+    };
+
+    use super::*;
+
+    /// Password vault with secrets, as if loaded from file
+    fn vault(password: &str, secrets: &HashMap<String, String>) -> Vault {
+        let mut file = VaultSettings::new(VaultModeSettings::PASSWORD);
+        let key = file.password_key(password).unwrap();
+        file.encrypt(&key, secrets).unwrap();
+        Vault {
+            file: Some(file),
+            key: None,
+        }
+    }
+
+    #[test]
+    fn unlock_and_lock() {
+        let secrets = HashMap::from([("env/token".to_string(), "abc".to_string())]);
+        let mut vault = vault("master", &secrets);
+        assert!(vault.is_created() && !vault.is_unlocked() && !vault.uses_os_key());
+        assert_eq!(vault.secrets().unwrap(), HashMap::new());
+
+        assert_eq!(
+            vault.unlock("wrong").unwrap_err(),
+            "Wrong password or key, vault could not be decrypted"
+        );
+        assert!(!vault.is_unlocked());
+
+        vault.unlock("master").unwrap();
+        assert!(vault.is_unlocked());
+        assert_eq!(vault.secrets().unwrap(), secrets);
+        assert!(!format!("{vault:?}").contains("key:"));
+
+        vault.lock();
+        assert!(!vault.is_unlocked());
+        assert_eq!(vault.secrets().unwrap(), HashMap::new());
+        // Locked vault keeps file untouched
+        vault.save(&HashMap::new()).unwrap();
+        vault.unlock("master").unwrap();
+        assert_eq!(vault.secrets().unwrap(), secrets);
+    }
+
+    #[test]
+    fn not_created() {
+        let mut vault = Vault::default();
+        assert!(!vault.is_created());
+        assert_eq!(vault.unlock("master").unwrap_err(), "Vault is not created");
+        assert_eq!(
+            vault.create(Some("")).unwrap_err(),
+            "Master password is empty"
+        );
+        assert!(!vault.is_created());
+    }
+
+    #[test]
+    fn environment_secrets() {
+        let mut environments = Environments::default();
+        environments.add();
+        let environment = &mut environments.items[0];
+        environment.secrets = vec![Header {
+            key: "token".into(),
+            value: "abc".into(),
+        }];
+        let id = environment.id.clone();
+
+        let mut vault = vault("master", &environments.secret_values());
+        vault.unlock("master").unwrap();
+
+        // Settings keep only names of secrets
+        let settings = EnvironmentsSettings::from(&environments);
+        assert_eq!(settings.items[0].secrets, vec!["token"]);
+        assert!(!serde_json::to_string(&settings).unwrap().contains("abc"));
+
+        let mut environments = Environments::from(&settings);
+        assert_eq!(environments.items[0].secrets[0].value, "");
+        environments.fill_secrets(&vault.secrets().unwrap());
+        assert_eq!(environments.items[0].id, id);
+        assert_eq!(environments.items[0].secrets[0].value, "abc");
+    }
+}
@@ -9,10 +9,13 @@ use crate::{
 
 mod bottom;
 pub mod colors;
+mod environments_window;
 pub mod fonts;
 pub mod icons;
 mod main_menu;
 mod main_page;
+mod runner_window;
+mod variables_inspector;
 
 /// UI states of data and window
 /// Add to `fn controls()` - to add mode keybinding shortcuts
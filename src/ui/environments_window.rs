@@ -0,0 +1,382 @@
+use egui::{
+    vec2, Button, Context, CornerRadius, Frame, Label, Margin, ScrollArea, TextEdit, Window,
+};
+use rfd::FileDialog;
+
+use crate::{
+    executor::variables,
+    states::{main_page::generics::Header, States},
+};
+
+/// Editor of environments and their variables
+pub struct EnvironmentsWindow {
+    pub is_visible: bool,
+    /// index of environment in editor
+    selected: usize,
+    /// new variable data - for UI, to add new variable
+    new_variable: Header,
+    /// new secret data - for UI, to add new secret
+    new_secret: Header,
+    /// master password input, cleared after use
+    vault_password: String,
+}
+
+impl EnvironmentsWindow {
+    pub fn new() -> Self {
+        Self {
+            is_visible: false,
+            selected: 0,
+            new_variable: Header::default(),
+            new_secret: Header::default(),
+            vault_password: "".into(),
+        }
+    }
+
+    /// Open editor with active environment selected
+    pub fn open(&mut self, states: &States) {
+        self.is_visible = true;
+        if let Some(active) = &states.environments.active {
+            if let Some(index) = states
+                .environments
+                .items
+                .iter()
+                .position(|environment| &environment.id == active)
+            {
+                self.selected = index;
+            }
+        }
+    }
+
+    pub fn update(&mut self, ctx: &Context, states: &mut States) {
+        let mut is_visible = self.is_visible;
+
+        Window::new("Environments")
+            .collapsible(false)
+            .resizable(true)
+            .default_size(vec2(600., 400.))
+            .open(&mut is_visible)
+            .show(ctx, |ui| {
+                Frame::default()
+                    .fill(states.style.color_main())
+                    .inner_margin(Margin::same(10))
+                    .corner_radius(CornerRadius::same(5))
+                    .show(ui, |ui| {
+                        ui.set_width(ui.available_width());
+                        ui.style_mut().spacing.button_padding = vec2(5., 5.);
+
+                        ui.horizontal_top(|ui| {
+                            self.update_list(ui, states);
+                            ui.separator();
+                            ui.vertical(|ui| {
+                                self.update_variables(ui, states);
+                                self.update_secrets(ui, states);
+                            });
+                        });
+
+                        ui.add_space(10.);
+                        ui.separator();
+                        ui.horizontal(|ui| {
+                            if ui
+                                .add_enabled(
+                                    states.environments.is_changed,
+                                    Button::new("Save").fill(states.style.color_success()),
+                                )
+                                .clicked()
+                            {
+                                states.save_environments();
+                            }
+                            ui.add(Label::new(
+                                "Variables used in requests as {{name}}, resolved on send",
+                            ))
+                            .on_hover_text(format!("Built-in variables:\n{}", variables::BUILT_IN));
+                        });
+                    });
+            });
+
+        self.is_visible = is_visible;
+    }
+
+    /// Environments names and Add button
+    fn update_list(&mut self, ui: &mut egui::Ui, states: &mut States) {
+        ui.vertical(|ui| {
+            ui.set_width(150.);
+
+            for i in 0..states.environments.items.len() {
+                let environment = &states.environments.items[i];
+                let is_active = states.environments.active.as_ref() == Some(&environment.id);
+                let name = if is_active {
+                    format!("{} (active)", environment.name)
+                } else {
+                    environment.name.clone()
+                };
+
+                if ui.selectable_label(self.selected == i, name).clicked() {
+                    self.selected = i;
+                }
+            }
+
+            ui.add_space(5.);
+            if ui.button("Add").clicked() {
+                self.selected = states.environments.add();
+            }
+        });
+    }
+
+    /// Name and variables table of selected environment
+    fn update_variables(&mut self, ui: &mut egui::Ui, states: &mut States) {
+        if self.selected >= states.environments.items.len() {
+            ui.add(Label::new("No environments yet"));
+            return;
+        }
+
+        let mut is_changed = false;
+        let mut remove = false;
+        let mut activate = false;
+        let mut import = None;
+        let is_active = states.environments.active.as_ref()
+            == Some(&states.environments.items[self.selected].id);
+        let environment = &mut states.environments.items[self.selected];
+
+        ui.horizontal(|ui| {
+            ui.add(Label::new(states.style.fonts.label_text("Name:")));
+            is_changed |= ui
+                .add(TextEdit::singleline(&mut environment.name).desired_width(200.))
+                .changed();
+
+            if !is_active && ui.button("Set active").clicked() {
+                activate = true;
+            }
+            if ui
+                .add(Button::new("Delete").fill(states.style.color_danger()))
+                .clicked()
+            {
+                remove = true;
+            }
+        });
+
+        ui.add_space(5.);
+        ui.horizontal(|ui| {
+            if ui
+                .button("Import...")
+                .on_hover_text(".env file or flat JSON object")
+                .clicked()
+            {
+                import = FileDialog::new()
+                    .set_directory("./")
+                    .add_filter("env", &["env"])
+                    .add_filter("json", &["json"])
+                    .add_filter("all", &["*"])
+                    .pick_file();
+            }
+            if !environment.source.is_empty() {
+                is_changed |= ui
+                    .checkbox(&mut environment.watch, "Watch file")
+                    .on_hover_text("Reload variables when file changes")
+                    .changed();
+                ui.add(Label::new(&environment.source).truncate());
+            }
+        });
+
+        ui.add_space(10.);
+
+        ScrollArea::vertical()
+            .id_salt("environment-variables")
+            .max_height(250.)
+            .show(ui, |ui| {
+                ui.style_mut().spacing.item_spacing = vec2(2., 2.);
+                let mut remove_variable = None;
+
+                for (i, variable) in environment.variables.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        is_changed |= ui
+                            .add(
+                                TextEdit::singleline(&mut variable.key)
+                                    .hint_text("name")
+                                    .desired_width(150.),
+                            )
+                            .changed();
+                        is_changed |= ui
+                            .add(
+                                TextEdit::singleline(&mut variable.value)
+                                    .hint_text("value")
+                                    .desired_width(ui.available_width() - 25.),
+                            )
+                            .changed();
+                        if ui
+                            .add(Button::new("x").fill(states.style.color_danger()))
+                            .clicked()
+                        {
+                            remove_variable = Some(i);
+                        }
+                    });
+                }
+
+                if let Some(i) = remove_variable {
+                    environment.variables.remove(i);
+                    is_changed = true;
+                }
+
+                ui.horizontal(|ui| {
+                    let key_resp = ui.add(
+                        TextEdit::singleline(&mut self.new_variable.key)
+                            .hint_text("new variable")
+                            .desired_width(150.),
+                    );
+                    let value_resp = ui.add(
+                        TextEdit::singleline(&mut self.new_variable.value)
+                            .hint_text("value")
+                            .desired_width(ui.available_width() - 25.),
+                    );
+                    if (key_resp.changed() || value_resp.changed())
+                        && (!self.new_variable.key.is_empty()
+                            || !self.new_variable.value.is_empty())
+                    {
+                        environment.variables.push(self.new_variable.clone());
+                        self.new_variable = Header::default();
+                        is_changed = true;
+                    }
+                });
+            });
+
+        if is_changed {
+            states.environments.is_changed = true;
+        }
+        if let Some(path) = import {
+            let path = path.to_string_lossy().to_string();
+            match states.environments.import_file(self.selected, path.clone()) {
+                Ok(count) => states.event_info(&format!("Imported {count} variables from {path}")),
+                Err(err) => states.event_error(&err),
+            }
+        }
+        if activate {
+            let id = states.environments.items[self.selected].id.clone();
+            states.environments.select(Some(id));
+        }
+        if remove {
+            states.environments.remove(self.selected);
+            self.selected = self.selected.saturating_sub(1);
+        }
+    }
+
+    /// Secret variables of selected environment, or vault unlock controls
+    fn update_secrets(&mut self, ui: &mut egui::Ui, states: &mut States) {
+        if self.selected >= states.environments.items.len() {
+            return;
+        }
+
+        ui.add_space(10.);
+        ui.horizontal(|ui| {
+            ui.add(Label::new(states.style.fonts.label_text("Secrets:")));
+            if states.vault.is_unlocked() && ui.button("Lock vault").clicked() {
+                states.lock_vault();
+            }
+        });
+        ui.add_space(5.);
+
+        if !states.vault.is_unlocked() {
+            ui.horizontal(|ui| {
+                if states.vault.uses_os_key() {
+                    if ui.button("Unlock with OS keyring").clicked() {
+                        states.unlock_vault("");
+                    }
+                    return;
+                }
+
+                let is_created = states.vault.is_created();
+                ui.add(
+                    TextEdit::singleline(&mut self.vault_password)
+                        .password(true)
+                        .hint_text(if is_created {
+                            "master password"
+                        } else {
+                            "new master password"
+                        })
+                        .desired_width(200.),
+                );
+                if ui
+                    .add_enabled(
+                        !self.vault_password.is_empty(),
+                        Button::new(if is_created { "Unlock" } else { "Create vault" }),
+                    )
+                    .clicked()
+                {
+                    states.unlock_vault(&self.vault_password);
+                    self.vault_password.clear();
+                }
+                if !is_created && ui.button("Use OS keyring").clicked() {
+                    states.unlock_vault("");
+                }
+            });
+            ui.add(Label::new(
+                "Secret values stored encrypted apart from settings and never exported",
+            ));
+            return;
+        }
+
+        let mut is_changed = false;
+        let environment = &mut states.environments.items[self.selected];
+
+        ScrollArea::vertical()
+            .id_salt("environment-secrets")
+            .show(ui, |ui| {
+                ui.style_mut().spacing.item_spacing = vec2(2., 2.);
+                let mut remove_secret = None;
+
+                for (i, secret) in environment.secrets.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        is_changed |= ui
+                            .add(
+                                TextEdit::singleline(&mut secret.key)
+                                    .hint_text("name")
+                                    .desired_width(150.),
+                            )
+                            .changed();
+                        is_changed |= ui
+                            .add(
+                                TextEdit::singleline(&mut secret.value)
+                                    .password(true)
+                                    .hint_text("value")
+                                    .desired_width(ui.available_width() - 25.),
+                            )
+                            .changed();
+                        if ui
+                            .add(Button::new("x").fill(states.style.color_danger()))
+                            .clicked()
+                        {
+                            remove_secret = Some(i);
+                        }
+                    });
+                }
+
+                if let Some(i) = remove_secret {
+                    environment.secrets.remove(i);
+                    is_changed = true;
+                }
+
+                ui.horizontal(|ui| {
+                    let key_resp = ui.add(
+                        TextEdit::singleline(&mut self.new_secret.key)
+                            .hint_text("new secret")
+                            .desired_width(150.),
+                    );
+                    let value_resp = ui.add(
+                        TextEdit::singleline(&mut self.new_secret.value)
+                            .password(true)
+                            .hint_text("value")
+                            .desired_width(ui.available_width() - 25.),
+                    );
+                    if (key_resp.changed() || value_resp.changed())
+                        && (!self.new_secret.key.is_empty() || !self.new_secret.value.is_empty())
+                    {
+                        environment.secrets.push(self.new_secret.clone());
+                        self.new_secret = Header::default();
+                        is_changed = true;
+                    }
+                });
+            });
+
+        if is_changed {
+            states.environments.is_changed = true;
+        }
+    }
+}
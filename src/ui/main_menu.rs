@@ -6,12 +6,21 @@ use egui::{
 };
 use rfd::FileDialog;
 
-use crate::states::States;
+use crate::{
+    states::States,
+    ui::{
+        environments_window::EnvironmentsWindow, runner_window::RunnerWindow,
+        variables_inspector::VariablesInspector,
+    },
+};
 
 pub struct MainMenu {
     export_folder_path: Option<PathBuf>,
     import_file_path: Option<PathBuf>,
     modal_about_is_visilbe: bool,
+    environments_window: EnvironmentsWindow,
+    variables_inspector: VariablesInspector,
+    runner_window: RunnerWindow,
 }
 
 impl MainMenu {
@@ -20,6 +29,9 @@ impl MainMenu {
             export_folder_path: None,
             import_file_path: None,
             modal_about_is_visilbe: false,
+            environments_window: EnvironmentsWindow::new(),
+            variables_inspector: VariablesInspector::new(),
+            runner_window: RunnerWindow::new(),
         }
     }
 
@@ -224,6 +236,91 @@ impl MainMenu {
                         },
                     );
 
+                    ui.menu_button(
+                        states
+                            .style
+                            .fonts
+                            .menu_text(&format!("Env: {}", states.environments.active_name()))
+                            .color(states.style.color_main()),
+                        |ui| {
+                            ui.style_mut().spacing.button_padding = vec2(10., 10.);
+                            ui.style_mut().visuals.widgets.hovered.weak_bg_fill =
+                                states.style.color_lighter();
+                            ui.style_mut().visuals.widgets.open.weak_bg_fill =
+                                states.style.color_lighter();
+
+                            // Selected after loop, environments cant change during it
+                            let mut selected = None;
+                            if ui
+                                .add(
+                                    Button::new(
+                                        states
+                                            .style
+                                            .fonts
+                                            .menu_text("No Environment")
+                                            .color(states.style.color_main()),
+                                    )
+                                    .selected(states.environments.active.is_none()),
+                                )
+                                .clicked()
+                            {
+                                selected = Some(None);
+                            };
+                            for environment in &states.environments.items {
+                                if ui
+                                    .add(
+                                        Button::new(
+                                            states
+                                                .style
+                                                .fonts
+                                                .menu_text(&environment.name)
+                                                .color(states.style.color_main()),
+                                        )
+                                        .selected(
+                                            states.environments.active.as_ref()
+                                                == Some(&environment.id),
+                                        ),
+                                    )
+                                    .clicked()
+                                {
+                                    selected = Some(Some(environment.id.clone()));
+                                };
+                            }
+                            if let Some(id) = selected {
+                                states.environments.select(id);
+                                states.save_environments();
+                            }
+
+                            ui.separator();
+
+                            if ui
+                                .button(
+                                    states
+                                        .style
+                                        .fonts
+                                        .menu_text("Manage Environments...")
+                                        .color(states.style.color_main()),
+                                )
+                                .clicked()
+                            {
+                                self.environments_window.open(states);
+                            };
+
+                            if ui
+                                .button(
+                                    states
+                                        .style
+                                        .fonts
+                                        .menu_text("Inspect Variables...")
+                                        .color(states.style.color_main()),
+                                )
+                                .clicked()
+                            {
+                                self.variables_inspector.is_visible = true;
+                            };
+                        },
+                    );
+
                     ui.menu_button(
                         states
                             .style
@@ -254,6 +351,15 @@ impl MainMenu {
                 });
             });
         self.about_window(ctx, states);
+        if self.environments_window.is_visible {
+            self.environments_window.update(ctx, states);
+        }
+        if self.variables_inspector.is_visible {
+            self.variables_inspector.update(ctx, states);
+        }
+        if states.runner.is_visible {
+            self.runner_window.update(ctx, states);
+        }
     }
 
     fn folders_picker(&mut self) {
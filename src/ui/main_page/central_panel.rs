@@ -8,15 +8,21 @@ use egui::{
 use crate::{
     states::States,
     ui::main_page::central_panel::{
-        request_details::RequestDetailsPanel, responses::ResponsesListPanel,
+        auth_details::{AuthDetails, AuthScope},
+        request_details::RequestDetailsPanel,
+        responses::ResponsesListPanel,
     },
 };
+mod auth_details;
+mod jwt_view;
 mod request_details;
 mod responses;
+mod variables_highlight;
 
 pub struct CentralPanel {
     request_details: RequestDetailsPanel,
     responses_list: ResponsesListPanel,
+    auth_details: AuthDetails,
 }
 
 impl CentralPanel {
@@ -24,6 +30,7 @@ impl CentralPanel {
         Self {
             request_details: RequestDetailsPanel::new(),
             responses_list: ResponsesListPanel::new(),
+            auth_details: AuthDetails::new(),
         }
     }
 
@@ -67,6 +74,8 @@ impl CentralPanel {
 
                     ui.add_space(20.);
 
+                    let variables = states.variables();
+                    let environment_variables = states.environment_variables();
                     let collection_option = states.main_page.selected_collection_mut();
                     if let Some(collection) = collection_option {
                         ui.horizontal(|ui| {
@@ -101,6 +110,102 @@ impl CentralPanel {
                                 collection.is_changed = true;
                             };
                         });
+
+                        ui.horizontal(|ui| {
+                            ui.add_space(10.);
+                            if ui.button("Run collection...").clicked() {
+                                states.runner.open(collection);
+                            }
+                        });
+
+                        ui.add_space(20.);
+
+                        // Requests with inherited auth use this one
+                        ui.horizontal(|ui| {
+                            ui.add_space(10.);
+                            ui.add(Label::new("Auth:").selectable(false));
+                            ui.add_space(40.);
+                            ui.vertical(|ui| {
+                                if self.auth_details.update(
+                                    ui,
+                                    &mut collection.draft.auth,
+                                    AuthScope {
+                                        is_request: false,
+                                        parent: None,
+                                        variables: &variables,
+                                    },
+                                    &states.style,
+                                    &states.events,
+                                ) {
+                                    collection.is_changed = true;
+                                }
+                            });
+                        });
+
+                        ui.add_space(20.);
+
+                        // Used by requests as {{name}}, override environment ones
+                        ui.horizontal(|ui| {
+                            ui.add_space(10.);
+                            ui.add(Label::new("Variables:").selectable(false));
+                            ui.vertical(|ui| {
+                                if self
+                                    .request_details
+                                    .update_generic_headers_table(
+                                        ui,
+                                        &mut collection.draft.variables,
+                                        Some(&mut collection.new_variable),
+                                        &environment_variables,
+                                        &states.style,
+                                    )
+                                    .is_some()
+                                {
+                                    collection.is_changed = true;
+                                }
+                            });
+                        });
+
+                        ui.add_space(20.);
+
+                        // Merged into every request, request headers win
+                        ui.horizontal(|ui| {
+                            ui.add_space(10.);
+                            ui.add(Label::new("Headers:").selectable(false));
+                            ui.add_space(10.);
+                            ui.vertical(|ui| {
+                                if self
+                                    .request_details
+                                    .update_generic_headers_table(
+                                        ui,
+                                        &mut collection.draft.headers,
+                                        Some(&mut collection.new_header),
+                                        &variables,
+                                        &states.style,
+                                    )
+                                    .is_some()
+                                {
+                                    collection.is_changed = true;
+                                }
+                            });
+                        });
+
+                        ui.add_space(20.);
+
+                        // Run around send of every request, before request ones
+                        ui.horizontal(|ui| {
+                            ui.add_space(10.);
+                            ui.add(Label::new("Scripts:").selectable(false));
+                            ui.add_space(18.);
+                            ui.vertical(|ui| {
+                                if self.request_details.update_scripts(
+                                    ui,
+                                    &mut collection.draft.scripts,
+                                    &states.style,
+                                ) {
+                                    collection.is_changed = true;
+                                }
+                            });
+                        });
                     };
                 });
         });
@@ -0,0 +1,452 @@
+use std::sync::{Arc, Mutex};
+
+use egui::{vec2, Frame, Grid, Label, Margin, RichText, TextEdit, Ui};
+
+use crate::{
+    executor::{hmac_signer, oauth2, variables},
+    states::{
+        main_page::{
+            generics::Header,
+            request::request_data::{
+                ApiKeyLocation, AuthType, HmacAlgorithm, HmacEncoding, HmacTimestamp, OAuth2Auth,
+                OAuth2Grant, RequestAuth,
+            },
+        },
+        Events, Style,
+    },
+};
+
+/// Authorization settings of request or collection
+pub struct AuthDetails {}
+
+/// Where edited auth belongs to
+pub struct AuthScope<'a> {
+    /// Request could inherit auth of parent
+    pub is_request: bool,
+    /// Name and auth of request collection, shown when inherited
+    pub parent: Option<(&'a str, &'a RequestAuth)>,
+    /// Of active environment, OAuth2 token cached for resolved config
+    pub variables: &'a [Header],
+}
+
+impl AuthDetails {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Draw auth editor. Returns true if auth changed
+    pub fn update(
+        &self,
+        ui: &mut Ui,
+        auth: &mut RequestAuth,
+        scope: AuthScope,
+        style: &Style,
+        events: &Arc<Mutex<Events>>,
+    ) -> bool {
+        let AuthScope {
+            is_request,
+            parent,
+            variables,
+        } = scope;
+        let mut changed = false;
+
+        Frame::new().show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.add(Label::new(style.fonts.label_text("Type:")));
+
+                ui.add_space(20.);
+                ui.menu_button(auth.auth_type.to_string(), |ui| {
+                    ui.style_mut().spacing.button_padding = vec2(5., 5.);
+
+                    for auth_type in [
+                        AuthType::INHERIT,
+                        AuthType::NONE,
+                        AuthType::BASIC,
+                        AuthType::DIGEST,
+                        AuthType::BEARER,
+                        AuthType::APIKEY,
+                        AuthType::OAUTH2,
+                        AuthType::AWSSIGV4,
+                        AuthType::HMAC,
+                    ] {
+                        if auth_type == AuthType::INHERIT && !is_request {
+                            continue;
+                        }
+                        if auth.auth_type != auth_type && ui.button(auth_type.to_string()).clicked()
+                        {
+                            auth.auth_type = auth_type;
+                            changed = true
+                        }
+                    }
+                });
+
+                ui.add_space(ui.available_width());
+            })
+        });
+
+        ui.add_space(10.);
+
+        let auth_field = |ui: &mut Ui, label: &str, value: &mut String, password: bool| {
+            let mut changed = false;
+            Frame::new().show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(Label::new(style.fonts.label_text(label)));
+
+                    ui.add_space(20.);
+
+                    changed = ui
+                        .add(
+                            TextEdit::singleline(value)
+                                .password(password)
+                                .desired_width(ui.available_width() - 20.),
+                        )
+                        .changed();
+                });
+            });
+
+            ui.add_space(10.);
+            changed
+        };
+
+        match auth.auth_type {
+            AuthType::INHERIT => {
+                self.update_inherited(ui, auth, parent, variables, style);
+            }
+            AuthType::NONE => {
+                ui.add(Label::new("Sent without authorization"));
+            }
+            AuthType::BASIC => {
+                changed |= auth_field(ui, "Username:", &mut auth.username, false);
+                changed |= auth_field(ui, "Password:", &mut auth.password, true);
+            }
+            AuthType::DIGEST => {
+                changed |= auth_field(ui, "Username:", &mut auth.username, false);
+                changed |= auth_field(ui, "Password:", &mut auth.password, true);
+
+                ui.add(Label::new(
+                    "Sent on 401 challenge of server. MD5, SHA-256 and their -sess variants, qop=auth.\nNTLM and Negotiate challenges are not supported",
+                ));
+            }
+            AuthType::BEARER => {
+                changed |= auth_field(ui, "Token:", &mut auth.token, true);
+            }
+            AuthType::APIKEY => {
+                changed |= auth_field(ui, "Key:", &mut auth.api_key_name, false);
+                changed |= auth_field(ui, "Value:", &mut auth.api_key_value, true);
+
+                Frame::new().show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add(Label::new(style.fonts.label_text("Add to:")));
+
+                        ui.add_space(20.);
+
+                        for location in [ApiKeyLocation::HEADER, ApiKeyLocation::QUERY] {
+                            let text = location.to_string();
+                            if ui
+                                .radio_value(&mut auth.api_key_location, location, text)
+                                .changed()
+                            {
+                                changed = true
+                            }
+                        }
+                    });
+                });
+            }
+            AuthType::OAUTH2 => {
+                let oauth2 = &mut auth.oauth2;
+
+                Frame::new().show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add(Label::new(style.fonts.label_text("Grant:")));
+
+                        ui.add_space(20.);
+                        ui.menu_button(oauth2.grant.to_string(), |ui| {
+                            ui.style_mut().spacing.button_padding = vec2(5., 5.);
+
+                            for grant in [
+                                OAuth2Grant::CLIENTCREDENTIALS,
+                                OAuth2Grant::PASSWORD,
+                                OAuth2Grant::AUTHCODE,
+                            ] {
+                                if oauth2.grant != grant && ui.button(grant.to_string()).clicked() {
+                                    oauth2.grant = grant;
+                                    changed = true
+                                }
+                            }
+                        });
+
+                        ui.add_space(ui.available_width());
+                    })
+                });
+
+                ui.add_space(10.);
+
+                if oauth2.grant == OAuth2Grant::AUTHCODE {
+                    changed |= auth_field(ui, "Authorization URL:", &mut oauth2.auth_url, false);
+                }
+                changed |= auth_field(ui, "Token URL:", &mut oauth2.token_url, false);
+                changed |= auth_field(ui, "Client id:", &mut oauth2.client_id, false);
+                changed |= auth_field(ui, "Client secret:", &mut oauth2.client_secret, true);
+                changed |= auth_field(ui, "Scope:", &mut oauth2.scope, false);
+                if oauth2.grant == OAuth2Grant::PASSWORD {
+                    changed |= auth_field(ui, "Username:", &mut oauth2.username, false);
+                    changed |= auth_field(ui, "Password:", &mut oauth2.password, true);
+                }
+
+                Frame::new().show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        if oauth2.grant == OAuth2Grant::AUTHCODE {
+                            ui.add(Label::new(style.fonts.label_text("Redirect port:")));
+
+                            ui.add_space(20.);
+
+                            let initial_value = oauth2.redirect_port.clone();
+                            if ui
+                                .add(
+                                    TextEdit::singleline(&mut oauth2.redirect_port)
+                                        .hint_text("random")
+                                        .char_limit(5)
+                                        .desired_width(50.),
+                                )
+                                .on_hover_text(oauth2::redirect_uri(oauth2.redirect_port()))
+                                .changed()
+                            {
+                                if oauth2.redirect_port.is_empty()
+                                    || oauth2.redirect_port.parse::<u16>().is_ok()
+                                {
+                                    changed = true
+                                } else {
+                                    oauth2.redirect_port = initial_value;
+                                }
+                            };
+
+                            ui.add_space(20.);
+                        }
+
+                        if ui
+                            .checkbox(
+                                &mut oauth2.credentials_in_body,
+                                "Client credentials in body",
+                            )
+                            .on_hover_text(
+                                "Send client id and secret as form fields, instead of Basic header",
+                            )
+                            .changed()
+                        {
+                            changed = true
+                        };
+                    });
+                });
+
+                ui.add_space(10.);
+
+                ui.horizontal(|ui| {
+                    ui.style_mut().spacing.button_padding = vec2(5., 5.);
+
+                    let resolved = variables::resolve_oauth2(oauth2, variables);
+                    if ui.button("Get new token").clicked() {
+                        oauth2::fetch_token(resolved.clone(), Arc::clone(events));
+                    }
+                    if ui.button("Clear token").clicked() {
+                        oauth2::clear_token(&resolved);
+                    }
+
+                    ui.add_space(20.);
+
+                    ui.add(Label::new(oauth2_status(&resolved)));
+                });
+            }
+            AuthType::AWSSIGV4 => {
+                let aws = &mut auth.aws;
+
+                changed |= auth_field(ui, "Access key:", &mut aws.access_key, false);
+                changed |= auth_field(ui, "Secret key:", &mut aws.secret_key, true);
+                changed |= auth_field(ui, "Session token:", &mut aws.session_token, true);
+                changed |= auth_field(ui, "Region:", &mut aws.region, false);
+                changed |= auth_field(ui, "Service:", &mut aws.service, false);
+
+                ui.add(Label::new(
+                    "Request is signed right before send, including body hash",
+                ));
+            }
+            AuthType::HMAC => {
+                let hmac = &mut auth.hmac;
+
+                Frame::new().show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add(Label::new(style.fonts.label_text("Algorithm:")));
+
+                        ui.add_space(20.);
+                        ui.menu_button(hmac.algorithm.to_string(), |ui| {
+                            ui.style_mut().spacing.button_padding = vec2(5., 5.);
+
+                            for algorithm in [
+                                HmacAlgorithm::SHA1,
+                                HmacAlgorithm::SHA256,
+                                HmacAlgorithm::SHA512,
+                            ] {
+                                if hmac.algorithm != algorithm
+                                    && ui.button(algorithm.to_string()).clicked()
+                                {
+                                    hmac.algorithm = algorithm;
+                                    changed = true
+                                }
+                            }
+                        });
+
+                        ui.add_space(20.);
+                        ui.add(Label::new(style.fonts.label_text("Encoding:")));
+
+                        ui.add_space(20.);
+                        for encoding in [HmacEncoding::HEX, HmacEncoding::BASE64] {
+                            let text = encoding.to_string();
+                            if ui.radio_value(&mut hmac.encoding, encoding, text).changed() {
+                                changed = true
+                            }
+                        }
+
+                        ui.add_space(ui.available_width());
+                    })
+                });
+
+                ui.add_space(10.);
+
+                changed |= auth_field(ui, "Secret:", &mut hmac.secret, true);
+                if ui
+                    .checkbox(&mut hmac.secret_base64, "Secret is base64")
+                    .changed()
+                {
+                    changed = true
+                };
+
+                ui.add_space(10.);
+
+                ui.add(Label::new(style.fonts.label_text("String to sign:")));
+                ui.add_space(5.);
+                if ui
+                    .add(
+                        TextEdit::multiline(&mut hmac.template)
+                            .code_editor()
+                            .desired_rows(4)
+                            .desired_width(ui.available_width() - 20.),
+                    )
+                    .on_hover_text(hmac_signer::PLACEHOLDERS)
+                    .changed()
+                {
+                    changed = true
+                };
+                ui.add(Label::new(RichText::new(hmac_signer::PLACEHOLDERS).weak()));
+
+                ui.add_space(10.);
+
+                changed |= auth_field(ui, "Signature header:", &mut hmac.signature_header, false);
+                changed |= auth_field(ui, "Signature value:", &mut hmac.signature_value, false);
+                changed |= auth_field(ui, "Timestamp header:", &mut hmac.timestamp_header, false);
+
+                Frame::new().show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add(Label::new(style.fonts.label_text("Timestamp:")));
+
+                        ui.add_space(20.);
+                        ui.menu_button(hmac.timestamp_format.to_string(), |ui| {
+                            ui.style_mut().spacing.button_padding = vec2(5., 5.);
+
+                            for format in [
+                                HmacTimestamp::UNIXSECONDS,
+                                HmacTimestamp::UNIXMILLIS,
+                                HmacTimestamp::ISO8601,
+                            ] {
+                                if hmac.timestamp_format != format
+                                    && ui.button(format.to_string()).clicked()
+                                {
+                                    hmac.timestamp_format = format;
+                                    changed = true
+                                }
+                            }
+                        });
+
+                        ui.add_space(ui.available_width());
+                    })
+                });
+            }
+        }
+
+        changed
+    }
+
+    /// Read-only view of auth, taken from parent
+    fn update_inherited(
+        &self,
+        ui: &mut Ui,
+        auth: &RequestAuth,
+        parent: Option<(&str, &RequestAuth)>,
+        variables: &[Header],
+        style: &Style,
+    ) {
+        let effective = auth.resolve(parent.map(|(_, parent_auth)| parent_auth));
+
+        match parent {
+            Some((name, _)) => ui.add(Label::new(format!(
+                "Inherited from collection \"{name}\": {}",
+                effective.auth_type
+            ))),
+            None => ui.add(Label::new(
+                "Request is not in collection, sent without authorization",
+            )),
+        };
+
+        let mut rows: Vec<(&str, Header)> = effective
+            .headers()
+            .into_iter()
+            .map(|header| ("Header", header))
+            .collect();
+        rows.extend(
+            effective
+                .query_params()
+                .into_iter()
+                .map(|param| ("Query", param)),
+        );
+        if rows.is_empty() {
+            return;
+        }
+
+        ui.add_space(10.);
+
+        Frame::new()
+            .inner_margin(Margin::same(5))
+            .fill(style.color_main())
+            .show(ui, |ui| {
+                Grid::new("inherited-auth")
+                    .num_columns(3)
+                    .spacing(vec2(10., 5.))
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (place, row) in rows {
+                            ui.add(Label::new(place));
+                            ui.add(Label::new(row.key));
+                            ui.add(Label::new(RichText::new(row.value).strong()));
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if effective.auth_type == AuthType::OAUTH2 {
+            ui.add_space(10.);
+            ui.add(Label::new(oauth2_status(&variables::resolve_oauth2(
+                &effective.oauth2,
+                variables,
+            ))));
+        }
+    }
+}
+
+/// Cached token state for UI
+fn oauth2_status(config: &OAuth2Auth) -> String {
+    match oauth2::cached_token(config) {
+        Some(token) => match token.expires_in() {
+            Some(secs) => format!("Token cached, expires in {secs} s"),
+            None => "Token cached".into(),
+        },
+        None => "No token, acquired on send".into(),
+    }
+}
@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use chrono::Local;
+use egui::{vec2, Frame, Label, Margin, RichText, TextEdit, Ui};
+
+use crate::states::{main_page::jwt::Jwt, Style};
+
+/// Decoded JWT: header, claims, expiry and signature check
+pub struct JwtView {}
+
+impl JwtView {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn update(&self, ui: &mut Ui, jwt: &mut Jwt, style: &Style) {
+        Frame::new()
+            .inner_margin(Margin::same(5))
+            .fill(style.color_main())
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(Label::new(style.fonts.label_text("Algorithm:")));
+                    ui.add(Label::new(RichText::new(jwt.algorithm()).strong()));
+
+                    if let Some(typ) = jwt.header["typ"].as_str() {
+                        ui.add_space(20.);
+                        ui.add(Label::new(style.fonts.label_text("Type:")));
+                        ui.add(Label::new(RichText::new(typ).strong()));
+                    }
+                });
+
+                for (claim, label) in [
+                    ("exp", "Expires:"),
+                    ("iat", "Issued:"),
+                    ("nbf", "Not before:"),
+                ] {
+                    let Some(time) = jwt.time_claim(claim) else {
+                        continue;
+                    };
+
+                    ui.horizontal(|ui| {
+                        ui.add(Label::new(style.fonts.label_text(label)));
+                        ui.add(Label::new(
+                            RichText::new(time.format("%Y-%m-%d %H:%M:%S").to_string()).strong(),
+                        ));
+
+                        if claim == "exp" {
+                            let left = (time - Local::now()).num_seconds();
+                            if left > 0 {
+                                ui.add(Label::new(
+                                    RichText::new(format!("in {}", countdown(left)))
+                                        .color(style.color_success()),
+                                ));
+                            } else {
+                                ui.add(Label::new(
+                                    RichText::new(format!("expired {} ago", countdown(-left)))
+                                        .color(style.color_danger()),
+                                ));
+                            }
+                            // Keep countdown ticking
+                            ui.ctx().request_repaint_after(Duration::from_secs(1));
+                        }
+                    });
+                }
+
+                ui.add_space(5.);
+
+                for (label, value) in [("Header:", &jwt.header), ("Claims:", &jwt.claims)] {
+                    ui.add(Label::new(style.fonts.label_text(label)));
+                    let mut text = serde_json::to_string_pretty(value).unwrap_or_default();
+                    ui.add(
+                        TextEdit::multiline(&mut text)
+                            .code_editor()
+                            .desired_rows(1)
+                            .desired_width(ui.available_width())
+                            .text_color(style.color_main())
+                            .background_color(style.color_light()),
+                    );
+                    ui.add_space(5.);
+                }
+
+                ui.add(Label::new(style.fonts.label_text("Verify signature:")));
+                ui.horizontal(|ui| {
+                    ui.style_mut().spacing.button_padding = vec2(5., 5.);
+
+                    if ui
+                        .add(
+                            TextEdit::multiline(&mut jwt.key)
+                                .code_editor()
+                                .desired_rows(1)
+                                .hint_text("Secret or PEM public key")
+                                .desired_width(ui.available_width() - 80.),
+                        )
+                        .changed()
+                    {
+                        jwt.verification = None;
+                    };
+
+                    if ui.button("Verify").clicked() {
+                        jwt.verify();
+                    }
+                });
+
+                match &jwt.verification {
+                    Some(Ok(())) => {
+                        ui.add(Label::new(
+                            RichText::new("Signature is valid").color(style.color_success()),
+                        ));
+                    }
+                    Some(Err(err)) => {
+                        ui.add(Label::new(
+                            RichText::new(format!("Signature is not valid: {err}"))
+                                .color(style.color_danger()),
+                        ));
+                    }
+                    None => {}
+                }
+            });
+    }
+}
+
+/// Duration as `1d 2h 3m 4s`, without leading zero parts
+fn countdown(seconds: i64) -> String {
+    let parts = [
+        (seconds / 86400, "d"),
+        (seconds % 86400 / 3600, "h"),
+        (seconds % 3600 / 60, "m"),
+        (seconds % 60, "s"),
+    ];
+
+    let mut result = vec![];
+    for (value, unit) in parts {
+        if value > 0 || !result.is_empty() || unit == "s" {
+            result.push(format!("{value}{unit}"));
+        }
+    }
+    result.join(" ")
+}
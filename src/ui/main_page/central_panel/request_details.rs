@@ -1,35 +1,65 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use egui::{
-    vec2, Align, Button, ComboBox, CornerRadius, FontFamily, FontId, FontSelection, Frame, Label,
-    Layout, Margin, RichText, ScrollArea, Separator, TextEdit, TopBottomPanel, Ui, WidgetText,
+    text::{CCursor, CCursorRange},
+    text_edit::TextEditState,
+    vec2, Align, Button, CollapsingHeader, ComboBox, CornerRadius, FontFamily, FontId,
+    FontSelection, Frame, Id, Label, Layout, Margin, RichText, ScrollArea, Separator, TextBuffer,
+    TextEdit, TopBottomPanel, Ui, WidgetText,
 };
 use rfd::FileDialog;
 
 use crate::{
+    executor::{
+        grpc::{self, GrpcCallKind},
+        scripts,
+        variables::Variables,
+    },
     settings::main_settings::entity::request_settings::{
         method_settigns::Method, protocol_settings::Protocol,
     },
     states::{
         main_page::{
+            collection::CollectionData,
             entity::Entity,
             generics::{CountedText, Header},
+            jwt::{self, Jwt},
             request::{
-                default_ws_headers,
-                request_data::{BodyFromData, FormFieldType},
-                HttpVersion, RequestBodyDetails, RequestDetails, RequestHeaders, RequestSetup,
+                default_ws_headers, is_sendable_close_code,
+                request_data::{
+                    Assertion, AssertionKind, BodyFromData, BodyMode, Capture, CaptureScope,
+                    CaptureSource, FormFieldType, Scripts,
+                },
+                truncate_bytes, HttpVersion, MqttCommand, MqttVersion, Request, RequestDetails,
+                RequestHeaders, RequestSetup, StompCommand, UdpPayloadFormat, WsMode,
+                MAX_CLOSE_REASON_BYTES,
             },
+            response::close_code_description,
+        },
+        Events, States, Style,
+    },
+    ui::{
+        icons::Icon,
+        main_page::central_panel::{
+            auth_details::{AuthDetails, AuthScope},
+            jwt_view::JwtView,
+            variables_highlight::VariablesHighlight,
+            EntityDetailsHeaderButtons,
         },
-        States, Style,
     },
-    ui::{icons::Icon, main_page::central_panel::EntityDetailsHeaderButtons},
 };
 
-pub struct RequestDetailsPanel {}
+pub struct RequestDetailsPanel {
+    auth_details: AuthDetails,
+    jwt_view: JwtView,
+}
 
 impl RequestDetailsPanel {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            auth_details: AuthDetails::new(),
+            jwt_view: JwtView::new(),
+        }
     }
 
     pub fn update(&self, ui: &mut Ui, states: &mut States) {
@@ -59,7 +89,11 @@ impl RequestDetailsPanel {
 
                     let request = request.unwrap();
 
-                    if request.draft.protocot_is_ws() {
+                    if request.draft.protocot_is_ws()
+                        || request.draft.protocot_is_grpc()
+                        || request.draft.protocot_is_mqtt()
+                        || request.draft.protocot_is_udp()
+                    {
                         ui.radio_value(
                             &mut request.visible_details,
                             RequestDetails::Message,
@@ -81,12 +115,40 @@ impl RequestDetailsPanel {
                         "Headers",
                     );
 
+                    if request.draft.protocot_is_http() || request.draft.protocot_is_ws() {
+                        ui.radio_value(&mut request.visible_details, RequestDetails::Auth, "Auth");
+                    }
+
                     if request.draft.protocot_is_http() {
                         ui.radio_value(&mut request.visible_details, RequestDetails::Body, "Body");
                     }
 
                     ui.radio_value(&mut request.visible_details, RequestDetails::Setup, "Setup");
 
+                    if request.draft.protocot_is_http() || request.draft.protocot_is_ws() {
+                        ui.radio_value(
+                            &mut request.visible_details,
+                            RequestDetails::Captures,
+                            "Captures",
+                        );
+                    }
+
+                    if request.draft.protocot_is_http() {
+                        ui.radio_value(
+                            &mut request.visible_details,
+                            RequestDetails::Tests,
+                            "Tests",
+                        );
+                    }
+
+                    if request.draft.protocot_is_http() || request.draft.protocot_is_ws() {
+                        ui.radio_value(
+                            &mut request.visible_details,
+                            RequestDetails::Scripts,
+                            "Scripts",
+                        );
+                    }
+
                     ui.add(Separator::default().horizontal());
                 });
 
@@ -96,18 +158,285 @@ impl RequestDetailsPanel {
                 if let Some(request) = states.main_page.selected_request() {
                     match request.visible_details {
                         RequestDetails::Header => self.update_headers(ui, states),
+                        RequestDetails::Auth => self.update_auth(ui, states),
                         RequestDetails::Body => self.update_body(ui, states),
                         RequestDetails::QueryParams => self.update_query_params(ui, states),
                         RequestDetails::Message => self.update_message(ui, states),
                         RequestDetails::Setup => self.update_setup(ui, states),
+                        RequestDetails::Captures => self.update_captures(ui, states),
+                        RequestDetails::Tests => self.update_tests(ui, states),
+                        RequestDetails::Scripts => {
+                            let request = states.main_page.selected_request_mut().unwrap();
+                            if self.update_scripts(ui, &mut request.draft.scripts, &states.style) {
+                                request.is_changed = true;
+                            }
+                        }
                     };
                 }
             })
         });
     }
 
+    /// Draw Authorization settings
+    fn update_auth(&self, ui: &mut Ui, states: &mut States) {
+        let parent = states
+            .main_page
+            .selected_collection()
+            .map(|collection| (collection.draft.name.clone(), collection.draft.auth.clone()));
+        let variables = states.variables();
+
+        ScrollArea::vertical().show(ui, |ui| {
+            let request = states.main_page.selected_request_mut().unwrap();
+
+            if self.auth_details.update(
+                ui,
+                &mut request.draft.auth,
+                AuthScope {
+                    is_request: true,
+                    parent: parent.as_ref().map(|(name, auth)| (name.as_str(), auth)),
+                    variables: &variables,
+                },
+                &states.style,
+                &states.events,
+            ) {
+                request.is_changed = true
+            }
+        });
+    }
+
+    /// Draw response values stored into variables
+    fn update_captures(&self, ui: &mut Ui, states: &mut States) {
+        let request = states.main_page.selected_request_mut().unwrap();
+        let style = &states.style;
+        let mut is_changed = false;
+        let mut remove = None;
+
+        ui.add(Label::new(
+            "Values extracted from every response into variables, for following requests",
+        ));
+        ui.add_space(10.);
+
+        ScrollArea::vertical().show(ui, |ui| {
+            ui.style_mut().spacing.item_spacing = vec2(2., 2.);
+
+            for (i, capture) in request.draft.captures.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.style_mut().visuals.extreme_bg_color = style.color_secondary();
+
+                    ComboBox::from_id_salt(format!("capture-source-{i}"))
+                        .selected_text(capture.source.to_string())
+                        .width(80.)
+                        .show_ui(ui, |ui| {
+                            for source in [
+                                CaptureSource::JSONPATH,
+                                CaptureSource::HEADER,
+                                CaptureSource::COOKIE,
+                                CaptureSource::REGEX,
+                            ] {
+                                let text = source.to_string();
+                                is_changed |= ui
+                                    .selectable_value(&mut capture.source, source, text)
+                                    .changed();
+                            }
+                        });
+
+                    let hint = match capture.source {
+                        CaptureSource::JSONPATH => "$.data.token",
+                        CaptureSource::HEADER => "header name",
+                        CaptureSource::COOKIE => "cookie name",
+                        CaptureSource::REGEX => "token=(\\w+)",
+                    };
+                    is_changed |= ui
+                        .add(
+                            TextEdit::singleline(&mut capture.expression)
+                                .hint_text(hint)
+                                .text_color(style.color_main())
+                                .font(style.fonts.textedit_small())
+                                .desired_width(ui.available_width() / 2.),
+                        )
+                        .changed();
+
+                    ui.add(Label::new("into"));
+
+                    is_changed |= ui
+                        .add(
+                            TextEdit::singleline(&mut capture.variable)
+                                .hint_text("variable")
+                                .text_color(style.color_main())
+                                .font(style.fonts.textedit_small())
+                                .desired_width(ui.available_width() - 140.),
+                        )
+                        .changed();
+
+                    ComboBox::from_id_salt(format!("capture-scope-{i}"))
+                        .selected_text(capture.scope.to_string())
+                        .width(90.)
+                        .show_ui(ui, |ui| {
+                            for scope in [CaptureScope::ENVIRONMENT, CaptureScope::COLLECTION] {
+                                let text = scope.to_string();
+                                is_changed |= ui
+                                    .selectable_value(&mut capture.scope, scope, text)
+                                    .changed();
+                            }
+                        });
+
+                    if ui
+                        .add(Button::new("x").fill(style.color_danger()))
+                        .clicked()
+                    {
+                        remove = Some(i);
+                    }
+                });
+            }
+
+            ui.add_space(5.);
+            if ui.button("Add capture").clicked() {
+                request.draft.captures.push(Capture::default());
+                is_changed = true;
+            }
+        });
+
+        if let Some(i) = remove {
+            request.draft.captures.remove(i);
+            is_changed = true;
+        }
+        if is_changed {
+            request.is_changed = true;
+        }
+    }
+
+    /// Draw assertions checked on every response
+    fn update_tests(&self, ui: &mut Ui, states: &mut States) {
+        let request = states.main_page.selected_request_mut().unwrap();
+        let style = &states.style;
+        let mut is_changed = false;
+        let mut remove = None;
+
+        // Tab stays selected after protocol change
+        if !request.draft.protocot_is_http() {
+            ui.add(Label::new(
+                RichText::new(format!(
+                    "Assertions checked only for HTTP responses, {} ignored for this protocol",
+                    request.draft.assertions.len()
+                ))
+                .color(style.color_danger()),
+            ));
+            return;
+        }
+
+        ui.add(Label::new(
+            "Assertions checked on every response, results shown next to response status",
+        ));
+        ui.add_space(10.);
+
+        ScrollArea::vertical().show(ui, |ui| {
+            ui.style_mut().spacing.item_spacing = vec2(2., 2.);
+
+            for (i, assertion) in request.draft.assertions.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.style_mut().visuals.extreme_bg_color = style.color_secondary();
+
+                    ComboBox::from_id_salt(format!("assertion-kind-{i}"))
+                        .selected_text(assertion.kind.to_string())
+                        .width(130.)
+                        .show_ui(ui, |ui| {
+                            for kind in AssertionKind::ALL {
+                                let text = kind.to_string();
+                                is_changed |= ui
+                                    .selectable_value(&mut assertion.kind, kind, text)
+                                    .changed();
+                            }
+                        });
+
+                    let target_hint = assertion.kind.target_hint();
+                    let expected_hint = assertion.kind.expected_hint();
+                    let width = if target_hint.is_some() && expected_hint.is_some() {
+                        (ui.available_width() - 30.) / 2.
+                    } else {
+                        ui.available_width() - 30.
+                    };
+
+                    if let Some(hint) = target_hint {
+                        is_changed |= ui
+                            .add(
+                                TextEdit::singleline(&mut assertion.target)
+                                    .hint_text(hint)
+                                    .text_color(style.color_main())
+                                    .font(style.fonts.textedit_small())
+                                    .desired_width(width),
+                            )
+                            .changed();
+                    }
+                    if let Some(hint) = expected_hint {
+                        is_changed |= ui
+                            .add(
+                                TextEdit::singleline(&mut assertion.expected)
+                                    .hint_text(hint)
+                                    .text_color(style.color_main())
+                                    .font(style.fonts.textedit_small())
+                                    .desired_width(width),
+                            )
+                            .changed();
+                    }
+
+                    if ui
+                        .add(Button::new("x").fill(style.color_danger()))
+                        .clicked()
+                    {
+                        remove = Some(i);
+                    }
+                });
+            }
+
+            ui.add_space(5.);
+            if ui.button("Add assertion").clicked() {
+                request.draft.assertions.push(Assertion::default());
+                is_changed = true;
+            }
+        });
+
+        if let Some(i) = remove {
+            request.draft.assertions.remove(i);
+            is_changed = true;
+        }
+        if is_changed {
+            request.is_changed = true;
+        }
+    }
+
+    /// Draw Rhai scripts editors, of request or collection.
+    /// Return is_changed state
+    pub fn update_scripts(&self, ui: &mut Ui, scripts: &mut Scripts, style: &Style) -> bool {
+        let mut is_changed = false;
+
+        ScrollArea::vertical().id_salt("scripts").show(ui, |ui| {
+            ui.style_mut().visuals.extreme_bg_color = style.color_secondary();
+
+            for (label, script) in [
+                ("Pre-request:", &mut scripts.pre_request),
+                ("Post-response:", &mut scripts.post_response),
+            ] {
+                ui.add(Label::new(label).selectable(false))
+                    .on_hover_text(scripts::FUNCTIONS);
+                is_changed |= ui
+                    .add(
+                        TextEdit::multiline(script)
+                            .code_editor()
+                            .desired_rows(8)
+                            .desired_width(ui.available_width() - 20.)
+                            .text_color(style.color_main()),
+                    )
+                    .changed();
+                ui.add_space(10.);
+            }
+        });
+
+        is_changed
+    }
+
     /// Draw Setup Settings
     fn update_setup(&self, ui: &mut Ui, states: &mut States) {
+        let id_salt = states.main_page.selected_request_salt();
         ScrollArea::vertical().show(ui, |ui| {
             let request = states.main_page.selected_request_mut().unwrap();
 
@@ -115,10 +444,184 @@ impl RequestDetailsPanel {
                 Protocol::HTTP | Protocol::HTTPS => self.update_setup_http(ui, states),
 
                 Protocol::WS | Protocol::WSS => self.update_setup_ws(ui, states),
+
+                Protocol::GRPC | Protocol::GRPCS => self.update_setup_grpc(ui, states, id_salt),
+
+                Protocol::MQTT | Protocol::MQTTS => self.update_setup_mqtt(ui, states),
+
+                Protocol::UDP => self.update_setup_udp(ui, states),
             }
         });
     }
 
+    fn update_setup_grpc(&self, ui: &mut Ui, states: &mut States, id_salt: String) {
+        let request = states.main_page.selected_request_mut().unwrap();
+        let pool = request.grpc_pool.lock().unwrap().clone();
+        let setup = request.draft.setup.grpc_mut().unwrap();
+
+        Frame::new().show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.add(Label::new(states.style.fonts.label_text("Services from:")));
+
+                ui.add_space(50.);
+                let proto_files_resp =
+                    ui.radio_value(&mut setup.use_reflection, false, "Proto files");
+                let reflection_resp =
+                    ui.radio_value(&mut setup.use_reflection, true, "Server reflection");
+                if proto_files_resp.changed() || reflection_resp.changed() {
+                    request.is_changed = true
+                };
+
+                ui.add_space(ui.available_width());
+            })
+        });
+
+        ui.add_space(10.);
+
+        if !setup.use_reflection {
+            Frame::new().show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(Label::new(states.style.fonts.label_text("Proto files:")));
+
+                    ui.add_space(20.);
+
+                    if ui.add(Button::new("Browse")).clicked() {
+                        let files = FileDialog::new()
+                            .set_directory("./")
+                            .add_filter("proto", &["proto"])
+                            .pick_files();
+                        if let Some(files) = files {
+                            for file in files {
+                                let file = file.to_string_lossy().to_string();
+                                if !setup.proto_files.contains(&file) {
+                                    setup.proto_files.push(file);
+                                }
+                            }
+                            request.is_changed = true;
+                        }
+                    }
+                });
+
+                let mut remove_index = None;
+                for (index, file) in setup.proto_files.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui.button("x").clicked() {
+                            remove_index = Some(index);
+                        }
+                        ui.add(Label::new(file.as_str()));
+                    });
+                }
+                if let Some(index) = remove_index {
+                    setup.proto_files.remove(index);
+                    request.is_changed = true;
+                }
+            });
+
+            ui.add_space(10.);
+
+            Frame::new().show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(Label::new(states.style.fonts.label_text("Import paths:")));
+
+                    ui.add_space(20.);
+
+                    if ui
+                        .add(
+                            TextEdit::singleline(&mut setup.import_paths)
+                                .hint_text("path/one;path/two")
+                                .desired_width(ui.available_width() - 20.),
+                        )
+                        .on_hover_text("Directories of selected files are used too")
+                        .changed()
+                    {
+                        request.is_changed = true
+                    };
+                });
+            });
+
+            ui.add_space(10.);
+        }
+
+        let mut load_services = false;
+        Frame::new().show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.add(Label::new(states.style.fonts.label_text("Method:")));
+
+                ui.add_space(50.);
+
+                let services = match &pool {
+                    Some(pool) => grpc::services(pool),
+                    None => vec![],
+                };
+
+                ComboBox::from_id_salt(format!("grpc-service-{id_salt}"))
+                    .selected_text(if setup.service.is_empty() {
+                        "<service>"
+                    } else {
+                        setup.service.as_str()
+                    })
+                    .show_ui(ui, |ui| {
+                        for (service, _) in &services {
+                            if ui
+                                .selectable_label(setup.service == *service, service)
+                                .clicked()
+                                && setup.service != *service
+                            {
+                                setup.service = service.clone();
+                                setup.method = "".into();
+                                request.is_changed = true;
+                            }
+                        }
+                    });
+
+                let methods = services
+                    .iter()
+                    .find(|(service, _)| *service == setup.service)
+                    .map(|(_, methods)| methods.clone())
+                    .unwrap_or_default();
+
+                ComboBox::from_id_salt(format!("grpc-method-{id_salt}"))
+                    .selected_text(if setup.method.is_empty() {
+                        "<method>"
+                    } else {
+                        setup.method.as_str()
+                    })
+                    .show_ui(ui, |ui| {
+                        for method in &methods {
+                            let label = format!(
+                                "{} ({})",
+                                method.name(),
+                                GrpcCallKind::from_method(method)
+                            );
+                            if ui
+                                .selectable_label(setup.method == method.name(), label)
+                                .clicked()
+                                && setup.method != method.name()
+                            {
+                                setup.method = method.name().to_string();
+                                request.is_changed = true;
+                            }
+                        }
+                    });
+
+                load_services = ui
+                    .button("Load")
+                    .on_hover_text("Load services to select method")
+                    .clicked();
+
+                if pool.is_some() {
+                    ui.add(Label::new(
+                        RichText::new("services loaded").color(states.style.color_success()),
+                    ));
+                }
+            });
+        });
+
+        if load_services {
+            request.load_grpc_services(Arc::clone(&states.events));
+        }
+    }
+
     fn update_setup_http(&self, ui: &mut Ui, states: &mut States) {
         let request = states.main_page.selected_request_mut().unwrap();
         let setup = request.draft.setup.http_mut().unwrap();
@@ -291,11 +794,350 @@ impl RequestDetailsPanel {
         });
     }
 
+    fn update_setup_mqtt(&self, ui: &mut Ui, states: &mut States) {
+        let request = states.main_page.selected_request_mut().unwrap();
+
+        let setup = request.draft.setup.mqtt_mut().unwrap();
+
+        Frame::new().show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.add(Label::new(states.style.fonts.label_text("MQTT version:")));
+
+                ui.add_space(50.);
+                ui.menu_button(setup.version.to_string(), |ui| {
+                    ui.style_mut().spacing.button_padding = vec2(5., 5.);
+
+                    for version in [MqttVersion::V311, MqttVersion::V5] {
+                        if setup.version != version && ui.button(version.to_string()).clicked() {
+                            setup.version = version;
+                            request.is_changed = true
+                        }
+                    }
+                });
+
+                ui.add_space(ui.available_width());
+            })
+        });
+
+        ui.add_space(10.);
+
+        Frame::new().show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.add(Label::new(states.style.fonts.label_text("Client id:")));
+
+                ui.add_space(20.);
+
+                if ui
+                    .add(
+                        TextEdit::singleline(&mut setup.client_id)
+                            .hint_text("generated on connect")
+                            .desired_width(ui.available_width() - 20.),
+                    )
+                    .changed()
+                {
+                    request.is_changed = true
+                };
+            });
+        });
+
+        ui.add_space(10.);
+
+        Frame::new().show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.add(Label::new(states.style.fonts.label_text("Username:")));
+
+                ui.add_space(20.);
+
+                if ui
+                    .add(
+                        TextEdit::singleline(&mut setup.username)
+                            .desired_width(ui.available_width() - 20.),
+                    )
+                    .changed()
+                {
+                    request.is_changed = true
+                };
+            });
+        });
+
+        ui.add_space(10.);
+
+        Frame::new().show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.add(Label::new(states.style.fonts.label_text("Password:")));
+
+                ui.add_space(20.);
+
+                if ui
+                    .add(
+                        TextEdit::singleline(&mut setup.password)
+                            .password(true)
+                            .desired_width(ui.available_width() - 20.),
+                    )
+                    .changed()
+                {
+                    request.is_changed = true
+                };
+            });
+        });
+
+        ui.add_space(10.);
+
+        Frame::new().show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.add(Label::new(states.style.fonts.label_text("Keep alive (s):")));
+
+                ui.add_space(20.);
+
+                let initial_value = setup.keep_alive.clone();
+                if ui
+                    .add(
+                        TextEdit::singleline(&mut setup.keep_alive)
+                            .char_limit(5)
+                            .desired_width(50.),
+                    )
+                    .on_hover_text("0 - disabled")
+                    .changed()
+                {
+                    match setup.keep_alive.parse::<u64>() {
+                        Ok(val) => {
+                            setup.keep_alive = val.to_string();
+                            request.is_changed = true
+                        }
+                        Err(_) => {
+                            if setup.keep_alive.is_empty() {
+                                setup.keep_alive = "0".into();
+                                request.is_changed = true
+                            } else {
+                                setup.keep_alive = initial_value;
+                            }
+                        }
+                    }
+                };
+
+                ui.add_space(20.);
+
+                if ui
+                    .checkbox(&mut setup.clean_session, "Clean session")
+                    .on_hover_text("Clean start for MQTT 5")
+                    .changed()
+                {
+                    request.is_changed = true
+                };
+            });
+        });
+    }
+
+    fn update_setup_udp(&self, ui: &mut Ui, states: &mut States) {
+        let request = states.main_page.selected_request_mut().unwrap();
+
+        let setup = request.draft.setup.udp_mut().unwrap();
+
+        Frame::new().show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.add(Label::new(
+                    states.style.fonts.label_text("Reply window (ms):"),
+                ));
+
+                ui.add_space(20.);
+
+                let initial_value = setup.reply_window.clone();
+                if ui
+                    .add(
+                        TextEdit::singleline(&mut setup.reply_window)
+                            .char_limit(6)
+                            .desired_width(60.),
+                    )
+                    .on_hover_text("Replies received within this time after send are shown")
+                    .changed()
+                {
+                    match setup.reply_window.parse::<u64>() {
+                        Ok(val) => {
+                            setup.reply_window = val.to_string();
+                            request.is_changed = true
+                        }
+                        Err(_) => {
+                            if setup.reply_window.is_empty() {
+                                setup.reply_window = "0".into();
+                                request.is_changed = true
+                            } else {
+                                setup.reply_window = initial_value;
+                            }
+                        }
+                    }
+                };
+            });
+        });
+    }
+
     fn update_setup_ws(&self, ui: &mut Ui, states: &mut States) {
         let request = states.main_page.selected_request_mut().unwrap();
 
         let setup = request.draft.setup.ws_mut().unwrap();
 
+        Frame::new().show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.add(Label::new(states.style.fonts.label_text("Mode:")));
+
+                ui.add_space(50.);
+                ui.menu_button(setup.mode.to_string(), |ui| {
+                    ui.style_mut().spacing.button_padding = vec2(5., 5.);
+
+                    for mode in [
+                        WsMode::RAW,
+                        WsMode::SOCKETIO,
+                        WsMode::STOMP,
+                        WsMode::GRAPHQL,
+                        WsMode::JSONRPC,
+                    ] {
+                        if setup.mode != mode && ui.button(mode.to_string()).clicked() {
+                            setup.mode = mode;
+                            request.is_changed = true
+                        }
+                    }
+                });
+
+                ui.add_space(ui.available_width());
+            })
+        });
+
+        ui.add_space(10.);
+
+        if setup.mode == WsMode::SOCKETIO {
+            Frame::new().show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(Label::new(states.style.fonts.label_text("Namespace:")));
+
+                    ui.add_space(20.);
+
+                    if ui
+                        .add(
+                            TextEdit::singleline(&mut setup.socket_io.namespace)
+                                .desired_width(ui.available_width() - 20.),
+                        )
+                        .changed()
+                    {
+                        request.is_changed = true
+                    };
+                });
+            });
+
+            ui.add_space(10.);
+
+            Frame::new().show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(Label::new(states.style.fonts.label_text("Auth (JSON):")));
+
+                    ui.add_space(20.);
+
+                    if ui
+                        .add(
+                            TextEdit::singleline(&mut setup.socket_io.auth)
+                                .hint_text("{\"token\": \"...\"}")
+                                .desired_width(ui.available_width() - 20.),
+                        )
+                        .on_hover_text("Optional payload sent with namespace connect")
+                        .changed()
+                    {
+                        request.is_changed = true
+                    };
+                });
+            });
+
+            ui.add_space(10.);
+        }
+
+        if setup.mode == WsMode::GRAPHQL {
+            Frame::new().show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(Label::new(
+                        states.style.fonts.label_text("Connection params (JSON):"),
+                    ));
+
+                    ui.add_space(20.);
+
+                    if ui
+                        .add(
+                            TextEdit::singleline(&mut setup.graphql.connection_params)
+                                .hint_text("{\"Authorization\": \"...\"}")
+                                .desired_width(ui.available_width() - 20.),
+                        )
+                        .on_hover_text("Optional payload sent with connection_init")
+                        .changed()
+                    {
+                        request.is_changed = true
+                    };
+                });
+            });
+
+            ui.add_space(10.);
+        }
+
+        if setup.mode == WsMode::STOMP {
+            Frame::new().show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(Label::new(states.style.fonts.label_text("Host:")));
+
+                    ui.add_space(20.);
+
+                    if ui
+                        .add(
+                            TextEdit::singleline(&mut setup.stomp.host)
+                                .hint_text("host from URI")
+                                .desired_width(ui.available_width() - 20.),
+                        )
+                        .on_hover_text("Virtual host sent in CONNECT frame")
+                        .changed()
+                    {
+                        request.is_changed = true
+                    };
+                });
+            });
+
+            ui.add_space(10.);
+
+            Frame::new().show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(Label::new(states.style.fonts.label_text("Login:")));
+
+                    ui.add_space(20.);
+
+                    if ui
+                        .add(
+                            TextEdit::singleline(&mut setup.stomp.login)
+                                .desired_width(ui.available_width() - 20.),
+                        )
+                        .changed()
+                    {
+                        request.is_changed = true
+                    };
+                });
+            });
+
+            ui.add_space(10.);
+
+            Frame::new().show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(Label::new(states.style.fonts.label_text("Passcode:")));
+
+                    ui.add_space(20.);
+
+                    if ui
+                        .add(
+                            TextEdit::singleline(&mut setup.stomp.passcode)
+                                .password(true)
+                                .desired_width(ui.available_width() - 20.),
+                        )
+                        .changed()
+                    {
+                        request.is_changed = true
+                    };
+                });
+            });
+
+            ui.add_space(10.);
+        }
+
         Frame::new().show(ui, |ui| {
             ui.horizontal(|ui| {
                 ui.add(Label::new(
@@ -335,41 +1177,125 @@ impl RequestDetailsPanel {
 
         Frame::new().show(ui, |ui| {
             ui.horizontal(|ui| {
-                ui.add(Label::new(
-                    states.style.fonts.label_text("Reconnection attempts:"),
-                ));
+                ui.add(Label::new(
+                    states.style.fonts.label_text("Reconnection attempts:"),
+                ));
+
+                ui.add_space(20.);
+
+                let initial_value = setup.reconnection_attempts.clone();
+                if ui
+                    .add(
+                        TextEdit::singleline(&mut setup.reconnection_attempts)
+                            .char_limit(2)
+                            .desired_width(20.),
+                    )
+                    .changed()
+                {
+                    match setup.reconnection_attempts.parse::<usize>() {
+                        Ok(val) => {
+                            setup.reconnection_attempts = val.to_string();
+                            request.is_changed = true
+                        }
+                        Err(_) => {
+                            if setup.reconnection_attempts.len() == 0 {
+                                setup.reconnection_attempts = "0".into();
+                                request.is_changed = true
+                            } else {
+                                setup.reconnection_attempts = initial_value;
+                            }
+                        }
+                    }
+                };
+            });
+        });
+
+        ui.add_space(10.);
+
+        Frame::new().show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.add(Label::new(states.style.fonts.label_text("Close code:")));
+
+                ui.add_space(20.);
+
+                let initial_value = setup.close_code.clone();
+                if ui
+                    .add(
+                        TextEdit::singleline(&mut setup.close_code)
+                            .char_limit(4)
+                            .desired_width(40.),
+                    )
+                    .on_hover_text("Code sent in Close frame on termination.\n1000 - Normal Closure, 1001 - Going Away, 3000-4999 - application codes.\nReserved 1004, 1005, 1006, 1015 and codes below 1000 could not be sent")
+                    .changed()
+                {
+                    match setup.close_code.parse::<u16>() {
+                        Ok(val) => {
+                            setup.close_code = val.to_string();
+                            request.is_changed = true
+                        }
+                        Err(_) => {
+                            if setup.close_code.is_empty() {
+                                setup.close_code = "1000".into();
+                                request.is_changed = true
+                            } else {
+                                setup.close_code = initial_value;
+                            }
+                        }
+                    }
+                };
+
+                // Partly typed code allowed, Normal Closure sent instead
+                let typed = setup.close_code.parse::<u16>().unwrap_or(1000);
+                if is_sendable_close_code(typed) {
+                    ui.add(Label::new(
+                        states
+                            .style
+                            .fonts
+                            .label_text(close_code_description(typed)),
+                    ));
+                } else {
+                    ui.add(Label::new(
+                        RichText::new(format!(
+                            "{typed} could not be sent, 1000 used instead"
+                        ))
+                        .color(states.style.color_danger()),
+                    ));
+                }
+            });
+        });
+
+        ui.add_space(10.);
+
+        Frame::new().show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.add(Label::new(states.style.fonts.label_text("Close reason:")));
 
                 ui.add_space(20.);
 
-                let initial_value = setup.reconnection_attempts.clone();
+                // Limit is in bytes, so multibyte text cut after edit
                 if ui
                     .add(
-                        TextEdit::singleline(&mut setup.reconnection_attempts)
-                            .char_limit(2)
-                            .desired_width(20.),
+                        TextEdit::singleline(&mut setup.close_reason)
+                            .desired_width(ui.available_width() - 20.),
                     )
+                    .on_hover_text(format!("Up to {MAX_CLOSE_REASON_BYTES} bytes"))
                     .changed()
                 {
-                    match setup.reconnection_attempts.parse::<usize>() {
-                        Ok(val) => {
-                            setup.reconnection_attempts = val.to_string();
-                            request.is_changed = true
-                        }
-                        Err(_) => {
-                            if setup.reconnection_attempts.len() == 0 {
-                                setup.reconnection_attempts = "0".into();
-                                request.is_changed = true
-                            } else {
-                                setup.reconnection_attempts = initial_value;
-                            }
-                        }
-                    }
+                    setup.close_reason =
+                        truncate_bytes(&setup.close_reason, MAX_CLOSE_REASON_BYTES).to_string();
+                    request.is_changed = true
                 };
             });
         });
     }
 
     fn update_message(&self, ui: &mut Ui, states: &mut States) {
+        let parent = states
+            .main_page
+            .selected_collection()
+            .map(|collection| collection.draft.clone());
+        let variables = states.variables();
+
         ui.group(|ui| {
             let request = states.main_page.selected_request_mut().unwrap();
             let send_btn_response = ui.horizontal(|ui| {
@@ -378,39 +1304,222 @@ impl RequestDetailsPanel {
                     request.prettier_ws_message();
                 };
 
+                if request.draft.protocot_is_grpc()
+                    && ui
+                        .button("Template")
+                        .on_hover_text("Replace message with all fields of selected method input")
+                        .clicked()
+                {
+                    request.grpc_message_template(Arc::clone(&states.events));
+                }
+
+                if let Some(setup) = request.draft.setup.udp_mut() {
+                    ui.menu_button(setup.payload_format.to_string(), |ui| {
+                        for format in [UdpPayloadFormat::TEXT, UdpPayloadFormat::HEX] {
+                            if setup.payload_format != format
+                                && ui.button(format.to_string()).clicked()
+                            {
+                                setup.payload_format = format;
+                                request.is_changed = true;
+                            }
+                        }
+                    })
+                    .response
+                    .on_hover_text("Hex: bytes like 0a 1b ff, replies shown as hex dump");
+                }
+
+                if let Some(setup) = request.draft.setup.mqtt_mut() {
+                    ui.menu_button(setup.command.to_string(), |ui| {
+                        for command in [
+                            MqttCommand::PUBLISH,
+                            MqttCommand::SUBSCRIBE,
+                            MqttCommand::UNSUBSCRIBE,
+                        ] {
+                            if setup.command != command && ui.button(command.to_string()).clicked()
+                            {
+                                setup.command = command;
+                                request.is_changed = true;
+                            }
+                        }
+                    });
+
+                    ui.add(Label::new("Topic:").selectable(false));
+                    if ui
+                        .add(
+                            TextEdit::singleline(&mut setup.topic)
+                                .hint_text("sensors/+/temperature")
+                                .desired_width(150.),
+                        )
+                        .on_hover_text("Topic to publish, or topic filter to subscribe with + and # wildcards")
+                        .changed()
+                    {
+                        request.is_changed = true;
+                    };
+
+                    if setup.command != MqttCommand::UNSUBSCRIBE {
+                        ui.menu_button(format!("QoS {}", setup.qos), |ui| {
+                            for qos in 0..=2 {
+                                if setup.qos != qos && ui.button(format!("QoS {qos}")).clicked() {
+                                    setup.qos = qos;
+                                    request.is_changed = true;
+                                }
+                            }
+                        });
+                    }
+
+                    if setup.command == MqttCommand::PUBLISH
+                        && ui.checkbox(&mut setup.retain, "Retain").changed()
+                    {
+                        request.is_changed = true;
+                    }
+                }
+
+                if let Some(setup) = request.draft.setup.ws_mut() {
+                    if setup.mode == WsMode::SOCKETIO {
+                        ui.add(Label::new("Event:").selectable(false));
+                        if ui
+                            .add(
+                                TextEdit::singleline(&mut setup.socket_io.event)
+                                    .desired_width(150.),
+                            )
+                            .on_hover_text("Message sent as event arguments. JSON array spreads into multiple arguments")
+                            .changed()
+                        {
+                            request.is_changed = true;
+                        };
+                    }
+
+                    if setup.mode == WsMode::STOMP {
+                        ui.menu_button(setup.stomp.command.to_string(), |ui| {
+                            for command in [
+                                StompCommand::SEND,
+                                StompCommand::SUBSCRIBE,
+                                StompCommand::UNSUBSCRIBE,
+                            ] {
+                                if setup.stomp.command != command
+                                    && ui.button(command.to_string()).clicked()
+                                {
+                                    setup.stomp.command = command;
+                                    request.is_changed = true;
+                                }
+                            }
+                        });
+
+                        ui.add(Label::new("Destination:").selectable(false));
+                        if ui
+                            .add(
+                                TextEdit::singleline(&mut setup.stomp.destination)
+                                    .hint_text("/topic/...")
+                                    .desired_width(150.),
+                            )
+                            .on_hover_text("Subscription id defaults to destination")
+                            .changed()
+                        {
+                            request.is_changed = true;
+                        };
+                    }
+                }
+
                 ui.add_space(ui.available_width() - 30.);
 
                 ui.add(Button::new("Send").fill(states.style.color_success()))
                     .clicked()
             });
             if send_btn_response.inner {
-                request.go(Arc::clone(&states.events), false);
+                request.go(
+                    Arc::clone(&states.events),
+                    false,
+                    parent.as_ref(),
+                    &variables,
+                );
             };
-            if self
-                .update_counted_textedit(ui, &mut request.draft.message, &states.style)
-                .is_some()
-            {
+            if let Some(setup) = request.draft.setup.ws_mut() {
+                if setup.mode == WsMode::STOMP {
+                    ui.horizontal(|ui| {
+                        ui.add(Label::new("Frame headers:").selectable(false));
+                        if ui
+                            .add(
+                                TextEdit::multiline(&mut setup.stomp.headers)
+                                    .hint_text("key:value per line")
+                                    .desired_rows(2)
+                                    .desired_width(ui.available_width() - 20.),
+                            )
+                            .changed()
+                        {
+                            request.is_changed = true;
+                        };
+                    });
+                }
+            }
+
+            // GraphQL subscriptions and JSON-RPC calls use body instead of raw message
+            let mode = match request.draft.setup.ws() {
+                Some(setup) => setup.mode.clone(),
+                None => WsMode::RAW,
+            };
+            let is_message_changed = match mode {
+                WsMode::GRAPHQL => self.update_graphql_editor(
+                    ui,
+                    request,
+                    &variables,
+                    parent.as_ref(),
+                    &states.style,
+                    &states.events,
+                ),
+                WsMode::JSONRPC => {
+                    self.update_json_rpc_editor(ui, request, &variables, &states.style)
+                }
+                _ => self.update_counted_textedit(
+                    ui,
+                    &mut request.draft.message,
+                    &variables,
+                    &states.style,
+                    None,
+                ),
+            };
+            if is_message_changed.is_some() {
                 request.is_changed = true;
             };
         });
     }
 
-    /// Generic Draw of text edit with counted lines
+    /// Generic Draw of text edit with counted lines.
+    /// variables - to highlight {{name}} placeholders
     fn update_counted_textedit(
         &self,
         ui: &mut Ui,
         counted_text: &mut CountedText,
+        variables: &[Header],
         style: &Style,
+        id: Option<Id>,
     ) -> Option<()> {
         let mut is_changed = None;
+        let highlight = VariablesHighlight::new(
+            variables,
+            style,
+            FontSelection::Style(egui::TextStyle::Monospace),
+            style.color_main(),
+        )
+        .multiline();
+        let mut layouter = |ui: &Ui, text: &dyn TextBuffer, wrap_width: f32| {
+            highlight.layout(ui, text, wrap_width)
+        };
+        let has_placeholders = counted_text.message.contains("{{");
+
         ScrollArea::vertical().show(ui, |ui| {
             Frame::new().inner_margin(Margin::same(5)).show(ui, |ui| {
                 ui.horizontal(|ui| {
                     ui.style_mut().visuals.extreme_bg_color = style.color_secondary();
-                    let textedit = TextEdit::multiline(&mut counted_text.message)
+                    let mut textedit = TextEdit::multiline(&mut counted_text.message)
                         .min_size(vec2(ui.available_width() - 20., ui.available_height()))
                         .code_editor()
                         .text_color(style.color_main());
+                    if has_placeholders {
+                        textedit = textedit.layouter(&mut layouter);
+                    }
+                    if let Some(id) = id {
+                        textedit = textedit.id(id);
+                    }
 
                     ui.style_mut().spacing.item_spacing = vec2(2., 3.);
                     ui.vertical(|ui| {
@@ -448,6 +1557,7 @@ impl RequestDetailsPanel {
     }
 
     fn update_query_params(&self, ui: &mut Ui, states: &mut States) {
+        let variables = states.variables();
         let request = states.main_page.selected_request_mut();
 
         if request.is_none() {
@@ -461,6 +1571,7 @@ impl RequestDetailsPanel {
                 ui,
                 &mut request.draft.query_params,
                 Some(&mut request.new_header),
+                &variables,
                 &states.style,
             )
             .is_some()
@@ -475,56 +1586,283 @@ impl RequestDetailsPanel {
         let request = states.main_page.selected_request_mut().unwrap();
         ui.group(|ui| {
             ui.horizontal(|ui| {
-                if ui
-                    .add(
-                        Button::new("Raw")
-                            .selected(request.visible_body == RequestBodyDetails::Raw),
-                    )
-                    .clicked()
-                {
-                    request.visible_body = RequestBodyDetails::Raw
+                for (mode, label) in [
+                    (BodyMode::RAW, "Raw"),
+                    (BodyMode::FORMDATA, "Form Data"),
+                    (BodyMode::BINARY, "Binary"),
+                    (BodyMode::GRAPHQL, "GraphQL"),
+                    (BodyMode::JSONRPC, "JSON-RPC"),
+                ] {
+                    if ui
+                        .add(Button::new(label).selected(request.draft.body.mode == mode))
+                        .clicked()
+                        && request.draft.body.mode != mode
+                    {
+                        request.draft.body.mode = mode;
+                        request.is_changed = true;
+                    }
                 }
+                ui.add_space(ui.available_width());
+            });
+        });
+
+        match request.draft.body.mode {
+            BodyMode::RAW => self.update_body_raw(ui, states),
+            BodyMode::FORMDATA => self.update_body_formdata(ui, states),
+            BodyMode::BINARY => self.update_body_binary(ui, states),
+            BodyMode::GRAPHQL => self.update_body_graphql(ui, states),
+            BodyMode::JSONRPC => self.update_body_json_rpc(ui, states),
+        }
+    }
+
+    /// Draw JSON-RPC body
+    fn update_body_json_rpc(&self, ui: &mut Ui, states: &mut States) {
+        let variables = states.variables();
+        let request = states.main_page.selected_request_mut().unwrap();
+        if self
+            .update_json_rpc_editor(ui, request, &variables, &states.style)
+            .is_some()
+        {
+            request.is_changed = true;
+        }
+    }
+
+    /// Generic Draw of JSON-RPC call editor. Id and envelope generated on send
+    fn update_json_rpc_editor(
+        &self,
+        ui: &mut Ui,
+        request: &mut Request,
+        variables: &[Header],
+        style: &Style,
+    ) -> Option<()> {
+        let mut is_changed = None;
+        let json_rpc = &mut request.draft.body.json_rpc;
 
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(!json_rpc.batch, |ui| {
+                ui.add(Label::new("Method:").selectable(false));
                 if ui
                     .add(
-                        Button::new("Form Data")
-                            .selected(request.visible_body == RequestBodyDetails::FormData),
+                        TextEdit::singleline(&mut json_rpc.method)
+                            .hint_text("eth_blockNumber")
+                            .desired_width(200.),
                     )
-                    .clicked()
+                    .changed()
                 {
-                    request.visible_body = RequestBodyDetails::FormData
-                }
+                    is_changed = Some(());
+                };
+
                 if ui
-                    .add(
-                        Button::new("Binary")
-                            .selected(request.visible_body == RequestBodyDetails::Binary),
-                    )
-                    .clicked()
+                    .checkbox(&mut json_rpc.notification, "Notification")
+                    .on_hover_text("Sent without id, server wont reply")
+                    .changed()
                 {
-                    request.visible_body = RequestBodyDetails::Binary
-                }
-                ui.add_space(ui.available_width());
+                    is_changed = Some(());
+                };
             });
+
+            if ui
+                .checkbox(&mut json_rpc.batch, "Batch")
+                .on_hover_text("Params editor holds list of calls:\n[{\"method\": \"...\", \"params\": [...], \"notification\": false}]")
+                .changed()
+            {
+                is_changed = Some(());
+            };
+        });
+
+        ui.add(
+            Label::new(if json_rpc.batch {
+                "Calls (JSON array)"
+            } else {
+                "Params (JSON array or object)"
+            })
+            .selectable(false),
+        );
+        if self
+            .update_counted_textedit(ui, &mut json_rpc.params, variables, style, None)
+            .is_some()
+        {
+            is_changed = Some(());
+        };
+
+        is_changed
+    }
+
+    /// Draw GraphQL body
+    fn update_body_graphql(&self, ui: &mut Ui, states: &mut States) {
+        let parent = states
+            .main_page
+            .selected_collection()
+            .map(|collection| collection.draft.clone());
+        let variables = states.variables();
+        let request = states.main_page.selected_request_mut().unwrap();
+        if self
+            .update_graphql_editor(
+                ui,
+                request,
+                &variables,
+                parent.as_ref(),
+                &states.style,
+                &states.events,
+            )
+            .is_some()
+        {
+            request.is_changed = true;
+        }
+    }
+
+    /// Generic Draw of GraphQL query and variables editors.
+    /// Query editor suggests fields of fetched schema for selection set under cursor.
+    /// parent - collection of request, its auth and headers used on schema fetch
+    fn update_graphql_editor(
+        &self,
+        ui: &mut Ui,
+        request: &mut Request,
+        variables: &[Header],
+        parent: Option<&CollectionData>,
+        style: &Style,
+        events: &Arc<Mutex<Events>>,
+    ) -> Option<()> {
+        let mut is_changed = None;
+        let query_id = ui.make_persistent_id(format!("graphql-query-{}", request.id));
+
+        ui.horizontal(|ui| {
+            ui.style_mut().spacing.button_padding = vec2(5., 5.);
+            ui.add(Label::new("Operation:").selectable(false));
+            if ui
+                .add(
+                    TextEdit::singleline(&mut request.draft.body.graphql.operation_name)
+                        .hint_text("optional")
+                        .desired_width(150.),
+                )
+                .changed()
+            {
+                is_changed = Some(());
+            };
+
+            if ui
+                .button("Fetch schema")
+                .on_hover_text("Introspection query to enable autocomplete")
+                .clicked()
+            {
+                request.fetch_graphql_schema(Arc::clone(events), parent, variables);
+            }
+
+            if request.graphql_schema.lock().unwrap().is_some() {
+                ui.add(Label::new(
+                    RichText::new("schema loaded").color(style.color_success()),
+                ));
+            }
         });
 
-        match request.visible_body {
-            RequestBodyDetails::Raw => self.update_body_raw(ui, states),
-            RequestBodyDetails::FormData => self.update_body_formdata(ui, states),
-            RequestBodyDetails::Binary => self.update_body_binary(ui, states),
+        // Cursor from previous frame is enough to build suggestions
+        let cursor = TextEditState::load(ui.ctx(), query_id)
+            .and_then(|state| state.cursor.char_range())
+            .map(|range| range.primary.index);
+        let suggestions = match (cursor, request.graphql_schema.lock().unwrap().as_ref()) {
+            (Some(cursor), Some(schema)) => {
+                let before_cursor: String = request
+                    .draft
+                    .body
+                    .graphql
+                    .query
+                    .message
+                    .chars()
+                    .take(cursor)
+                    .collect();
+                schema
+                    .suggestions(&before_cursor)
+                    .map(|(prefix, suggestions)| (cursor, prefix, suggestions))
+            }
+            _ => None,
+        };
+
+        if let Some((cursor, prefix, suggestions)) = suggestions {
+            ui.horizontal_wrapped(|ui| {
+                for suggestion in suggestions {
+                    if ui.small_button(&suggestion).clicked() {
+                        let query = &mut request.draft.body.graphql.query;
+                        let start = cursor - prefix.chars().count();
+                        let mut message: String = query.message.chars().take(start).collect();
+                        message.push_str(&suggestion);
+                        message.extend(query.message.chars().skip(cursor));
+                        query.set(message);
+
+                        if let Some(mut state) = TextEditState::load(ui.ctx(), query_id) {
+                            let new_cursor = CCursor::new(start + suggestion.chars().count());
+                            state
+                                .cursor
+                                .set_char_range(Some(CCursorRange::one(new_cursor)));
+                            state.store(ui.ctx(), query_id);
+                        }
+                        ui.memory_mut(|memory| memory.request_focus(query_id));
+                        is_changed = Some(());
+                    }
+                }
+            });
         }
+
+        ui.columns(2, |columns| {
+            columns[0].add(Label::new("Query").selectable(false));
+            if self
+                .update_counted_textedit(
+                    &mut columns[0],
+                    &mut request.draft.body.graphql.query,
+                    variables,
+                    style,
+                    Some(query_id),
+                )
+                .is_some()
+            {
+                is_changed = Some(());
+            };
+
+            columns[1].add(Label::new("Variables (JSON)").selectable(false));
+            if self
+                .update_counted_textedit(
+                    &mut columns[1],
+                    &mut request.draft.body.graphql.variables,
+                    variables,
+                    style,
+                    None,
+                )
+                .is_some()
+            {
+                is_changed = Some(());
+            };
+        });
+
+        is_changed
     }
 
     /// Draw binary file select
     fn update_body_binary(&self, ui: &mut Ui, states: &mut States) {
+        let variables = states.variables();
+        let highlight = VariablesHighlight::new(
+            &variables,
+            &states.style,
+            FontSelection::Default,
+            states.style.color_main(),
+        );
+        let mut layouter = |ui: &Ui, text: &dyn TextBuffer, wrap_width: f32| {
+            highlight.layout(ui, text, wrap_width)
+        };
+
         ui.horizontal(|ui| {
             ui.group(|ui| {
                 let request = states.main_page.selected_request_mut().unwrap();
                 ui.style_mut().visuals.extreme_bg_color = states.style.color_secondary();
-                ui.add(
-                    TextEdit::singleline(&mut request.draft.body.binary_path)
-                        .desired_width(ui.available_width() - 65.)
-                        .text_color(states.style.color_main()),
-                );
+                if ui
+                    .add(
+                        TextEdit::singleline(&mut request.draft.body.binary_path)
+                            .desired_width(ui.available_width() - 65.)
+                            .text_color(states.style.color_main())
+                            .layouter(&mut layouter),
+                    )
+                    .changed()
+                {
+                    request.is_changed = true;
+                };
 
                 if ui.add(Button::new("Browse")).clicked() {
                     let file = FileDialog::new().set_directory("./").pick_file();
@@ -539,12 +1877,14 @@ impl RequestDetailsPanel {
 
     /// Draw form data
     fn update_body_formdata(&self, ui: &mut Ui, states: &mut States) {
+        let variables = states.variables();
         let request = states.main_page.selected_request_mut().unwrap();
         if self
             .update_form_data_table(
                 ui,
                 &mut request.draft.body.form_data,
                 Some(&mut request.new_body_form_field),
+                &variables,
                 &states.style,
             )
             .is_some()
@@ -555,9 +1895,16 @@ impl RequestDetailsPanel {
 
     /// Draw body part
     fn update_body_raw(&self, ui: &mut Ui, states: &mut States) {
+        let variables = states.variables();
         let request = states.main_page.selected_request_mut().unwrap();
         if self
-            .update_counted_textedit(ui, &mut request.draft.body.raw, &states.style)
+            .update_counted_textedit(
+                ui,
+                &mut request.draft.body.raw,
+                &variables,
+                &states.style,
+                None,
+            )
             .is_some()
         {
             request.is_changed = true;
@@ -643,9 +1990,19 @@ impl RequestDetailsPanel {
         ui: &mut Ui,
         items: &mut Vec<BodyFromData>,
         new_value: Option<&mut BodyFromData>,
+        variables: &[Header],
         style: &Style,
     ) -> Option<()> {
         let mut is_changes = None;
+        let highlight = VariablesHighlight::new(
+            variables,
+            style,
+            style.fonts.textedit_small(),
+            style.color_main(),
+        );
+        let mut layouter = |ui: &Ui, text: &dyn TextBuffer, wrap_width: f32| {
+            highlight.layout(ui, text, wrap_width)
+        };
         ScrollArea::vertical().show(ui, |ui| {
             ui.style_mut().spacing.item_spacing = vec2(2., 2.);
             ui.style_mut().visuals.widgets.active.corner_radius = CornerRadius::ZERO;
@@ -663,7 +2020,8 @@ impl RequestDetailsPanel {
                             let header_key_resp = ui.add(
                                 TextEdit::singleline(&mut items[i].key)
                                     .text_color(style.color_main())
-                                    .font(style.fonts.textedit_small()),
+                                    .font(style.fonts.textedit_small())
+                                    .layouter(&mut layouter),
                             );
 
                             ComboBox::from_id_salt(format!("form-data-field-type-{i}"))
@@ -691,7 +2049,8 @@ impl RequestDetailsPanel {
                                 TextEdit::singleline(&mut items[i].value)
                                     .desired_width(ui.available_width() - 25.)
                                     .text_color(style.color_main())
-                                    .font(style.fonts.textedit_small()),
+                                    .font(style.fonts.textedit_small())
+                                    .layouter(&mut layouter),
                             );
 
                             if header_key_resp.changed() || header_value_reasp.changed() {
@@ -774,14 +2133,24 @@ impl RequestDetailsPanel {
     /// Draw Form Data Table.
     /// If no new_value provided - all data is read only
     /// Return is_changed state - Some() - heave changes, None - no changes.
-    fn update_generic_headers_table(
+    pub fn update_generic_headers_table(
         &self,
         ui: &mut Ui,
         items: &mut Vec<Header>,
         new_value: Option<&mut Header>,
+        variables: &[Header],
         style: &Style,
     ) -> Option<()> {
         let mut is_changes = None;
+        let highlight = VariablesHighlight::new(
+            variables,
+            style,
+            style.fonts.textedit_small(),
+            style.color_main(),
+        );
+        let mut layouter = |ui: &Ui, text: &dyn TextBuffer, wrap_width: f32| {
+            highlight.layout(ui, text, wrap_width)
+        };
         ScrollArea::vertical().show(ui, |ui| {
             ui.style_mut().spacing.item_spacing = vec2(2., 2.);
             ui.style_mut().visuals.widgets.active.corner_radius = CornerRadius::ZERO;
@@ -799,13 +2168,15 @@ impl RequestDetailsPanel {
                             let header_key_resp = ui.add(
                                 TextEdit::singleline(&mut items[i].key)
                                     .text_color(style.color_main())
-                                    .font(style.fonts.textedit_small()),
+                                    .font(style.fonts.textedit_small())
+                                    .layouter(&mut layouter),
                             );
                             let header_value_reasp = ui.add(
                                 TextEdit::singleline(&mut items[i].value)
                                     .desired_width(ui.available_width() - 25.)
                                     .text_color(style.color_main())
-                                    .font(style.fonts.textedit_small()),
+                                    .font(style.fonts.textedit_small())
+                                    .layouter(&mut layouter),
                             );
 
                             if header_key_resp.changed() || header_value_reasp.changed() {
@@ -863,6 +2234,13 @@ impl RequestDetailsPanel {
 
     /// Draw headers Table
     fn update_headers(&self, ui: &mut Ui, states: &mut States) {
+        let parent = states
+            .main_page
+            .selected_collection()
+            .map(|collection| collection.draft.clone());
+        let variables = states.variables();
+        let parent_auth = parent.as_ref().map(|collection| &collection.auth);
+
         let request = states.main_page.selected_request_mut();
 
         if request.is_none() {
@@ -871,7 +2249,7 @@ impl RequestDetailsPanel {
 
         let request = request.unwrap();
 
-        if [Protocol::WS, Protocol::WSS].contains(&request.draft.protocol) {
+        if request.draft.protocot_is_ws() || request.draft.protocot_is_http() {
             ui.group(|ui| {
                 ui.horizontal(|ui| {
                     if ui
@@ -904,12 +2282,15 @@ impl RequestDetailsPanel {
             });
         };
 
-        if request.visible_headers == RequestHeaders::Custom {
+        if request.visible_headers == RequestHeaders::Custom
+            || !(request.draft.protocot_is_ws() || request.draft.protocot_is_http())
+        {
             if self
                 .update_generic_headers_table(
                     ui,
                     &mut request.draft.headers,
                     Some(&mut request.new_header),
+                    &variables,
                     &states.style,
                 )
                 .is_some()
@@ -917,13 +2298,71 @@ impl RequestDetailsPanel {
                 request.is_changed = true
             };
         } else {
-            self.update_generic_headers_table(ui, &mut default_ws_headers(), None, &states.style);
+            // Computed on send, cant be changed here
+            let mut default_headers = if request.draft.protocot_is_ws() {
+                default_ws_headers()
+            } else {
+                vec![]
+            };
+            match &parent {
+                Some(collection) => {
+                    default_headers.extend(collection.merge_headers(&request.draft.headers))
+                }
+                None => default_headers.extend(request.draft.headers.iter().cloned()),
+            }
+            default_headers.extend(request.draft.auth.resolve(parent_auth).headers());
+
+            self.update_generic_headers_table(
+                ui,
+                &mut default_headers,
+                None,
+                &variables,
+                &states.style,
+            );
+        }
+
+        // Tokens of custom, collection and auth headers, like Bearer
+        let mut headers = match &parent {
+            Some(collection) => collection.merge_headers(&request.draft.headers),
+            None => request.draft.headers.clone(),
+        };
+        headers.extend(request.draft.auth.resolve(parent_auth).headers());
+        let mut substitution = Variables::new(&variables);
+        for header in headers.iter_mut() {
+            header.value = substitution.substitute(&header.value);
+        }
+        jwt::sync(&mut request.header_jwts, Jwt::find_in_headers(&headers));
+
+        if !request.header_jwts.is_empty() {
+            ui.add_space(10.);
+            CollapsingHeader::new(format!("JWT ({})", request.header_jwts.len()))
+                .id_salt(format!("request-jwts-{}", request.id))
+                .show(ui, |ui| {
+                    for jwt in request.header_jwts.iter_mut() {
+                        self.jwt_view.update(ui, jwt, &states.style);
+                        ui.add_space(10.);
+                    }
+                });
         }
     }
 
     /// Draw URL group
     fn update_url(&self, ui: &mut Ui, states: &mut States) {
         let id_salt = states.main_page.selected_request_salt();
+        let parent = states
+            .main_page
+            .selected_collection()
+            .map(|collection| collection.draft.clone());
+        let variables = states.variables();
+        let highlight = VariablesHighlight::new(
+            &variables,
+            &states.style,
+            states.style.fonts.textedit_big(),
+            states.style.color_lighter(),
+        );
+        let mut layouter = |ui: &Ui, text: &dyn TextBuffer, wrap_width: f32| {
+            highlight.layout(ui, text, wrap_width)
+        };
 
         let request = states.main_page.selected_request_mut();
         if request.is_none() {
@@ -1022,6 +2461,31 @@ impl RequestDetailsPanel {
                                     Protocol::WSS,
                                     "WSS",
                                 );
+                                let protocol_grpc_resp = ui.selectable_value(
+                                    &mut request.draft.protocol,
+                                    Protocol::GRPC,
+                                    "GRPC",
+                                );
+                                let protocol_grpcs_resp = ui.selectable_value(
+                                    &mut request.draft.protocol,
+                                    Protocol::GRPCS,
+                                    "GRPCS",
+                                );
+                                let protocol_mqtt_resp = ui.selectable_value(
+                                    &mut request.draft.protocol,
+                                    Protocol::MQTT,
+                                    "MQTT",
+                                );
+                                let protocol_mqtts_resp = ui.selectable_value(
+                                    &mut request.draft.protocol,
+                                    Protocol::MQTTS,
+                                    "MQTTS",
+                                );
+                                let protocol_udp_resp = ui.selectable_value(
+                                    &mut request.draft.protocol,
+                                    Protocol::UDP,
+                                    "UDP",
+                                );
 
                                 if protocol_https_resp.changed() || protocol_http_resp.changed() {
                                     request.draft.setup = RequestSetup::default()
@@ -1031,10 +2495,27 @@ impl RequestDetailsPanel {
                                     request.draft.setup = RequestSetup::default_ws()
                                 }
 
+                                if protocol_grpc_resp.changed() || protocol_grpcs_resp.changed() {
+                                    request.draft.setup = RequestSetup::default_grpc()
+                                }
+
+                                if protocol_mqtt_resp.changed() || protocol_mqtts_resp.changed() {
+                                    request.draft.setup = RequestSetup::default_mqtt()
+                                }
+
+                                if protocol_udp_resp.changed() {
+                                    request.draft.setup = RequestSetup::default_udp()
+                                }
+
                                 if protocol_http_resp.changed()
                                     || protocol_https_resp.changed()
                                     || protocol_ws_resp.changed()
                                     || protocol_wss_resp.changed()
+                                    || protocol_grpc_resp.changed()
+                                    || protocol_grpcs_resp.changed()
+                                    || protocol_mqtt_resp.changed()
+                                    || protocol_mqtts_resp.changed()
+                                    || protocol_udp_resp.changed()
                                 {
                                     request.is_changed = true;
                                 }
@@ -1064,7 +2545,8 @@ impl RequestDetailsPanel {
                                 .desired_width(ui.available_width() - 45.)
                                 .text_color(states.style.color_lighter())
                                 .background_color(states.style.color_light())
-                                .font(states.style.fonts.textedit_big()),
+                                .font(states.style.fonts.textedit_big())
+                                .layouter(&mut layouter),
                         );
 
                         if request_url_resp.changed() {
@@ -1089,7 +2571,12 @@ impl RequestDetailsPanel {
                             states.style.color_danger()
                         }));
                     if execute_request_btn_resp.clicked() && executos_is_free {
-                        request.go(Arc::clone(&states.events), true);
+                        request.go(
+                            Arc::clone(&states.events),
+                            true,
+                            parent.as_ref(),
+                            &variables,
+                        );
                     } else if execute_request_btn_resp.clicked() && !executos_is_free {
                         request.termiate();
                     };
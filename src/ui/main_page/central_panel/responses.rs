@@ -8,22 +8,27 @@ use egui::{
 use crate::{
     settings::main_settings::entity::request_settings::protocol_settings::Protocol,
     states::{
-        main_page::response::{Response, ResponseView},
+        main_page::response::{close_code_description, Response, ResponseView, ResponsesFilter},
         States, Style,
     },
-    ui::{icons::Icon, main_page::central_panel::responses::json_view::JsonView},
+    ui::{
+        icons::Icon,
+        main_page::central_panel::{jwt_view::JwtView, responses::json_view::JsonView},
+    },
 };
 
 mod json_view;
 
 pub struct ResponsesListPanel {
     json_view: JsonView,
+    jwt_view: JwtView,
 }
 
 impl ResponsesListPanel {
     pub fn new() -> Self {
         Self {
             json_view: JsonView::new(),
+            jwt_view: JwtView::new(),
         }
     }
 
@@ -58,6 +63,26 @@ impl ResponsesListPanel {
                                 if ui.button("Clear all").clicked() && responses.len() > 0 {
                                     responses.clear();
                                 }
+
+                                // Server pushed messages could be viewed separately from replies
+                                if responses.iter().any(|response| response.is_notification) {
+                                    ui.separator();
+                                    ui.radio_value(
+                                        &mut request.visible_responses,
+                                        ResponsesFilter::All,
+                                        "All",
+                                    );
+                                    ui.radio_value(
+                                        &mut request.visible_responses,
+                                        ResponsesFilter::Replies,
+                                        "Replies",
+                                    );
+                                    ui.radio_value(
+                                        &mut request.visible_responses,
+                                        ResponsesFilter::Notifications,
+                                        "Notifications",
+                                    );
+                                }
                             })
                         });
                     });
@@ -67,6 +92,9 @@ impl ResponsesListPanel {
                         .show(ui, |ui| {
                             Frame::new().inner_margin(Margin::same(10)).show(ui, |ui| {
                                 for i in (0..responses.len()).rev() {
+                                    if !request.visible_responses.is_visible(&responses[i]) {
+                                        continue;
+                                    }
                                     self.update_response(
                                         ui,
                                         &mut responses[i],
@@ -116,6 +144,12 @@ impl ResponsesListPanel {
                 ResponseView::HEADERS,
                 "Headers",
             );
+            if !response.data.jwts.is_empty() {
+                ui.radio_value(&mut response.selected_view, ResponseView::JWT, "JWT");
+            }
+            if !response.assertions.is_empty() {
+                ui.radio_value(&mut response.selected_view, ResponseView::TESTS, "Tests");
+            }
         });
         match response.selected_view {
             ResponseView::JSON => {
@@ -178,6 +212,43 @@ impl ResponsesListPanel {
                             })
                     });
             }
+            ResponseView::JWT => {
+                for jwt in response.data.jwts.iter_mut() {
+                    self.jwt_view.update(ui, jwt, style);
+                    ui.add_space(10.);
+                }
+            }
+            ResponseView::TESTS => {
+                Frame::new()
+                    .inner_margin(Margin::same(5))
+                    .fill(style.color_main())
+                    .show(ui, |ui| {
+                        Grid::new(format!("response-tests-{}", response.time))
+                            .num_columns(3)
+                            .spacing(vec2(10., 5.))
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for result in &response.assertions {
+                                    if result.is_passed {
+                                        ui.add(Label::new(
+                                            RichText::new("PASS").color(Color32::GREEN).strong(),
+                                        ));
+                                    } else {
+                                        ui.add(Label::new(
+                                            RichText::new("FAIL")
+                                                .color(style.color_danger())
+                                                .strong(),
+                                        ));
+                                    }
+                                    ui.add(Label::new(&result.name).wrap_mode(TextWrapMode::Wrap));
+                                    ui.add(
+                                        Label::new(&result.message).wrap_mode(TextWrapMode::Wrap),
+                                    );
+                                    ui.end_row();
+                                }
+                            })
+                    });
+            }
         };
     }
 
@@ -245,6 +316,60 @@ impl ResponsesListPanel {
                     ..Default::default()
                 },
             );
+
+            let (passed, total) = response.assertions_passed();
+            if total > 0 {
+                let (badge_color, badge_text) = if passed == total {
+                    (Color32::GREEN, format!("✔ {passed}/{total} PASSED"))
+                } else {
+                    (Color32::RED, format!("✘ {}/{total} FAILED", total - passed))
+                };
+
+                job.append(
+                    &badge_text,
+                    15.0,
+                    TextFormat {
+                        color: badge_color,
+                        font_id: FontId::new(14.0, FontFamily::Monospace),
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+
+        if let Some(event) = &response.event {
+            job.append(
+                event,
+                15.0,
+                TextFormat {
+                    color: Color32::LIGHT_BLUE,
+                    font_id: FontId::new(14.0, FontFamily::Monospace),
+                    ..Default::default()
+                },
+            );
+        }
+
+        if let Some(close_code) = response.close_code {
+            // Normal closures are fine, everything else most likely protocol violation
+            let close_color = if close_code == 1000 || close_code == 1001 {
+                Color32::GREEN
+            } else {
+                Color32::RED
+            };
+
+            job.append(
+                &format!(
+                    "CLOSE {} {}",
+                    close_code,
+                    close_code_description(close_code).to_uppercase()
+                ),
+                15.0,
+                TextFormat {
+                    color: close_color,
+                    font_id: FontId::new(14.0, FontFamily::Monospace),
+                    ..Default::default()
+                },
+            );
         }
 
         job
@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use egui::{text::LayoutJob, Color32, FontSelection, Galley, Stroke, TextBuffer, TextFormat, Ui};
+
+use crate::{
+    executor::variables::{self, Variables},
+    states::{main_page::generics::Header, Style},
+};
+
+/// Layouter for text edits: {{variable}} placeholders colored as success when
+/// active environment has them, and underlined as danger otherwise
+pub struct VariablesHighlight<'a> {
+    variables: &'a [Header],
+    style: &'a Style,
+    font: FontSelection,
+    color: Color32,
+    multiline: bool,
+}
+
+impl<'a> VariablesHighlight<'a> {
+    pub fn new(
+        variables: &'a [Header],
+        style: &'a Style,
+        font: FontSelection,
+        color: Color32,
+    ) -> Self {
+        Self {
+            variables,
+            style,
+            font,
+            color,
+            multiline: false,
+        }
+    }
+
+    pub fn multiline(mut self) -> Self {
+        self.multiline = true;
+        self
+    }
+
+    pub fn layout(&self, ui: &Ui, text: &dyn TextBuffer, wrap_width: f32) -> Arc<Galley> {
+        let text = text.as_str();
+        // FontSelection is not Clone
+        let font_id = match &self.font {
+            FontSelection::Default => FontSelection::Default,
+            FontSelection::FontId(font_id) => FontSelection::FontId(font_id.clone()),
+            FontSelection::Style(text_style) => FontSelection::Style(text_style.clone()),
+        }
+        .resolve(ui.style());
+        let lookup = Variables::new(self.variables);
+
+        let mut job = LayoutJob::default();
+        let mut last = 0;
+        for (range, name) in variables::placeholders(text) {
+            job.append(
+                &text[last..range.start],
+                0.,
+                TextFormat::simple(font_id.clone(), self.color),
+            );
+
+            let format = match lookup.is_known(&name) {
+                true => TextFormat::simple(font_id.clone(), self.style.color_success()),
+                false => TextFormat {
+                    underline: Stroke::new(1., self.style.color_danger()),
+                    ..TextFormat::simple(font_id.clone(), self.style.color_danger())
+                },
+            };
+            job.append(&text[range.clone()], 0., format);
+            last = range.end;
+        }
+        job.append(
+            &text[last..],
+            0.,
+            TextFormat::simple(font_id.clone(), self.color),
+        );
+
+        if self.multiline {
+            job.wrap.max_width = wrap_width;
+        } else {
+            job.wrap.max_width = f32::INFINITY;
+            job.break_on_newline = false;
+        }
+        ui.fonts(|fonts| fonts.layout_job(job))
+    }
+}
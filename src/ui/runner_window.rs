@@ -0,0 +1,232 @@
+use std::time::Duration;
+
+use egui::{
+    vec2, Color32, Context, CornerRadius, Frame, Grid, Label, Margin, RichText, ScrollArea,
+    TextEdit, Window,
+};
+
+use crate::states::States;
+
+/// Collection runner: selection of requests, run options and report of last run
+pub struct RunnerWindow {}
+
+impl RunnerWindow {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn update(&mut self, ctx: &Context, states: &mut States) {
+        let mut is_visible = states.runner.is_visible;
+
+        Window::new(format!("Run {}", states.runner.collection_name))
+            .id("collection-runner".into())
+            .collapsible(false)
+            .resizable(true)
+            .default_size(vec2(600., 500.))
+            .open(&mut is_visible)
+            .show(ctx, |ui| {
+                Frame::default()
+                    .fill(states.style.color_main())
+                    .inner_margin(Margin::same(10))
+                    .corner_radius(CornerRadius::same(5))
+                    .show(ui, |ui| {
+                        ui.set_width(ui.available_width());
+
+                        ScrollArea::vertical().show(ui, |ui| {
+                            self.update_steps(ui, states);
+                            ui.add_space(10.);
+                            self.update_controls(ui, states);
+                            ui.add_space(10.);
+                            ui.separator();
+                            self.update_report(ui, states);
+                        });
+                    });
+            });
+
+        states.runner.is_visible = is_visible;
+
+        // Run advances on frames, so they must go on without user input
+        if states.runner.is_running() {
+            ctx.request_repaint_after(Duration::from_millis(50));
+        }
+    }
+
+    /// Requests in run order, with selection
+    fn update_steps(&self, ui: &mut egui::Ui, states: &mut States) {
+        ui.horizontal(|ui| {
+            ui.add(Label::new(states.style.fonts.label_text("Requests:")));
+            if !states.runner.is_running() {
+                if ui.button("All").clicked() {
+                    for step in states.runner.steps.iter_mut() {
+                        step.is_selected = step.is_runnable;
+                    }
+                }
+                if ui.button("None").clicked() {
+                    for step in states.runner.steps.iter_mut() {
+                        step.is_selected = false;
+                    }
+                }
+            }
+        });
+        ui.add_space(5.);
+
+        if states.runner.steps.is_empty() {
+            ui.add(Label::new("Collection has no requests"));
+            return;
+        }
+
+        let current = states.runner.current_step();
+        let is_running = states.runner.is_running();
+        for (i, step) in states.runner.steps.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add_enabled_ui(step.is_runnable && !is_running, |ui| {
+                    ui.checkbox(&mut step.is_selected, &step.name)
+                })
+                .response
+                .on_disabled_hover_text(if step.is_runnable {
+                    "Run in progress"
+                } else {
+                    "Only HTTP requests could be run"
+                });
+                if current == Some(i) {
+                    ui.spinner();
+                }
+            });
+        }
+    }
+
+    fn update_controls(&self, ui: &mut egui::Ui, states: &mut States) {
+        ui.horizontal(|ui| {
+            ui.style_mut().visuals.extreme_bg_color = states.style.color_secondary();
+
+            ui.add(Label::new("Delay, ms:"));
+            ui.add(
+                TextEdit::singleline(&mut states.runner.delay)
+                    .hint_text("0")
+                    .desired_width(60.),
+            );
+            ui.add_space(10.);
+            ui.checkbox(&mut states.runner.stop_on_failure, "Stop on failure");
+            ui.add_space(10.);
+
+            if states.runner.is_running() {
+                if ui.button("Stop").clicked() {
+                    states.runner.stop();
+                    states.event_warning(&"Runner: stopped".into());
+                }
+            } else if ui.button("Run").clicked() {
+                states.runner.start(&states.events);
+            }
+        });
+    }
+
+    /// Result per request, with link to its response
+    fn update_report(&self, ui: &mut egui::Ui, states: &mut States) {
+        let (passed, total) = states.runner.passed();
+        ui.horizontal(|ui| {
+            ui.add(Label::new(states.style.fonts.label_text("Report:")));
+            if total > 0 {
+                let color = if passed == total {
+                    Color32::GREEN
+                } else {
+                    states.style.color_danger()
+                };
+                ui.add(Label::new(
+                    RichText::new(format!("passed {passed} of {total}"))
+                        .color(color)
+                        .strong(),
+                ));
+                let duration: Duration = states
+                    .runner
+                    .report
+                    .iter()
+                    .filter_map(|report| report.duration)
+                    .sum();
+                ui.add(Label::new(format!("in {} ms", duration.as_millis())));
+            }
+        });
+        ui.add_space(5.);
+
+        if states.runner.report.is_empty() {
+            ui.add(Label::new("Not run yet"));
+            return;
+        }
+
+        let mut show = None;
+        Grid::new("runner-report")
+            .num_columns(6)
+            .spacing(vec2(10., 5.))
+            .striped(true)
+            .show(ui, |ui| {
+                for (i, report) in states.runner.report.iter().enumerate() {
+                    if report.is_passed {
+                        ui.add(Label::new(
+                            RichText::new("PASS").color(Color32::GREEN).strong(),
+                        ));
+                    } else {
+                        ui.add(Label::new(
+                            RichText::new("FAIL")
+                                .color(states.style.color_danger())
+                                .strong(),
+                        ));
+                    }
+                    ui.add(Label::new(&report.name));
+                    ui.add(Label::new(if report.code > 0 {
+                        report.code.to_string()
+                    } else {
+                        "-".into()
+                    }));
+                    ui.add(Label::new(match report.duration {
+                        Some(duration) => format!("{} ms", duration.as_millis()),
+                        None => "-".into(),
+                    }));
+
+                    // Assertions on hover, error instead when not sent
+                    match &report.error {
+                        Some(error) => {
+                            ui.add(Label::new(
+                                RichText::new(error).color(states.style.color_danger()),
+                            ));
+                        }
+                        None if report.assertions.is_empty() => {
+                            ui.add(Label::new("No assertions"));
+                        }
+                        None => {
+                            let failed = report
+                                .assertions
+                                .iter()
+                                .filter(|result| !result.is_passed)
+                                .count();
+                            let details = report
+                                .assertions
+                                .iter()
+                                .map(|result| {
+                                    format!(
+                                        "{} {} {}",
+                                        if result.is_passed { "PASS" } else { "FAIL" },
+                                        result.name,
+                                        result.message
+                                    )
+                                })
+                                .collect::<Vec<String>>()
+                                .join("\n");
+                            ui.add(Label::new(format!(
+                                "Assertions: {} passed, {failed} failed",
+                                report.assertions.len() - failed
+                            )))
+                            .on_hover_text(details);
+                        }
+                    }
+
+                    if report.response_idx.is_some() && ui.link("Show response").clicked() {
+                        show = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        if let Some(i) = show {
+            states.runner.show_response(i, &mut states.main_page);
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use egui::{vec2, Context, CornerRadius, Frame, Grid, Label, Margin, RichText, ScrollArea, Window};
+
+use crate::{
+    executor::variables,
+    states::{main_page::request::request_data::CaptureScope, States},
+};
+
+/// Read only view of variables resolved for selected entity and values captured from responses
+pub struct VariablesInspector {
+    pub is_visible: bool,
+}
+
+impl VariablesInspector {
+    pub fn new() -> Self {
+        Self { is_visible: false }
+    }
+
+    pub fn update(&mut self, ctx: &Context, states: &mut States) {
+        let mut is_visible = self.is_visible;
+
+        Window::new("Variables Inspector")
+            .collapsible(false)
+            .resizable(true)
+            .default_size(vec2(600., 400.))
+            .open(&mut is_visible)
+            .show(ctx, |ui| {
+                Frame::default()
+                    .fill(states.style.color_main())
+                    .inner_margin(Margin::same(10))
+                    .corner_radius(CornerRadius::same(5))
+                    .show(ui, |ui| {
+                        ui.set_width(ui.available_width());
+
+                        ScrollArea::vertical().show(ui, |ui| {
+                            self.update_variables(ui, states);
+                            ui.add_space(10.);
+                            ui.separator();
+                            self.update_captured(ui, states);
+                        });
+                    });
+            });
+
+        self.is_visible = is_visible;
+    }
+
+    /// Variables as resolved on send: collection ones override environment ones
+    fn update_variables(&self, ui: &mut egui::Ui, states: &States) {
+        ui.add(Label::new(states.style.fonts.label_text("Variables:")));
+        ui.add_space(5.);
+
+        // (source, name, value)
+        let mut rows = vec![];
+        if let Some(collection) = states.main_page.selected_collection() {
+            for variable in collection.draft.variables.iter() {
+                if !variable.key.trim().is_empty() {
+                    rows.push((
+                        format!("Collection {}", collection.draft.name),
+                        variable.key.trim().to_string(),
+                        variable.value.clone(),
+                    ));
+                }
+            }
+        }
+        let environment = states.environments.active_name();
+        for variable in states.environments.variables() {
+            rows.push((
+                format!("Environment {environment}"),
+                variable.key.trim().to_string(),
+                variable.value,
+            ));
+        }
+        // Values never shown
+        if states.vault.is_unlocked() {
+            for secret in states.environments.secrets() {
+                rows.push((
+                    format!("Secret {environment}"),
+                    secret.key.trim().to_string(),
+                    "••••••".into(),
+                ));
+            }
+        }
+
+        if rows.is_empty() {
+            ui.add(Label::new("No variables for selected entity"));
+        } else {
+            Grid::new("variables-inspector")
+                .num_columns(3)
+                .spacing(vec2(10., 5.))
+                .striped(true)
+                .show(ui, |ui| {
+                    for (i, (source, name, value)) in rows.iter().enumerate() {
+                        let is_overridden = rows[..i].iter().any(|row| &row.1 == name);
+
+                        ui.add(Label::new(source));
+                        ui.add(Label::new(format!("{{{{{name}}}}}")));
+                        if is_overridden {
+                            ui.add(Label::new(
+                                RichText::new(format!("{value} (overridden)")).weak(),
+                            ));
+                        } else {
+                            ui.add(Label::new(RichText::new(value).strong()));
+                        }
+                        ui.end_row();
+                    }
+                });
+        }
+
+        ui.add_space(5.);
+        ui.add(Label::new(format!(
+            "Built-in variables:\n{}",
+            variables::BUILT_IN
+        )));
+    }
+
+    /// Captured values, newest first
+    fn update_captured(&self, ui: &mut egui::Ui, states: &mut States) {
+        ui.horizontal(|ui| {
+            ui.add(Label::new(states.style.fonts.label_text("Captured:")));
+            if !states.environments.captured.is_empty() && ui.button("Clear").clicked() {
+                states.environments.captured.clear();
+            }
+        });
+        ui.add_space(5.);
+
+        if states.environments.captured.is_empty() {
+            ui.add(Label::new("Nothing captured yet"));
+            return;
+        }
+
+        let secrets = states.environments.secrets();
+
+        Grid::new("captured-inspector")
+            .num_columns(5)
+            .spacing(vec2(10., 5.))
+            .striped(true)
+            .show(ui, |ui| {
+                for record in states.environments.captured.iter().rev() {
+                    ui.add(Label::new(
+                        record.captured.time.format("%H:%M:%S").to_string(),
+                    ));
+                    ui.add(Label::new(&record.request));
+                    ui.add(Label::new(format!("{{{{{}}}}}", record.captured.variable)));
+                    let is_secret = record.captured.scope == CaptureScope::ENVIRONMENT
+                        && secrets
+                            .iter()
+                            .any(|secret| secret.key.trim() == record.captured.variable);
+                    if is_secret {
+                        ui.add(Label::new("••••••"));
+                    } else {
+                        ui.add(Label::new(RichText::new(&record.captured.value).strong()));
+                    }
+                    match &record.target {
+                        Some(target) => {
+                            ui.add(Label::new(format!("{} {target}", record.captured.scope)))
+                        }
+                        None => ui.add(Label::new(
+                            RichText::new("Not stored").color(states.style.color_danger()),
+                        )),
+                    };
+                    ui.end_row();
+                }
+            });
+    }
+}